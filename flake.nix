@@ -134,6 +134,9 @@
               glib-networking
               libsoup_3
               alsa-lib
+              libopus
+              dbus
+              libpulseaudio
               onnxruntime
               libayatana-appindicator
               libevdev
@@ -222,6 +225,9 @@
               pkg-config
               openssl
               alsa-lib
+              libopus
+              dbus
+              libpulseaudio
               libsoup_3
               webkitgtk_4_1
               gtk3
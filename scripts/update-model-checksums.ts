@@ -0,0 +1,160 @@
+import { execFileSync } from "child_process";
+import { createHash, Hash } from "crypto";
+import fs from "fs";
+import os from "os";
+import path from "path";
+import { fileURLToPath } from "url";
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+
+// Configuration
+const MANIFEST_PATH = path.join(
+  __dirname,
+  "..",
+  "src-tauri",
+  "resources",
+  "model_manifest.json",
+);
+
+interface ManifestModel {
+  id: string;
+  filename: string;
+  url: string | null;
+  is_directory: boolean;
+  sha256?: string | null;
+}
+
+interface Manifest {
+  revision: number;
+  models: ManifestModel[];
+}
+
+// Must match `model_integrity::sha256_file` in the app
+function hashFileInto(hash: Hash, filePath: string): void {
+  const fd = fs.openSync(filePath, "r");
+  const buffer = Buffer.alloc(1 << 16);
+  try {
+    let read: number;
+    while ((read = fs.readSync(fd, buffer, 0, buffer.length, null)) > 0) {
+      hash.update(buffer.subarray(0, read));
+    }
+  } finally {
+    fs.closeSync(fd);
+  }
+}
+
+function collectFiles(root: string, dir: string, out: string[][]): void {
+  for (const entry of fs.readdirSync(dir, { withFileTypes: true })) {
+    const entryPath = path.join(dir, entry.name);
+    if (entry.isDirectory()) {
+      collectFiles(root, entryPath, out);
+    } else if (entry.isFile()) {
+      out.push(path.relative(root, entryPath).split(path.sep));
+    }
+  }
+}
+
+// Rust orders paths component by component, not as joined strings
+function compareComponents(a: string[], b: string[]): number {
+  for (let i = 0; i < Math.min(a.length, b.length); i++) {
+    const order = Buffer.compare(Buffer.from(a[i]), Buffer.from(b[i]));
+    if (order !== 0) return order;
+  }
+  return a.length - b.length;
+}
+
+// Must match `model_integrity::sha256_dir` in the app
+function hashDir(root: string): string {
+  const files: string[][] = [];
+  collectFiles(root, root, files);
+  files.sort(compareComponents);
+
+  const hash = createHash("sha256");
+  for (const components of files) {
+    const filePath = path.join(root, ...components);
+    const size = Buffer.alloc(8);
+    size.writeBigUInt64LE(BigInt(fs.statSync(filePath).size));
+    hash.update(components.join("/"));
+    hash.update(Buffer.from([0]));
+    hash.update(size);
+    hashFileInto(hash, filePath);
+  }
+  return hash.digest("hex");
+}
+
+// Must match `extracted_model_root` in the app
+function extractedModelRoot(extractDir: string): string {
+  const dirs = fs
+    .readdirSync(extractDir, { withFileTypes: true })
+    .filter((entry) => entry.isDirectory());
+  return dirs.length === 1 ? path.join(extractDir, dirs[0].name) : extractDir;
+}
+
+async function download(url: string, dest: string): Promise<void> {
+  const response = await fetch(url);
+  if (!response.ok || !response.body) {
+    throw new Error(`HTTP ${response.status} for ${url}`);
+  }
+  const out = fs.createWriteStream(dest);
+  for await (const chunk of response.body) {
+    out.write(chunk);
+  }
+  await new Promise<void>((resolve, reject) => {
+    out.on("error", reject);
+    out.end(resolve);
+  });
+}
+
+async function checksum(model: ManifestModel, workDir: string): Promise<string> {
+  const payload = path.join(workDir, `${model.filename}.partial`);
+  await download(model.url!, payload);
+
+  if (!model.is_directory) {
+    const hash = createHash("sha256");
+    hashFileInto(hash, payload);
+    return hash.digest("hex");
+  }
+
+  const extractDir = path.join(workDir, `${model.filename}.extracting`);
+  fs.mkdirSync(extractDir);
+  execFileSync("tar", ["-xzf", payload, "-C", extractDir]);
+  return hashDir(extractedModelRoot(extractDir));
+}
+
+async function main(): Promise<void> {
+  const onlyMissing = process.argv.includes("--missing");
+  const manifest = JSON.parse(
+    fs.readFileSync(MANIFEST_PATH, "utf8"),
+  ) as Manifest;
+
+  let changed = false;
+  for (const model of manifest.models) {
+    if (!model.url || (onlyMissing && model.sha256)) continue;
+
+    const workDir = fs.mkdtempSync(path.join(os.tmpdir(), "model-checksum-"));
+    try {
+      console.log(`Hashing ${model.id} from ${model.url}`);
+      const digest = await checksum(model, workDir);
+      if (model.sha256 !== digest) {
+        console.log(`  ${model.sha256 ?? "(none)"} -> ${digest}`);
+        model.sha256 = digest;
+        changed = true;
+      }
+    } finally {
+      fs.rmSync(workDir, { recursive: true, force: true });
+    }
+  }
+
+  if (changed) {
+    manifest.revision += 1;
+    fs.writeFileSync(MANIFEST_PATH, JSON.stringify(manifest, null, 2) + "\n");
+    console.log(`Updated manifest to revision ${manifest.revision}`);
+  } else {
+    console.log("All checksums are up to date");
+  }
+}
+
+main().catch((error) => {
+  console.error((error as Error).message);
+  process.exit(1);
+});
@@ -2,9 +2,12 @@
 use crate::apple_intelligence;
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
 use crate::managers::audio::AudioRecordingManager;
-use crate::managers::history::HistoryManager;
+use crate::managers::history::{HistoryManager, TranscriptionMetrics};
 use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID};
+use crate::output;
+use crate::settings::{
+    get_settings, is_custom_binding, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID,
+};
 use crate::shortcut;
 use crate::tray::{change_tray_icon, TrayIconState};
 use crate::utils::{
@@ -17,6 +20,7 @@ use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
+use tauri::async_runtime::JoinHandle;
 use tauri::AppHandle;
 use tauri::{Emitter, Manager};
 
@@ -31,6 +35,22 @@ impl Drop for FinishGuard {
     }
 }
 
+/// Record where a transcript went once its history entry has been saved.
+fn record_output_when_saved(
+    hm: Arc<HistoryManager>,
+    saved: JoinHandle<Option<i64>>,
+    target: &'static str,
+    error: Option<String>,
+) {
+    tauri::async_runtime::spawn(async move {
+        if let Ok(Some(id)) = saved.await {
+            if let Err(e) = hm.record_output(id, target, error.as_deref()) {
+                error!("Failed to record output result: {}", e);
+            }
+        }
+    });
+}
+
 // Shortcut Action Trait
 pub trait ShortcutAction: Send + Sync {
     fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str);
@@ -226,9 +246,10 @@ impl ShortcutAction for TranscribeAction {
         let start_time = Instant::now();
         debug!("TranscribeAction::start called for binding: {}", binding_id);
 
-        // Load model in the background
+        // Load (or switch to) this binding's model in the background
+        let settings = get_settings(app);
         let tm = app.state::<Arc<TranscriptionManager>>();
-        tm.initiate_model_load();
+        tm.initiate_model_load(&settings.for_binding(binding_id).selected_model);
 
         let binding_id = binding_id.to_string();
         change_tray_icon(app, TrayIconState::Recording);
@@ -237,27 +258,26 @@ impl ShortcutAction for TranscribeAction {
         let rm = app.state::<Arc<AudioRecordingManager>>();
 
         // Get the microphone mode to determine audio feedback timing
-        let settings = get_settings(app);
         let is_always_on = settings.always_on_microphone;
         debug!("Microphone mode - always_on: {}", is_always_on);
 
         let mut recording_started = false;
         if is_always_on {
-            // Always-on mode: Play audio feedback immediately, then apply mute after sound finishes
+            // Always-on mode: Play audio feedback immediately, then duck other audio after sound finishes
             debug!("Always-on mode: Playing audio feedback immediately");
             let rm_clone = Arc::clone(&rm);
             let app_clone = app.clone();
             // The blocking helper exits immediately if audio feedback is disabled,
-            // so we can always reuse this thread to ensure mute happens right after playback.
+            // so we can always reuse this thread to ensure ducking happens right after playback.
             std::thread::spawn(move || {
                 play_feedback_sound_blocking(&app_clone, SoundType::Start);
-                rm_clone.apply_mute();
+                rm_clone.apply_ducking();
             });
 
             recording_started = rm.try_start_recording(&binding_id);
             debug!("Recording started: {}", recording_started);
         } else {
-            // On-demand mode: Start recording first, then play audio feedback, then apply mute
+            // On-demand mode: Start recording first, then play audio feedback, then duck other audio
             // This allows the microphone to be activated before playing the sound
             debug!("On-demand mode: Starting recording first, then audio feedback");
             let recording_start_time = Instant::now();
@@ -269,11 +289,11 @@ impl ShortcutAction for TranscribeAction {
                 let rm_clone = Arc::clone(&rm);
                 std::thread::spawn(move || {
                     std::thread::sleep(std::time::Duration::from_millis(100));
-                    debug!("Handling delayed audio feedback/mute sequence");
+                    debug!("Handling delayed audio feedback/ducking sequence");
                     // Helper handles disabled audio feedback by returning early, so we reuse it
-                    // to keep mute sequencing consistent in every mode.
+                    // to keep ducking sequencing consistent in every mode.
                     play_feedback_sound_blocking(&app_clone, SoundType::Start);
-                    rm_clone.apply_mute();
+                    rm_clone.apply_ducking();
                 });
             } else {
                 debug!("Failed to start recording");
@@ -306,8 +326,8 @@ impl ShortcutAction for TranscribeAction {
         change_tray_icon(app, TrayIconState::Transcribing);
         show_transcribing_overlay(app);
 
-        // Unmute before playing audio feedback so the stop sound is audible
-        rm.remove_mute();
+        // Restore other audio before playing audio feedback so the stop sound is audible
+        rm.remove_ducking();
 
         // Play audio feedback for recording stop
         play_feedback_sound(app, SoundType::Stop);
@@ -331,17 +351,31 @@ impl ShortcutAction for TranscribeAction {
                     samples.len()
                 );
 
+                // The binding's own model, language, prompt and output
+                let settings = get_settings(&ah).for_binding(&binding_id);
+                let post_process = post_process || settings.binding_post_processes(&binding_id);
+                let output = settings.binding_output(&binding_id);
+
+                // Another binding may have switched models since recording
+                // started; make sure this binding's model is loaded
+                let loaded = tm.load_model(&settings.selected_model);
+
                 let transcription_time = Instant::now();
                 let samples_clone = samples.clone(); // Clone for history saving
-                match tm.transcribe(samples) {
+                match loaded
+                    .and_then(|_| tm.transcribe_with(&settings.selected_model, samples, &settings))
+                {
                     Ok(transcription) => {
+                        let transcription_elapsed = transcription_time.elapsed();
                         debug!(
                             "Transcription completed in {:?}: '{}'",
-                            transcription_time.elapsed(),
-                            transcription
+                            transcription_elapsed, transcription
                         );
+                        let metrics = TranscriptionMetrics {
+                            model_id: Some(settings.selected_model.clone()),
+                            transcription_ms: transcription_elapsed.as_millis() as u64,
+                        };
                         if !transcription.is_empty() {
-                            let settings = get_settings(&ah);
                             let mut final_text = transcription.clone();
                             let mut post_processed_text: Option<String> = None;
                             let mut post_process_prompt: Option<String> = None;
@@ -382,43 +416,91 @@ impl ShortcutAction for TranscribeAction {
                                 post_processed_text = Some(final_text.clone());
                             }
 
-                            // Save to history with post-processed text and prompt
+                            // Save to history with post-processed text and prompt,
+                            // then send the text to a non-desktop output target
                             let hm_clone = Arc::clone(&hm);
                             let transcription_for_history = transcription.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
+                            let ah_output = ah.clone();
+                            let output_target = output.clone();
+                            let output_text = final_text.clone();
+                            let output_binding = binding_id.clone();
+                            let saved = tauri::async_runtime::spawn(async move {
+                                let entry_id = match hm_clone
                                     .save_transcription(
                                         samples_clone,
                                         transcription_for_history,
                                         post_processed_text,
                                         post_process_prompt,
+                                        metrics,
                                     )
                                     .await
                                 {
-                                    error!("Failed to save transcription to history: {}", e);
+                                    Ok(id) => Some(id),
+                                    Err(e) => {
+                                        error!("Failed to save transcription to history: {}", e);
+                                        None
+                                    }
+                                };
+                                if output_target.is_desktop() {
+                                    // Recorded once the paste has run
+                                    return entry_id;
                                 }
-                            });
 
-                            // Paste the final text (either processed or original)
-                            let ah_clone = ah.clone();
-                            let paste_time = Instant::now();
-                            ah.run_on_main_thread(move || {
-                                match utils::paste(final_text, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => error!("Failed to paste transcription: {}", e),
+                                let name = output::target_name(&output_target);
+                                let result = output::deliver(
+                                    &ah_output,
+                                    &output_target,
+                                    &output_binding,
+                                    &output_text,
+                                )
+                                .await;
+                                match &result {
+                                    Ok(()) => debug!("Transcript sent to {} output", name),
+                                    Err(e) => {
+                                        error!("Failed to send transcript to {}: {}", name, e)
+                                    }
                                 }
-                                // Hide the overlay after transcription is complete
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                            })
-                            .unwrap_or_else(|e| {
-                                error!("Failed to run paste on main thread: {:?}", e);
+                                if let Some(id) = entry_id {
+                                    if let Err(e) =
+                                        hm_clone.record_output(id, name, result.err().as_deref())
+                                    {
+                                        error!("Failed to record output result: {}", e);
+                                    }
+                                }
+                                None
+                            });
+
+                            if !output.is_desktop() {
+                                // Delivered in the background once history is saved
                                 utils::hide_recording_overlay(&ah);
                                 change_tray_icon(&ah, TrayIconState::Idle);
-                            });
+                            } else {
+                                // Paste the final text (either processed or original)
+                                let ah_clone = ah.clone();
+                                let hm_paste = Arc::clone(&hm);
+                                let name = output::target_name(&output);
+                                let paste_time = Instant::now();
+                                ah.run_on_main_thread(move || {
+                                    let result =
+                                        utils::paste(final_text, ah_clone.clone(), &settings);
+                                    match &result {
+                                        Ok(()) => debug!(
+                                            "Text pasted successfully in {:?}",
+                                            paste_time.elapsed()
+                                        ),
+                                        Err(e) => error!("Failed to paste transcription: {}", e),
+                                    }
+                                    record_output_when_saved(hm_paste, saved, name, result.err());
+                                    // Hide the overlay after transcription is complete
+                                    utils::hide_recording_overlay(&ah_clone);
+                                    change_tray_icon(&ah_clone, TrayIconState::Idle);
+                                })
+                                .unwrap_or_else(|e| {
+                                    error!("Failed to run paste on main thread: {:?}", e);
+                                    utils::hide_recording_overlay(&ah);
+                                    change_tray_icon(&ah, TrayIconState::Idle);
+                                });
+                            }
                         } else {
                             let _ = ah.emit("transcription-empty", ());
                             utils::hide_recording_overlay(&ah);
@@ -458,6 +540,21 @@ impl ShortcutAction for CancelAction {
     }
 }
 
+// Hands-Free Action
+struct HandsFreeAction;
+
+impl ShortcutAction for HandsFreeAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        if let Some(coordinator) = app.try_state::<TranscriptionCoordinator>() {
+            coordinator.toggle_hands_free();
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Arming toggles on press only
+    }
+}
+
 // Test Action
 struct TestAction;
 
@@ -481,6 +578,15 @@ impl ShortcutAction for TestAction {
     }
 }
 
+/// The action behind a binding. Bindings the user created all transcribe,
+/// with their own settings applied when they stop.
+pub fn action_for(binding_id: &str) -> Option<Arc<dyn ShortcutAction>> {
+    if is_custom_binding(binding_id) {
+        return ACTION_MAP.get("transcribe").cloned();
+    }
+    ACTION_MAP.get(binding_id).cloned()
+}
+
 // Static Action Map
 pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::new(|| {
     let mut map = HashMap::new();
@@ -498,6 +604,10 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "hands_free".to_string(),
+        Arc::new(HandsFreeAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
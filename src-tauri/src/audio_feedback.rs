@@ -1,10 +1,11 @@
+use crate::audio_toolkit::{constants, encode_wav_bytes};
 use crate::settings::SoundTheme;
 use crate::settings::{self, AppSettings};
 use cpal::traits::{DeviceTrait, HostTrait};
 use log::{debug, error, warn};
 use rodio::OutputStreamBuilder;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor, Read, Seek};
 use std::path::{Path, PathBuf};
 use std::thread;
 use tauri::{AppHandle, Manager};
@@ -67,6 +68,46 @@ pub fn play_test_sound(app: &AppHandle, sound_type: SoundType) {
     }
 }
 
+/// Two short high beeps, played when a recording is stopped for hitting its
+/// length or memory limit. Synthesised so custom sound themes need no extra
+/// file.
+pub fn play_warning_sound(app: &AppHandle) {
+    let settings = settings::get_settings(app);
+    if !settings.audio_feedback {
+        return;
+    }
+    let selected_device = settings.selected_output_device.clone();
+    let volume = settings.audio_feedback_volume;
+    thread::spawn(move || {
+        let result = encode_wav_bytes(&warning_tone())
+            .map_err(|e| e.into())
+            .and_then(|wav| play_audio(Cursor::new(wav), selected_device, volume));
+        if let Err(e) = result {
+            error!("Failed to play warning sound: {}", e);
+        }
+    });
+}
+
+fn warning_tone() -> Vec<f32> {
+    let rate = constants::WHISPER_SAMPLE_RATE as f32;
+    let beep = (rate * 0.12) as usize;
+    let gap = (rate * 0.08) as usize;
+    // Short fades keep the beeps from clicking
+    let fade = (rate * 0.005) as usize;
+
+    let mut tone = Vec::with_capacity(2 * beep + gap);
+    for n in 0..2 {
+        if n > 0 {
+            tone.resize(tone.len() + gap, 0.0);
+        }
+        tone.extend((0..beep).map(|i| {
+            let envelope = (i.min(beep - 1 - i) as f32 / fade as f32).min(1.0);
+            0.4 * envelope * (2.0 * std::f32::consts::PI * 880.0 * i as f32 / rate).sin()
+        }));
+    }
+    tone
+}
+
 fn play_sound_async(app: &AppHandle, path: PathBuf) {
     let app_handle = app.clone();
     thread::spawn(move || {
@@ -94,6 +135,18 @@ fn play_audio_file(
     selected_device: Option<String>,
     volume: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    play_audio(BufReader::new(file), selected_device, volume)
+}
+
+fn play_audio<R>(
+    audio: R,
+    selected_device: Option<String>,
+    volume: f32,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    R: Read + Seek + Send + Sync + 'static,
+{
     let stream_builder = if let Some(device_name) = selected_device {
         if device_name == "Default" {
             debug!("Using default device");
@@ -126,10 +179,7 @@ fn play_audio_file(
     let stream_handle = stream_builder.open_stream()?;
     let mixer = stream_handle.mixer();
 
-    let file = File::open(path)?;
-    let buf_reader = BufReader::new(file);
-
-    let sink = rodio::play(mixer, buf_reader)?;
+    let sink = rodio::play(mixer, audio)?;
     sink.set_volume(volume);
     sink.sleep_until_end();
 
@@ -0,0 +1,59 @@
+/// How the channels of an input device are folded into the mono stream.
+///
+/// Channels are numbered from zero. Picking a single channel isolates one
+/// input of an audio interface, such as a lavalier on input 2; picking a
+/// subset averages just those, leaving out unused or noisy inputs. The result
+/// is always a single mono mix: inputs are not kept apart or attributed to
+/// speakers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ChannelMix {
+    /// Average every channel
+    #[default]
+    All,
+    /// Average the listed channels
+    Select(Vec<usize>),
+}
+
+impl ChannelMix {
+    /// Build from a stored selection, where an empty list means every channel.
+    pub fn from_channels(channels: &[u16]) -> Self {
+        if channels.is_empty() {
+            ChannelMix::All
+        } else {
+            ChannelMix::Select(channels.iter().map(|&c| c as usize).collect())
+        }
+    }
+
+    /// Channel indices to average for a device with `channels` channels.
+    /// Channels the device does not have are ignored; if none are left every
+    /// channel is used, so a stale selection never silences the microphone.
+    pub fn resolve(&self, channels: usize) -> Vec<usize> {
+        let mut selected = match self {
+            ChannelMix::All => Vec::new(),
+            ChannelMix::Select(list) => list.iter().copied().filter(|&c| c < channels).collect(),
+        };
+        selected.sort_unstable();
+        selected.dedup();
+        if selected.is_empty() {
+            (0..channels).collect()
+        } else {
+            selected
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_selection_against_device_channels() {
+        assert_eq!(ChannelMix::All.resolve(2), vec![0, 1]);
+        assert_eq!(ChannelMix::from_channels(&[]), ChannelMix::All);
+        assert_eq!(ChannelMix::from_channels(&[1]).resolve(4), vec![1]);
+        assert_eq!(ChannelMix::from_channels(&[3, 1, 3]).resolve(4), vec![1, 3]);
+        // Selection made for a bigger interface
+        assert_eq!(ChannelMix::from_channels(&[1, 5]).resolve(2), vec![1]);
+        assert_eq!(ChannelMix::from_channels(&[5]).resolve(2), vec![0, 1]);
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::Result;
+use audiopus::coder::{Decoder as OpusDecoder, Encoder as OpusEncoder};
+use audiopus::{packet::Packet, Application, Bitrate, Channels, MutSignals, SampleRate};
+use ogg::{PacketReader, PacketWriteEndInfo, PacketWriter};
+use std::io::Cursor;
+use std::path::Path;
+
+use super::flac::encode_flac;
+use super::utils::encode_wav_bytes;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+/// Opus frames are 20 ms, the usual choice for speech.
+const OPUS_FRAME_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize / 50;
+const OPUS_BITRATE: i32 = 24_000;
+const OPUS_MAX_PACKET: usize = 4000;
+/// Ogg Opus granule positions are always counted at 48 kHz.
+const OPUS_GRANULE_RATE_FACTOR: u64 = 48_000 / WHISPER_SAMPLE_RATE as u64;
+const OPUS_STREAM_SERIAL: u32 = 0x6861_6e64;
+
+/// Container/codec used for a stored recording.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioCodec {
+    Wav,
+    Flac,
+    Opus,
+}
+
+impl AudioCodec {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioCodec::Wav => "wav",
+            AudioCodec::Flac => "flac",
+            AudioCodec::Opus => "opus",
+        }
+    }
+
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let ext = Path::new(file_name).extension()?.to_str()?;
+        match ext.to_ascii_lowercase().as_str() {
+            "wav" => Some(AudioCodec::Wav),
+            "flac" => Some(AudioCodec::Flac),
+            "opus" => Some(AudioCodec::Opus),
+            _ => None,
+        }
+    }
+}
+
+/// Encode 16 kHz mono samples with the given codec.
+pub fn encode_samples(samples: &[f32], codec: AudioCodec) -> Result<Vec<u8>> {
+    match codec {
+        AudioCodec::Wav => encode_wav_bytes(samples),
+        AudioCodec::Flac => {
+            let pcm: Vec<i16> = samples.iter().map(|&s| to_i16(s)).collect();
+            Ok(encode_flac(&pcm, WHISPER_SAMPLE_RATE))
+        }
+        AudioCodec::Opus => encode_opus(samples),
+    }
+}
+
+/// Decode a stored recording back to 16 kHz mono samples. Audio in any
+/// other layout is rejected rather than misread.
+pub fn decode_samples(data: &[u8], codec: AudioCodec) -> Result<Vec<f32>> {
+    match codec {
+        AudioCodec::Wav => {
+            let mut reader = hound::WavReader::new(Cursor::new(data))?;
+            let spec = reader.spec();
+            if spec.sample_format != hound::SampleFormat::Int {
+                return Err(unsupported_layout(spec.sample_rate, spec.channels, 0));
+            }
+            check_layout(spec.sample_rate, spec.channels, spec.bits_per_sample)?;
+            reader
+                .samples::<i16>()
+                .map(|s| Ok(from_i16(s? as i32)))
+                .collect()
+        }
+        AudioCodec::Flac => {
+            let mut reader = claxon::FlacReader::new(Cursor::new(data))?;
+            let info = reader.streaminfo();
+            check_layout(
+                info.sample_rate,
+                info.channels as u16,
+                info.bits_per_sample as u16,
+            )?;
+            reader.samples().map(|s| Ok(from_i16(s?))).collect()
+        }
+        AudioCodec::Opus => decode_opus(data),
+    }
+}
+
+/// Read a recording from disk, picking the decoder from its extension.
+pub fn read_recording_samples<P: AsRef<Path>>(path: P) -> Result<Vec<f32>> {
+    let path = path.as_ref();
+    let codec = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(AudioCodec::from_file_name)
+        .ok_or_else(|| anyhow::anyhow!("Unsupported recording format: {:?}", path))?;
+    decode_samples(&std::fs::read(path)?, codec)
+}
+
+fn check_layout(sample_rate: u32, channels: u16, bits_per_sample: u16) -> Result<()> {
+    if sample_rate == WHISPER_SAMPLE_RATE && channels == 1 && bits_per_sample == 16 {
+        Ok(())
+    } else {
+        Err(unsupported_layout(sample_rate, channels, bits_per_sample))
+    }
+}
+
+/// `bits_per_sample` is 0 for floating-point audio.
+fn unsupported_layout(sample_rate: u32, channels: u16, bits_per_sample: u16) -> anyhow::Error {
+    let format = if bits_per_sample == 0 {
+        "float".to_string()
+    } else {
+        format!("{}-bit", bits_per_sample)
+    };
+    anyhow::anyhow!(
+        "Unsupported audio layout ({} Hz, {} channel(s), {}); convert it to {} Hz mono 16-bit",
+        sample_rate,
+        channels,
+        format,
+        WHISPER_SAMPLE_RATE
+    )
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample * i16::MAX as f32) as i16
+}
+
+fn from_i16(sample: i32) -> f32 {
+    sample as f32 / i16::MAX as f32
+}
+
+fn encode_opus(samples: &[f32]) -> Result<Vec<u8>> {
+    let mut encoder = OpusEncoder::new(SampleRate::Hz16000, Channels::Mono, Application::Voip)?;
+    encoder.set_bitrate(Bitrate::BitsPerSecond(OPUS_BITRATE))?;
+    let lookahead = encoder.lookahead()? as usize;
+    let pre_skip = lookahead as u64 * OPUS_GRANULE_RATE_FACTOR;
+
+    let mut writer = PacketWriter::new(Vec::new());
+    writer.write_packet(
+        opus_head(pre_skip as u16).into_boxed_slice(),
+        OPUS_STREAM_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+    writer.write_packet(
+        opus_tags().into_boxed_slice(),
+        OPUS_STREAM_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+
+    // Pad with silence so the encoder's lookahead is flushed and the last
+    // frame is complete.
+    let mut padded = samples.to_vec();
+    padded.resize(samples.len() + lookahead, 0.0);
+    let frames = padded.len().div_ceil(OPUS_FRAME_SAMPLES).max(1);
+    padded.resize(frames * OPUS_FRAME_SAMPLES, 0.0);
+
+    let final_granule = pre_skip + samples.len() as u64 * OPUS_GRANULE_RATE_FACTOR;
+    let mut packet = vec![0u8; OPUS_MAX_PACKET];
+    for (index, frame) in padded.chunks(OPUS_FRAME_SAMPLES).enumerate() {
+        let len = encoder.encode_float(frame, &mut packet)?;
+        let is_last = index + 1 == frames;
+        let granule = if is_last {
+            final_granule
+        } else {
+            ((index + 1) * OPUS_FRAME_SAMPLES) as u64 * OPUS_GRANULE_RATE_FACTOR
+        };
+        let end_info = if is_last {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        writer.write_packet(
+            packet[..len].to_vec().into_boxed_slice(),
+            OPUS_STREAM_SERIAL,
+            end_info,
+            granule,
+        )?;
+    }
+
+    Ok(writer.into_inner())
+}
+
+fn decode_opus(data: &[u8]) -> Result<Vec<f32>> {
+    let mut reader = PacketReader::new(Cursor::new(data));
+
+    let head = reader
+        .read_packet()?
+        .ok_or_else(|| anyhow::anyhow!("Opus stream is empty"))?;
+    if head.data.len() < 19 || &head.data[..8] != b"OpusHead" {
+        anyhow::bail!("Missing OpusHead packet");
+    }
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as u64;
+    // OpusTags carries nothing we need
+    reader.read_packet()?;
+
+    let mut decoder = OpusDecoder::new(SampleRate::Hz16000, Channels::Mono)?;
+    let mut frame = vec![0f32; OPUS_FRAME_SAMPLES * 6];
+    let mut samples = Vec::new();
+    let mut final_granule = 0;
+    while let Some(packet) = reader.read_packet()? {
+        let input = Packet::try_from(packet.data.as_slice())?;
+        let output = MutSignals::try_from(frame.as_mut_slice())?;
+        let decoded = decoder.decode_float(Some(input), output, false)?;
+        samples.extend_from_slice(&frame[..decoded]);
+        final_granule = packet.absgp_page();
+    }
+
+    let skip = (pre_skip / OPUS_GRANULE_RATE_FACTOR) as usize;
+    let total = (final_granule.saturating_sub(pre_skip) / OPUS_GRANULE_RATE_FACTOR) as usize;
+    let end = (skip + total).min(samples.len());
+    Ok(samples
+        .get(skip..end)
+        .map(<[f32]>::to_vec)
+        .unwrap_or_default())
+}
+
+fn opus_head(pre_skip: u16) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&WHISPER_SAMPLE_RATE.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // mono/stereo channel mapping
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    let vendor = b"handy";
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (i as f32 / 16_000.0 * 300.0 * std::f32::consts::TAU).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn codec_from_file_name() {
+        assert_eq!(
+            AudioCodec::from_file_name("handy-1.FLAC"),
+            Some(AudioCodec::Flac)
+        );
+        assert_eq!(
+            AudioCodec::from_file_name("handy-1.opus"),
+            Some(AudioCodec::Opus)
+        );
+        assert_eq!(AudioCodec::from_file_name("handy-1.wav.enc"), None);
+    }
+
+    #[test]
+    fn flac_and_wav_roundtrip_exactly() {
+        let samples = tone(8000);
+        let wav = decode_samples(
+            &encode_samples(&samples, AudioCodec::Wav).unwrap(),
+            AudioCodec::Wav,
+        )
+        .unwrap();
+        let flac_bytes = encode_samples(&samples, AudioCodec::Flac).unwrap();
+        let flac = decode_samples(&flac_bytes, AudioCodec::Flac).unwrap();
+
+        assert_eq!(wav, flac);
+        assert!(flac_bytes.len() < encode_samples(&samples, AudioCodec::Wav).unwrap().len());
+    }
+
+    #[test]
+    fn rejects_wav_in_other_layouts() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44_100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut data = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut data, spec).unwrap();
+        for _ in 0..44_100 {
+            writer.write_sample(0i16).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let err = decode_samples(data.get_ref(), AudioCodec::Wav).unwrap_err();
+        assert!(err.to_string().contains("44100 Hz, 2 channel(s), 16-bit"));
+    }
+
+    #[test]
+    fn opus_roundtrip_keeps_length() {
+        let samples = tone(16_000 + 123);
+        let encoded = encode_samples(&samples, AudioCodec::Opus).unwrap();
+        let decoded = decode_samples(&encoded, AudioCodec::Opus).unwrap();
+
+        assert_eq!(decoded.len(), samples.len());
+        assert!(encoded.len() < samples.len() * 2 / 4);
+    }
+}
@@ -0,0 +1,288 @@
+//! Small FLAC encoder for the 16-bit mono recordings kept in history.
+//!
+//! Every block is coded with the best fixed predictor (orders 0-4) and a
+//! single Rice partition, falling back to a verbatim subframe when prediction
+//! does not pay off. That captures most of FLAC's gain on speech without
+//! pulling in a full encoder; any standard decoder can read the output.
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_RICE_PARAM: u32 = 14;
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    /// Append the low `count` bits of `value`, most significant first.
+    /// `count` must be at most 32.
+    fn write(&mut self, value: u64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        self.acc = (self.acc << count) | (value & ((1u64 << count) - 1));
+        self.bits += count;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+        self.acc &= (1u64 << self.bits) - 1;
+    }
+
+    fn write_signed(&mut self, value: i64, count: u32) {
+        self.write(value as u64, count);
+    }
+
+    fn write_unary(&mut self, mut zeros: u64) {
+        while zeros >= 32 {
+            self.write(0, 32);
+            zeros -= 32;
+        }
+        self.write(1, zeros as u32 + 1);
+    }
+
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+}
+
+/// Encode 16-bit mono samples as a complete FLAC stream.
+pub fn encode_flac(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    w.bytes.extend_from_slice(b"fLaC");
+
+    // STREAMINFO, flagged as the last metadata block
+    w.write(1, 1);
+    w.write(0, 7);
+    w.write(34, 24);
+    w.write(BLOCK_SIZE as u64, 16);
+    w.write(BLOCK_SIZE as u64, 16);
+    w.write(0, 24); // min frame size unknown
+    w.write(0, 24); // max frame size unknown
+    w.write(sample_rate as u64, 20);
+    w.write(0, 3); // one channel
+    w.write((BITS_PER_SAMPLE - 1) as u64, 5);
+    let total = samples.len() as u64;
+    w.write(total >> 32, 4);
+    w.write(total & 0xFFFF_FFFF, 32);
+    for _ in 0..4 {
+        w.write(0, 32); // MD5 left unset
+    }
+
+    let mut out = w.bytes;
+    for (index, block) in samples.chunks(BLOCK_SIZE).enumerate() {
+        out.extend(encode_frame(index as u64, block));
+    }
+    out
+}
+
+fn encode_frame(frame_number: u64, block: &[i16]) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    w.write(0b11_1111_1111_1110, 14); // sync code
+    w.write(0, 1);
+    w.write(0, 1); // fixed block size stream
+    w.write(0b0111, 4); // 16-bit block size at end of header
+    w.write(0b0000, 4); // sample rate from STREAMINFO
+    w.write(0b0000, 4); // mono
+    w.write(0b100, 3); // 16 bits per sample
+    w.write(0, 1);
+    write_utf8_number(&mut w, frame_number);
+    w.write(block.len() as u64 - 1, 16);
+    let header_crc = crc8(&w.bytes);
+    w.write(header_crc as u64, 8);
+
+    let samples: Vec<i64> = block.iter().map(|&s| s as i64).collect();
+    write_subframe(&mut w, &samples);
+
+    w.align();
+    let frame_crc = crc16(&w.bytes);
+    w.write(frame_crc as u64, 16);
+    w.bytes
+}
+
+fn write_subframe(w: &mut BitWriter, samples: &[i64]) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        w.write(0, 1);
+        w.write(0b000000, 6); // constant
+        w.write(0, 1);
+        w.write_signed(samples[0], BITS_PER_SAMPLE);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * BITS_PER_SAMPLE as u64;
+    let best = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residual = fixed_residual(samples, order);
+            let (param, bits) = best_rice_param(&residual);
+            let total = order as u64 * BITS_PER_SAMPLE as u64 + 2 + 4 + 4 + bits;
+            (order, residual, param, total)
+        })
+        .min_by_key(|(_, _, _, total)| *total);
+
+    match best {
+        Some((order, residual, param, total)) if total < verbatim_bits => {
+            w.write(0, 1);
+            w.write(0b001000 | order as u64, 6); // fixed predictor
+            w.write(0, 1);
+            for &sample in &samples[..order] {
+                w.write_signed(sample, BITS_PER_SAMPLE);
+            }
+            w.write(0b00, 2); // Rice coding with 4-bit parameters
+            w.write(0, 4); // partition order 0
+            w.write(param as u64, 4);
+            for &r in &residual {
+                let folded = fold(r);
+                w.write_unary(folded >> param);
+                w.write(folded, param);
+            }
+        }
+        _ => {
+            w.write(0, 1);
+            w.write(0b000001, 6); // verbatim
+            w.write(0, 1);
+            for &sample in samples {
+                w.write_signed(sample, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+fn fixed_residual(s: &[i64], order: usize) -> Vec<i64> {
+    (order..s.len())
+        .map(|i| match order {
+            0 => s[i],
+            1 => s[i] - s[i - 1],
+            2 => s[i] - 2 * s[i - 1] + s[i - 2],
+            3 => s[i] - 3 * s[i - 1] + 3 * s[i - 2] - s[i - 3],
+            _ => s[i] - 4 * s[i - 1] + 6 * s[i - 2] - 4 * s[i - 3] + s[i - 4],
+        })
+        .collect()
+}
+
+/// Map signed residuals onto unsigned values: 0, -1, 1, -2, 2, ...
+fn fold(r: i64) -> u64 {
+    if r >= 0 {
+        (r as u64) << 1
+    } else {
+        (((-r) as u64) << 1) - 1
+    }
+}
+
+fn best_rice_param(residual: &[i64]) -> (u32, u64) {
+    (0..=MAX_RICE_PARAM)
+        .map(|param| {
+            let bits = residual
+                .iter()
+                .map(|&r| (fold(r) >> param) + 1 + param as u64)
+                .sum();
+            (param, bits)
+        })
+        .min_by_key(|(_, bits)| *bits)
+        .unwrap_or((0, 0))
+}
+
+/// Frame numbers use the extended UTF-8 style coding from the FLAC spec.
+fn write_utf8_number(w: &mut BitWriter, n: u64) {
+    if n < 0x80 {
+        w.write(n, 8);
+        return;
+    }
+    let mut len = 2u32;
+    while n >= 1u64 << (5 * len + 1) {
+        len += 1;
+    }
+    let prefix = (0xFF00u64 >> len) & 0xFF;
+    w.write(prefix | (n >> (6 * (len - 1))), 8);
+    for i in (0..len - 1).rev() {
+        w.write(0x80 | ((n >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn decode(bytes: &[u8]) -> Vec<i16> {
+        let mut reader = claxon::FlacReader::new(Cursor::new(bytes)).expect("valid stream");
+        reader
+            .samples()
+            .map(|s| s.expect("valid sample") as i16)
+            .collect()
+    }
+
+    #[test]
+    fn roundtrip_is_lossless() {
+        let samples: Vec<i16> = (0..10_000)
+            .map(|i| {
+                let t = i as f32 / 16_000.0;
+                let tone = (t * 440.0 * std::f32::consts::TAU).sin() * 12_000.0;
+                let noise = ((i * 7919) % 301) as f32 - 150.0;
+                (tone + noise) as i16
+            })
+            .collect();
+
+        let encoded = encode_flac(&samples, 16_000);
+        assert_eq!(decode(&encoded), samples);
+        assert!(encoded.len() < samples.len() * 2);
+    }
+
+    #[test]
+    fn handles_silence_extremes_and_short_input() {
+        let mut samples = vec![0i16; 5000];
+        samples.extend([i16::MAX, i16::MIN, i16::MAX, i16::MIN, 3]);
+        assert_eq!(decode(&encode_flac(&samples, 16_000)), samples);
+        assert_eq!(decode(&encode_flac(&[42], 16_000)), vec![42]);
+        assert!(decode(&encode_flac(&[], 16_000)).is_empty());
+    }
+
+    #[test]
+    fn frame_numbers_use_multibyte_coding() {
+        let mut w = BitWriter::new();
+        write_utf8_number(&mut w, 0x7FF);
+        assert_eq!(w.bytes, vec![0xDF, 0xBF]);
+    }
+}
@@ -0,0 +1,283 @@
+//! System audio capture from PulseAudio / PipeWire monitor sources.
+//!
+//! cpal only sees input devices, so monitor sources are recorded with `parec`
+//! (also provided by pipewire-pulse) at the microphone's sample rate. The
+//! microphone stream stays the clock: every chunk it delivers takes the same
+//! number of system audio samples, either mixed in or in place of the mic.
+
+use std::collections::VecDeque;
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+
+/// How system audio is combined with the microphone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopbackMode {
+    /// Microphone and system audio together, e.g. both sides of a call
+    Mix,
+    /// System audio only, e.g. a video playing on the machine
+    Replace,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopbackSpec {
+    /// Monitor source name as known to the sound server
+    pub source: String,
+    pub mode: LoopbackMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopbackSource {
+    pub name: String,
+    pub description: String,
+}
+
+/// At most this much system audio waits for the microphone; the rest is
+/// dropped so the two never drift apart audibly.
+const MAX_BUFFERED_MS: usize = 250;
+
+/// Monitor sources of the default sound server. Empty where there is none.
+pub fn list_loopback_sources() -> Vec<LoopbackSource> {
+    #[cfg(target_os = "linux")]
+    {
+        let output = std::process::Command::new("pactl")
+            .args(["list", "sources"])
+            // Field names are translated otherwise
+            .env("LC_ALL", "C")
+            .output();
+        match output {
+            Ok(o) if o.status.success() => parse_pactl_sources(&String::from_utf8_lossy(&o.stdout)),
+            Ok(o) => {
+                log::debug!("pactl failed: {}", String::from_utf8_lossy(&o.stderr));
+                Vec::new()
+            }
+            Err(e) => {
+                log::debug!("pactl not available: {}", e);
+                Vec::new()
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Pick the monitor sources out of `pactl list sources` output.
+fn parse_pactl_sources(output: &str) -> Vec<LoopbackSource> {
+    let mut sources = Vec::new();
+    let mut name: Option<String> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("Source #") {
+            name = None;
+        } else if let Some(value) = line.strip_prefix("Name: ") {
+            name = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("Description: ") {
+            if let Some(name) = name.take().filter(|n| n.ends_with(".monitor")) {
+                sources.push(LoopbackSource {
+                    name,
+                    description: value.to_string(),
+                });
+            }
+        }
+    }
+    sources
+}
+
+/// System audio waiting to be combined with the microphone.
+struct LoopbackBuffer {
+    samples: VecDeque<f32>,
+    capacity: usize,
+    /// `parec` exited or closed its output
+    ended: bool,
+}
+
+impl LoopbackBuffer {
+    fn new(sample_rate: u32) -> Self {
+        let capacity = sample_rate as usize * MAX_BUFFERED_MS / 1000;
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            ended: false,
+        }
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        self.samples.extend(samples);
+        let excess = self.samples.len().saturating_sub(self.capacity);
+        self.samples.drain(..excess);
+    }
+
+    /// Combine a microphone chunk with as much system audio, padding with
+    /// silence when the monitor source is behind.
+    fn apply(&mut self, mode: LoopbackMode, mic: &mut [f32]) {
+        for sample in mic.iter_mut() {
+            let system = self.samples.pop_front().unwrap_or(0.0);
+            *sample = match mode {
+                LoopbackMode::Mix => (*sample + system).clamp(-1.0, 1.0),
+                LoopbackMode::Replace => system,
+            };
+        }
+    }
+}
+
+/// Read raw mono f32 samples from `parec` into `buffer` until its output
+/// closes, then mark the buffer as ended.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn pump(mut output: impl std::io::Read, buffer: &Mutex<LoopbackBuffer>) {
+    let mut bytes = [0u8; 4096];
+    let mut pending = Vec::<u8>::with_capacity(4);
+    let mut samples = Vec::<f32>::with_capacity(bytes.len() / 4);
+    loop {
+        let n = match output.read(&mut bytes) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        // Reads can split a sample; carry the remainder over
+        pending.extend_from_slice(&bytes[..n]);
+        let whole = pending.len() / 4 * 4;
+        samples.clear();
+        samples.extend(
+            pending[..whole]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        );
+        pending.drain(..whole);
+        buffer.lock().unwrap().push(&samples);
+    }
+    buffer.lock().unwrap().ended = true;
+}
+
+/// A running `parec` recording of a monitor source.
+pub struct LoopbackCapture {
+    child: Child,
+    buffer: Arc<Mutex<LoopbackBuffer>>,
+    mode: LoopbackMode,
+}
+
+impl LoopbackCapture {
+    /// Start recording `spec.source` as mono f32 at `sample_rate`.
+    pub fn start(spec: &LoopbackSpec, sample_rate: u32) -> Result<Self, String> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::process::{Command, Stdio};
+
+            let mut child = Command::new("parec")
+                .arg(format!("--device={}", spec.source))
+                .args(["--format=float32le", "--channels=1", "--raw"])
+                .arg(format!("--rate={}", sample_rate))
+                .arg("--latency-msec=20")
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| format!("Failed to start parec: {}", e))?;
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or("parec has no output pipe".to_string())?;
+
+            let buffer = Arc::new(Mutex::new(LoopbackBuffer::new(sample_rate)));
+            let reader_buffer = buffer.clone();
+            let source = spec.source.clone();
+            std::thread::spawn(move || {
+                pump(stdout, &reader_buffer);
+                log::info!("System audio capture from {} ended", source);
+            });
+
+            log::info!(
+                "Capturing system audio from {} at {} Hz ({:?})",
+                spec.source,
+                sample_rate,
+                spec.mode
+            );
+            Ok(Self {
+                child,
+                buffer,
+                mode: spec.mode,
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (spec, sample_rate);
+            Err("System audio capture is only supported on Linux".to_string())
+        }
+    }
+
+    /// Mix system audio into, or put it in place of, a microphone chunk.
+    pub fn apply(&self, mic: &mut [f32]) {
+        self.buffer.lock().unwrap().apply(self.mode, mic);
+    }
+
+    /// Whether `parec` stopped delivering audio and everything it sent has
+    /// been used. From then on `apply` would only add silence.
+    pub fn has_ended(&self) -> bool {
+        let buffer = self.buffer.lock().unwrap();
+        buffer.ended && buffer.samples.is_empty()
+    }
+}
+
+impl Drop for LoopbackCapture {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_only_monitor_sources() {
+        let output = "Source #55\n\
+            \tState: SUSPENDED\n\
+            \tName: alsa_output.pci-0000_00_1f.3.analog-stereo.monitor\n\
+            \tDescription: Monitor of Built-in Audio Analog Stereo\n\
+            \tDriver: PipeWire\n\
+            \n\
+            Source #56\n\
+            \tName: alsa_input.pci-0000_00_1f.3.analog-stereo\n\
+            \tDescription: Built-in Audio Analog Stereo\n";
+        assert_eq!(
+            parse_pactl_sources(output),
+            vec![LoopbackSource {
+                name: "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor".to_string(),
+                description: "Monitor of Built-in Audio Analog Stereo".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn mixes_or_replaces_and_pads_with_silence() {
+        let mut buffer = LoopbackBuffer::new(16_000);
+        buffer.push(&[0.5, 0.5]);
+        let mut mic = [0.25, 0.75, 0.25];
+        buffer.apply(LoopbackMode::Mix, &mut mic);
+        assert_eq!(mic, [0.75, 1.0, 0.25]);
+
+        buffer.push(&[0.1]);
+        let mut mic = [0.9, 0.9];
+        buffer.apply(LoopbackMode::Replace, &mut mic);
+        assert_eq!(mic, [0.1, 0.0]);
+
+        // Never more than the cap waits for the microphone
+        buffer.push(&vec![0.0; 16_000]);
+        assert_eq!(buffer.samples.len(), 16_000 * MAX_BUFFERED_MS / 1000);
+    }
+
+    #[test]
+    fn reading_marks_the_end_of_the_output() {
+        let bytes: Vec<u8> = [0.5f32, -0.25]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            // A trailing partial sample is dropped
+            .chain([0u8, 1])
+            .collect();
+        let buffer = Mutex::new(LoopbackBuffer::new(16_000));
+        pump(std::io::Cursor::new(bytes), &buffer);
+
+        let buffer = buffer.into_inner().unwrap();
+        assert!(buffer.ended);
+        assert_eq!(buffer.samples, [0.5, -0.25]);
+    }
+}
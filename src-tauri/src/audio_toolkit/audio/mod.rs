@@ -1,12 +1,23 @@
 // Re-export all audio components
+mod channels;
+mod codec;
 mod device;
+mod flac;
+mod loopback;
+mod pre_roll;
 mod recorder;
 mod resampler;
 mod utils;
 mod visualizer;
 
+pub use channels::ChannelMix;
+pub use codec::{decode_samples, encode_samples, read_recording_samples, AudioCodec};
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
-pub use recorder::AudioRecorder;
+pub use loopback::{
+    list_loopback_sources, LoopbackCapture, LoopbackMode, LoopbackSource, LoopbackSpec,
+};
+pub use pre_roll::PreRoll;
+pub use recorder::{AudioRecorder, RecordingLimits, RecordingProgress, StreamFault};
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use utils::encode_wav_bytes;
 pub use visualizer::AudioVisualiser;
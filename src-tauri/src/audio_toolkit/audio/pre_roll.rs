@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+/// Ring of the most recent frames heard while not recording, so speech that
+/// starts together with the hotkey can be prepended to the recording.
+///
+/// Frames only arrive while the stream is open, so this needs an always-on
+/// microphone (or hands-free or the wake word, which keep it open). An
+/// on-demand stream opens on the keypress and has nothing earlier to keep.
+pub struct PreRoll {
+    frames: VecDeque<Vec<f32>>,
+    capacity: usize,
+}
+
+impl PreRoll {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Change how many frames are kept, dropping the oldest if it shrinks.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, frame: &[f32]) {
+        if self.capacity == 0 {
+            return;
+        }
+        // Reuse the oldest buffer once full
+        let mut buf = if self.frames.len() == self.capacity {
+            self.frames.pop_front().unwrap_or_default()
+        } else {
+            Vec::with_capacity(frame.len())
+        };
+        buf.clear();
+        buf.extend_from_slice(frame);
+        self.frames.push_back(buf);
+    }
+
+    /// Take the buffered frames, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = Vec<f32>> + '_ {
+        self.frames.drain(..)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_latest_frames_in_order() {
+        let mut pre_roll = PreRoll::new(3);
+        for i in 0..5 {
+            pre_roll.push(&[i as f32; 4]);
+        }
+        let firsts: Vec<f32> = pre_roll.drain().map(|f| f[0]).collect();
+        assert_eq!(firsts, vec![2.0, 3.0, 4.0]);
+        assert_eq!(pre_roll.drain().count(), 0);
+
+        for i in 0..3 {
+            pre_roll.push(&[i as f32; 4]);
+        }
+        pre_roll.set_capacity(1);
+        let firsts: Vec<f32> = pre_roll.drain().map(|f| f[0]).collect();
+        assert_eq!(firsts, vec![2.0]);
+
+        pre_roll.set_capacity(0);
+        pre_roll.push(&[1.0; 4]);
+        assert_eq!(pre_roll.drain().count(), 0);
+    }
+}
@@ -1,6 +1,9 @@
 use std::{
     io::Error,
-    sync::{mpsc, Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -10,24 +13,70 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{AudioVisualiser, ChannelMix, FrameResampler, LoopbackCapture, LoopbackSpec, PreRoll},
     constants,
+    dsp::{DspChain, StageStats},
     vad::{self, VadFrame},
+    wake_word::WakeWordDetector,
     VoiceActivityDetector,
 };
 
+/// Frames handed to the VAD are 30 ms, 480 samples at 16 kHz.
+const FRAME_DURATION: Duration = Duration::from_millis(30);
+const FRAME_SAMPLES: usize = 480;
+
 enum Cmd {
     Start,
     Stop(mpsc::Sender<Vec<f32>>),
+    SwitchDevice(Device, ChannelMix, mpsc::Sender<Result<(), String>>),
     Shutdown,
 }
 
+/// Limits applied to each recording.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecordingLimits {
+    /// Length from the first speech on, including silence the VAD dropped
+    /// after it. Time spent armed and waiting for speech does not count.
+    pub max_duration: Option<Duration>,
+    /// Samples kept, which bounds memory at four bytes per sample
+    pub max_samples: Option<usize>,
+}
+
+/// Snapshot of the recording in progress.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordingProgress {
+    /// Time since the first speech of the recording
+    pub elapsed: Duration,
+    /// Audio the VAD kept as speech
+    pub speech: Duration,
+    pub max_duration: Option<Duration>,
+}
+
+/// Problem reported by a capture stream after it started.
+#[derive(Debug, Clone)]
+pub struct StreamFault {
+    pub device: String,
+    pub message: String,
+    /// The device went away, e.g. a USB headset was unplugged
+    pub device_lost: bool,
+}
+
 pub struct AudioRecorder {
     device: Option<Device>,
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    speech_cb: Option<Arc<dyn Fn(bool) + Send + Sync + 'static>>,
+    progress_cb: Option<Arc<dyn Fn(RecordingProgress) + Send + Sync + 'static>>,
+    limit_cb: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    limits: Arc<Mutex<RecordingLimits>>,
+    wake_word: Arc<Mutex<Option<WakeWordDetector>>>,
+    wake_cb: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    dsp: Arc<Mutex<Option<DspChain>>>,
+    pre_roll_frames: Arc<AtomicUsize>,
+    loopback: Arc<Mutex<Option<LoopbackSpec>>>,
+    fault_cb: Option<Arc<dyn Fn(StreamFault) + Send + Sync + 'static>>,
 }
 
 impl AudioRecorder {
@@ -38,6 +87,16 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            speech_cb: None,
+            progress_cb: None,
+            limit_cb: None,
+            limits: Arc::new(Mutex::new(RecordingLimits::default())),
+            wake_word: Arc::new(Mutex::new(None)),
+            wake_cb: None,
+            dsp: Arc::new(Mutex::new(None)),
+            pre_roll_frames: Arc::new(AtomicUsize::new(0)),
+            loopback: Arc::new(Mutex::new(None)),
+            fault_cb: None,
         })
     }
 
@@ -46,6 +105,14 @@ impl AudioRecorder {
         self
     }
 
+    /// Swap the detector, taking effect from the next frame if the stream is open.
+    pub fn set_vad(&mut self, vad: Box<dyn VoiceActivityDetector>) {
+        match &self.vad {
+            Some(current) => *current.lock().unwrap() = vad,
+            None => self.vad = Some(Arc::new(Mutex::new(vad))),
+        }
+    }
+
     pub fn with_level_callback<F>(mut self, cb: F) -> Self
     where
         F: Fn(Vec<f32>) + Send + Sync + 'static,
@@ -54,7 +121,97 @@ impl AudioRecorder {
         self
     }
 
-    pub fn open(&mut self, device: Option<Device>) -> Result<(), Box<dyn std::error::Error>> {
+    /// Called with the VAD decision for every frame while recording.
+    pub fn with_speech_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(bool) + Send + Sync + 'static,
+    {
+        self.speech_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called about twice a second while recording.
+    pub fn with_progress_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(RecordingProgress) + Send + Sync + 'static,
+    {
+        self.progress_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called once per recording when it reaches one of its limits. Audio past
+    /// the sample limit is dropped until the recording is stopped.
+    pub fn with_limit_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.limit_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Limits for recordings started from now on.
+    pub fn set_limits(&self, limits: RecordingLimits) {
+        *self.limits.lock().unwrap() = limits;
+    }
+
+    /// Called when the wake word detector fires while not recording.
+    pub fn with_wake_word_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.wake_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called from the audio backend's thread when the stream reports an error.
+    pub fn with_fault_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(StreamFault) + Send + Sync + 'static,
+    {
+        self.fault_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Install or remove the wake word detector, live if the stream is open.
+    pub fn set_wake_word(&self, detector: Option<WakeWordDetector>) {
+        *self.wake_word.lock().unwrap() = detector;
+    }
+
+    /// Install or remove the conditioning chain run on every resampled frame,
+    /// live if the stream is open.
+    pub fn set_dsp(&self, chain: Option<DspChain>) {
+        *self.dsp.lock().unwrap() = chain.filter(|c| !c.is_empty());
+    }
+
+    /// Keep this much of the audio heard while idle and prepend it when
+    /// recording starts. Applies live if the stream is open; a stream opened
+    /// just to record has no idle audio, see [`PreRoll`].
+    pub fn set_pre_roll(&self, duration: Duration) {
+        let frames = duration.as_millis().div_ceil(FRAME_DURATION.as_millis()) as usize;
+        self.pre_roll_frames.store(frames, Ordering::Relaxed);
+    }
+
+    /// Capture system audio alongside the microphone, or stop doing so.
+    /// Applies live if the stream is open.
+    pub fn set_loopback(&self, spec: Option<LoopbackSpec>) {
+        *self.loopback.lock().unwrap() = spec;
+    }
+
+    /// Per-stage timings of the current conditioning chain.
+    pub fn dsp_stats(&self) -> Vec<StageStats> {
+        self.dsp
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|c| c.stats())
+            .unwrap_or_default()
+    }
+
+    pub fn open(
+        &mut self,
+        device: Option<Device>,
+        mix: ChannelMix,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         if self.worker_handle.is_some() {
             return Ok(()); // already open
         }
@@ -74,53 +231,65 @@ impl AudioRecorder {
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let rec_hooks = RecordingHooks {
+            speech_cb: self.speech_cb.clone(),
+            progress_cb: self.progress_cb.clone(),
+            limit_cb: self.limit_cb.clone(),
+            limits: self.limits.clone(),
+        };
+        let wake_word = self.wake_word.clone();
+        let wake_cb = self.wake_cb.clone();
+        let dsp = self.dsp.clone();
+        let pre_roll_frames = self.pre_roll_frames.clone();
+        let loopback = self.loopback.clone();
+        let fault_cb = self.fault_cb.clone();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
 
         let worker = std::thread::spawn(move || {
-            let config = AudioRecorder::get_preferred_config(&thread_device)
-                .expect("failed to fetch preferred config");
-
-            let sample_rate = config.sample_rate().0;
-            let channels = config.channels() as usize;
-
-            log::info!(
-                "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
-                thread_device.name(),
-                sample_rate,
-                channels,
-                config.sample_format()
-            );
-
-            let stream = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
+            let capture = match Capture::start(&thread_device, &mix, sample_tx.clone(), &fault_cb) {
+                Ok(capture) => capture,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
                 }
-                _ => panic!("unsupported sample format"),
             };
-
-            stream.play().expect("failed to start stream");
+            let _ = ready_tx.send(Ok(()));
 
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
+            run_consumer(
+                CaptureSource {
+                    capture: Some(capture),
+                    sample_tx,
+                    on_fault: fault_cb,
+                    loopback: None,
+                    loopback_spec: None,
+                },
+                vad,
+                sample_rx,
+                cmd_rx,
+                level_cb,
+                rec_hooks,
+                FrameHooks {
+                    dsp,
+                    wake_word,
+                    on_wake: wake_cb,
+                    pre_roll_frames,
+                    loopback,
+                },
+            );
             // stream is dropped here, after run_consumer returns
         });
 
+        // Report a device that cannot be opened to the caller instead of
+        // leaving a dead worker behind
+        let ready = ready_rx
+            .recv()
+            .unwrap_or_else(|_| Err("Capture thread exited unexpectedly".to_string()));
+        if let Err(e) = ready {
+            let _ = worker.join();
+            return Err(e.into());
+        }
+
         self.device = Some(device);
         self.cmd_tx = Some(cmd_tx);
         self.worker_handle = Some(worker);
@@ -128,6 +297,39 @@ impl AudioRecorder {
         Ok(())
     }
 
+    /// Move the open stream to another device, keeping what has been recorded
+    /// so far. On failure the stream stays open without a device until the
+    /// next switch.
+    pub fn switch_device(
+        &mut self,
+        device: Device,
+        mix: ChannelMix,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let tx = self.cmd_tx.as_ref().ok_or("Recorder is not open")?;
+        let (reply_tx, reply_rx) = mpsc::channel();
+        tx.send(Cmd::SwitchDevice(device.clone(), mix, reply_tx))?;
+        match reply_rx.recv()? {
+            Ok(()) => {
+                self.device = Some(device);
+                Ok(())
+            }
+            Err(e) => {
+                self.device = None;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Number of channels the device would be captured with.
+    pub fn channel_count(device: &Device) -> Result<u16, Box<dyn std::error::Error>> {
+        Ok(Self::get_preferred_config(device)?.channels())
+    }
+
+    /// Name of the device the stream is capturing from.
+    pub fn device_name(&self) -> Option<String> {
+        self.device.as_ref().and_then(|d| d.name().ok())
+    }
+
     pub fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = &self.cmd_tx {
             tx.send(Cmd::Start)?;
@@ -154,15 +356,18 @@ impl AudioRecorder {
         Ok(())
     }
 
-    fn build_stream<T>(
+    fn build_stream<T, E>(
         device: &cpal::Device,
         config: &cpal::SupportedStreamConfig,
         sample_tx: mpsc::Sender<Vec<f32>>,
         channels: usize,
+        selected: Vec<usize>,
+        on_error: E,
     ) -> Result<cpal::Stream, cpal::BuildStreamError>
     where
         T: Sample + SizedSample + Send + 'static,
         f32: cpal::FromSample<T>,
+        E: FnMut(cpal::StreamError) + Send + 'static,
     {
         let mut output_buffer = Vec::new();
 
@@ -173,16 +378,16 @@ impl AudioRecorder {
                 // Direct conversion without intermediate Vec
                 output_buffer.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
             } else {
-                // Convert to mono directly
+                // Fold the selected channels into mono directly
                 let frame_count = data.len() / channels;
                 output_buffer.reserve(frame_count);
 
                 for frame in data.chunks_exact(channels) {
-                    let mono_sample = frame
+                    let mono_sample = selected
                         .iter()
-                        .map(|&sample| sample.to_sample::<f32>())
+                        .map(|&c| frame[c].to_sample::<f32>())
                         .sum::<f32>()
-                        / channels as f32;
+                        / selected.len() as f32;
                     output_buffer.push(mono_sample);
                 }
             }
@@ -192,12 +397,7 @@ impl AudioRecorder {
             }
         };
 
-        device.build_input_stream(
-            &config.clone().into(),
-            stream_cb,
-            |err| log::error!("Stream error: {}", err),
-            None,
-        )
+        device.build_input_stream(&config.clone().into(), stream_cb, on_error, None)
     }
 
     fn get_preferred_config(
@@ -239,71 +439,346 @@ impl AudioRecorder {
     }
 }
 
+/// A running input stream and the rate its samples arrive at.
+struct Capture {
+    _stream: cpal::Stream,
+    sample_rate: u32,
+}
+
+impl Capture {
+    fn start(
+        device: &Device,
+        mix: &ChannelMix,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+        on_fault: &Option<Arc<dyn Fn(StreamFault) + Send + Sync + 'static>>,
+    ) -> Result<Self, String> {
+        let name = device.name().unwrap_or_else(|_| "Unknown".into());
+        let config = AudioRecorder::get_preferred_config(device)
+            .map_err(|e| format!("No usable input config on {}: {}", name, e))?;
+
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let selected = mix.resolve(channels);
+
+        log::info!(
+            "Using device: {:?}\nSample rate: {}\nChannels: {} (using {:?})\nFormat: {:?}",
+            name,
+            sample_rate,
+            channels,
+            selected,
+            config.sample_format()
+        );
+
+        let on_error = {
+            let name = name.clone();
+            let on_fault = on_fault.clone();
+            move |err: cpal::StreamError| {
+                log::error!("Stream error on {}: {}", name, err);
+                if let Some(cb) = &on_fault {
+                    cb(StreamFault {
+                        device: name.clone(),
+                        message: err.to_string(),
+                        device_lost: matches!(err, cpal::StreamError::DeviceNotAvailable),
+                    });
+                }
+            }
+        };
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::U8 => AudioRecorder::build_stream::<u8, _>(
+                device, &config, sample_tx, channels, selected, on_error,
+            ),
+            cpal::SampleFormat::I8 => AudioRecorder::build_stream::<i8, _>(
+                device, &config, sample_tx, channels, selected, on_error,
+            ),
+            cpal::SampleFormat::I16 => AudioRecorder::build_stream::<i16, _>(
+                device, &config, sample_tx, channels, selected, on_error,
+            ),
+            cpal::SampleFormat::I32 => AudioRecorder::build_stream::<i32, _>(
+                device, &config, sample_tx, channels, selected, on_error,
+            ),
+            cpal::SampleFormat::F32 => AudioRecorder::build_stream::<f32, _>(
+                device, &config, sample_tx, channels, selected, on_error,
+            ),
+            other => return Err(format!("Unsupported sample format {:?} on {}", other, name)),
+        }
+        .map_err(|e| format!("Failed to open {}: {}", name, e))?;
+
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start {}: {}", name, e))?;
+
+        Ok(Self {
+            _stream: stream,
+            sample_rate,
+        })
+    }
+}
+
+/// The stream being consumed, and what is needed to replace it on another
+/// device.
+struct CaptureSource {
+    capture: Option<Capture>,
+    sample_tx: mpsc::Sender<Vec<f32>>,
+    on_fault: Option<Arc<dyn Fn(StreamFault) + Send + Sync + 'static>>,
+    /// System audio combined with the microphone, and the spec it was last
+    /// started from, kept even if starting failed so it is not retried on
+    /// every chunk
+    loopback: Option<LoopbackCapture>,
+    loopback_spec: Option<LoopbackSpec>,
+}
+
+impl CaptureSource {
+    /// Combine system audio into a microphone chunk. Once `parec` has exited
+    /// the capture is dropped and reported, so a Replace recording goes on
+    /// with the microphone instead of silence. The spec is kept so it is not
+    /// restarted until the setting changes.
+    fn apply_loopback(&mut self, chunk: &mut [f32]) {
+        let Some(loopback) = &self.loopback else {
+            return;
+        };
+        if !loopback.has_ended() {
+            loopback.apply(chunk);
+            return;
+        }
+
+        self.loopback = None;
+        let source = self
+            .loopback_spec
+            .as_ref()
+            .map_or_else(String::new, |spec| spec.source.clone());
+        log::warn!(
+            "System audio capture from {} stopped, recording the microphone only",
+            source
+        );
+        if let Some(cb) = &self.on_fault {
+            cb(StreamFault {
+                device: source,
+                message: "System audio capture stopped; recording the microphone only".to_string(),
+                device_lost: false,
+            });
+        }
+    }
+
+    /// Start, restart or stop system audio capture to match `wanted`.
+    fn sync_loopback(&mut self, wanted: &Option<LoopbackSpec>, sample_rate: u32) {
+        if &self.loopback_spec == wanted {
+            return;
+        }
+        self.loopback = None;
+        self.loopback_spec = wanted.clone();
+        if let Some(spec) = wanted {
+            match LoopbackCapture::start(spec, sample_rate) {
+                Ok(capture) => self.loopback = Some(capture),
+                Err(e) => log::error!("System audio capture from {} failed: {}", spec.source, e),
+            }
+        }
+    }
+}
+
+/// Per-frame processing shared with the consumer thread: the conditioning
+/// chain, the wake word detector and what to call when it fires, how many
+/// idle frames to keep as pre-roll, and which system audio to capture.
+struct FrameHooks {
+    dsp: Arc<Mutex<Option<DspChain>>>,
+    wake_word: Arc<Mutex<Option<WakeWordDetector>>>,
+    on_wake: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    pre_roll_frames: Arc<AtomicUsize>,
+    loopback: Arc<Mutex<Option<LoopbackSpec>>>,
+}
+
+/// Callbacks about the recording in progress, and the limits it runs under.
+struct RecordingHooks {
+    speech_cb: Option<Arc<dyn Fn(bool) + Send + Sync + 'static>>,
+    progress_cb: Option<Arc<dyn Fn(RecordingProgress) + Send + Sync + 'static>>,
+    limit_cb: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    limits: Arc<Mutex<RecordingLimits>>,
+}
+
+/// Progress is reported every this many frames, about half a second.
+const PROGRESS_EVERY_FRAMES: u32 = 17;
+
+fn samples_duration(samples: usize) -> Duration {
+    Duration::from_secs_f64(samples as f64 / constants::WHISPER_SAMPLE_RATE as f64)
+}
+
 fn run_consumer(
-    in_sample_rate: u32,
+    mut source: CaptureSource,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    rec_hooks: RecordingHooks,
+    hooks: FrameHooks,
 ) {
-    let mut frame_resampler = FrameResampler::new(
-        in_sample_rate as usize,
-        constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
-    );
+    fn resampler_for(in_sample_rate: u32) -> FrameResampler {
+        FrameResampler::new(
+            in_sample_rate as usize,
+            constants::WHISPER_SAMPLE_RATE as usize,
+            FRAME_DURATION,
+        )
+    }
+
+    let mut in_sample_rate = source
+        .capture
+        .as_ref()
+        .map_or(constants::WHISPER_SAMPLE_RATE, |c| c.sample_rate);
+    let mut frame_resampler = resampler_for(in_sample_rate);
 
     let mut processed_samples = Vec::<f32>::new();
+    let mut conditioned = Vec::<f32>::new();
+    let mut pre_roll = PreRoll::new(hooks.pre_roll_frames.load(Ordering::Relaxed));
     let mut recording = false;
+    // Per-recording bookkeeping, reset on start
+    let mut max_duration: Option<Duration> = None;
+    let mut max_samples = usize::MAX;
+    let mut elapsed_frames: u32 = 0;
+    let mut heard_speech = false;
+    let mut limit_reached = false;
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
     const WINDOW_SIZE: usize = 512;
-    let mut visualizer = AudioVisualiser::new(
-        in_sample_rate,
-        WINDOW_SIZE,
-        BUCKETS,
-        400.0,  // vocal_min_hz
-        4000.0, // vocal_max_hz
-    );
+    fn visualiser_for(in_sample_rate: u32) -> AudioVisualiser {
+        AudioVisualiser::new(
+            in_sample_rate,
+            WINDOW_SIZE,
+            BUCKETS,
+            400.0,  // vocal_min_hz
+            4000.0, // vocal_max_hz
+        )
+    }
+    let mut visualizer = visualiser_for(in_sample_rate);
 
     fn handle_frame(
         samples: &[f32],
         recording: bool,
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+        speech_cb: &Option<Arc<dyn Fn(bool) + Send + Sync + 'static>>,
+        max_samples: usize,
         out_buf: &mut Vec<f32>,
-    ) {
+    ) -> bool {
         if !recording {
-            return;
+            return false;
         }
+        let room = max_samples.saturating_sub(out_buf.len());
 
         if let Some(vad_arc) = vad {
             let mut det = vad_arc.lock().unwrap();
-            match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => out_buf.extend_from_slice(buf),
-                VadFrame::Noise => {}
+            let frame = det.push_frame(samples).unwrap_or(VadFrame::Speech(samples));
+            if let Some(cb) = speech_cb {
+                cb(frame.is_speech());
+            }
+            match frame {
+                VadFrame::Speech(buf) => {
+                    out_buf.extend_from_slice(&buf[..buf.len().min(room)]);
+                    true
+                }
+                VadFrame::Noise => false,
             }
         } else {
-            out_buf.extend_from_slice(samples);
+            out_buf.extend_from_slice(&samples[..samples.len().min(room)]);
+            true
+        }
+    }
+
+    /// Run the conditioning chain, if any, over a copy of the frame. The chain
+    /// also runs while idle so the noise estimate is settled when speech starts.
+    fn condition<'a>(
+        dsp: &Mutex<Option<DspChain>>,
+        frame: &'a [f32],
+        buf: &'a mut Vec<f32>,
+    ) -> &'a [f32] {
+        match dsp.lock().unwrap().as_mut() {
+            Some(chain) => {
+                buf.clear();
+                buf.extend_from_slice(frame);
+                chain.process(buf);
+                buf
+            }
+            None => frame,
         }
     }
 
     loop {
-        let raw = match sample_rx.recv() {
-            Ok(s) => s,
-            Err(_) => break, // stream closed
+        let raw = match sample_rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(s) => Some(s),
+            // Keep serving commands while the device is silent or gone
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break, // stream closed
         };
 
-        // ---------- spectrum processing ---------------------------------- //
-        if let Some(buckets) = visualizer.feed(&raw) {
-            if let Some(cb) = &level_cb {
-                cb(buckets);
-            }
+        if source.capture.is_some() {
+            source.sync_loopback(&hooks.loopback.lock().unwrap(), in_sample_rate);
         }
 
-        // ---------- existing pipeline ------------------------------------ //
-        frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
-        });
+        if let Some(mut raw) = raw {
+            source.apply_loopback(&mut raw);
+
+            // ---------- spectrum processing ------------------------------ //
+            if let Some(buckets) = visualizer.feed(&raw) {
+                if let Some(cb) = &level_cb {
+                    cb(buckets);
+                }
+            }
+
+            // ---------- existing pipeline -------------------------------- //
+            frame_resampler.push(&raw, &mut |frame: &[f32]| {
+                let frame = condition(&hooks.dsp, frame, &mut conditioned);
+                // Spot the wake word ahead of VAD, only while idle
+                if !recording {
+                    pre_roll.set_capacity(hooks.pre_roll_frames.load(Ordering::Relaxed));
+                    pre_roll.push(frame);
+                    if let Some(detector) = hooks.wake_word.lock().unwrap().as_mut() {
+                        if detector.push_frame(frame) {
+                            // The pre-roll holds the wake phrase itself, which
+                            // should not end up in the transcript
+                            pre_roll.clear();
+                            if let Some(cb) = &hooks.on_wake {
+                                cb();
+                            }
+                        }
+                    }
+                }
+                heard_speech |= handle_frame(
+                    frame,
+                    recording,
+                    &vad,
+                    &rec_hooks.speech_cb,
+                    max_samples,
+                    &mut processed_samples,
+                );
+                // Hands-free and wake word recordings can sit armed for a
+                // long time; the clock starts with the first speech
+                if !recording || !heard_speech {
+                    return;
+                }
+
+                elapsed_frames += 1;
+                let elapsed = FRAME_DURATION * elapsed_frames;
+                if elapsed_frames.is_multiple_of(PROGRESS_EVERY_FRAMES) {
+                    if let Some(cb) = &rec_hooks.progress_cb {
+                        cb(RecordingProgress {
+                            elapsed,
+                            speech: samples_duration(processed_samples.len()),
+                            max_duration,
+                        });
+                    }
+                }
+                let too_long = max_duration.is_some_and(|max| elapsed >= max);
+                if !limit_reached && (too_long || processed_samples.len() >= max_samples) {
+                    limit_reached = true;
+                    log::warn!(
+                        "Recording limit reached after {:?} with {} samples",
+                        elapsed,
+                        processed_samples.len()
+                    );
+                    if let Some(cb) = &rec_hooks.limit_cb {
+                        cb();
+                    }
+                }
+            });
+        }
 
         // non-blocking check for a command
         while let Ok(cmd) = cmd_rx.try_recv() {
@@ -311,23 +786,214 @@ fn run_consumer(
                 Cmd::Start => {
                     processed_samples.clear();
                     recording = true;
+                    let limits = *rec_hooks.limits.lock().unwrap();
+                    max_duration = limits.max_duration;
+                    max_samples = limits.max_samples.unwrap_or(usize::MAX);
+                    elapsed_frames = 0;
+                    heard_speech = false;
+                    limit_reached = false;
                     visualizer.reset(); // Reset visualization buffer
                     if let Some(v) = &vad {
                         v.lock().unwrap().reset();
                     }
+                    if let Some(detector) = hooks.wake_word.lock().unwrap().as_mut() {
+                        detector.reset();
+                    }
+                    // Audio from just before the keypress goes through the VAD
+                    // like any other frame
+                    for frame in pre_roll.drain() {
+                        heard_speech |= handle_frame(
+                            &frame,
+                            true,
+                            &vad,
+                            &rec_hooks.speech_cb,
+                            max_samples,
+                            &mut processed_samples,
+                        );
+                    }
                 }
                 Cmd::Stop(reply_tx) => {
                     recording = false;
 
                     frame_resampler.finish(&mut |frame: &[f32]| {
                         // we still want to process the last few frames
-                        handle_frame(frame, true, &vad, &mut processed_samples)
+                        let frame = condition(&hooks.dsp, frame, &mut conditioned);
+                        handle_frame(
+                            frame,
+                            true,
+                            &vad,
+                            &rec_hooks.speech_cb,
+                            max_samples,
+                            &mut processed_samples,
+                        );
                     });
 
+                    // Push silence through the chain to drain what its stages
+                    // still hold back
+                    let latency = hooks
+                        .dsp
+                        .lock()
+                        .unwrap()
+                        .as_ref()
+                        .map_or(0, |c| c.latency_samples());
+                    if latency > 0 {
+                        let tail = vec![0.0; latency.div_ceil(FRAME_SAMPLES) * FRAME_SAMPLES];
+                        for chunk in tail.chunks(FRAME_SAMPLES) {
+                            let frame = condition(&hooks.dsp, chunk, &mut conditioned);
+                            handle_frame(
+                                frame,
+                                true,
+                                &vad,
+                                &rec_hooks.speech_cb,
+                                max_samples,
+                                &mut processed_samples,
+                            );
+                        }
+                    }
+
                     let _ = reply_tx.send(std::mem::take(&mut processed_samples));
                 }
+                Cmd::SwitchDevice(device, mix, reply_tx) => {
+                    // Release the old device first; some backends allow one
+                    // stream per device. Samples still queued from it may be
+                    // at another rate and are dropped.
+                    source.capture = None;
+                    source.sync_loopback(&None, in_sample_rate);
+                    while sample_rx.try_recv().is_ok() {}
+
+                    match Capture::start(&device, &mix, source.sample_tx.clone(), &source.on_fault)
+                    {
+                        Ok(capture) => {
+                            if capture.sample_rate != in_sample_rate {
+                                in_sample_rate = capture.sample_rate;
+                                frame_resampler = resampler_for(in_sample_rate);
+                                visualizer = visualiser_for(in_sample_rate);
+                            }
+                            source.capture = Some(capture);
+                            let _ = reply_tx.send(Ok(()));
+                        }
+                        Err(e) => {
+                            let _ = reply_tx.send(Err(e));
+                        }
+                    }
+                }
                 Cmd::Shutdown => return,
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    struct Consumer {
+        samples: mpsc::Sender<Vec<f32>>,
+        commands: mpsc::Sender<Cmd>,
+        /// Signals that a chunk of samples was taken in
+        levels: mpsc::Receiver<()>,
+        thread: thread::JoinHandle<()>,
+    }
+
+    impl Consumer {
+        /// Hand over a chunk and wait until the consumer has taken it, so
+        /// it is processed ahead of commands sent afterwards.
+        fn feed(&self, samples: Vec<f32>) {
+            self.samples.send(samples).unwrap();
+            self.levels.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+
+        fn stop(&self) -> Vec<f32> {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            self.commands.send(Cmd::Stop(reply_tx)).unwrap();
+            reply_rx.recv_timeout(Duration::from_secs(5)).unwrap()
+        }
+
+        fn shut_down(self) {
+            self.commands.send(Cmd::Shutdown).unwrap();
+            drop(self.samples);
+            self.thread.join().unwrap();
+        }
+    }
+
+    /// Run the consumer thread without a device, taking audio at 16 kHz.
+    fn spawn_consumer(pre_roll_frames: usize) -> Consumer {
+        let (sample_tx, sample_rx) = mpsc::channel();
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let source = CaptureSource {
+            capture: None,
+            sample_tx: sample_tx.clone(),
+            on_fault: None,
+            loopback: None,
+            loopback_spec: None,
+        };
+        let rec_hooks = RecordingHooks {
+            speech_cb: None,
+            progress_cb: None,
+            limit_cb: None,
+            limits: Arc::new(Mutex::new(RecordingLimits::default())),
+        };
+        let hooks = FrameHooks {
+            dsp: Arc::new(Mutex::new(None)),
+            wake_word: Arc::new(Mutex::new(None)),
+            on_wake: None,
+            pre_roll_frames: Arc::new(AtomicUsize::new(pre_roll_frames)),
+            loopback: Arc::new(Mutex::new(None)),
+        };
+        let (level_tx, levels) = mpsc::channel();
+        let level_cb: Arc<dyn Fn(Vec<f32>) + Send + Sync> = Arc::new(move |_| {
+            let _ = level_tx.send(());
+        });
+        let thread = thread::spawn(move || {
+            run_consumer(
+                source,
+                None,
+                sample_rx,
+                cmd_rx,
+                Some(level_cb),
+                rec_hooks,
+                hooks,
+            )
+        });
+        Consumer {
+            samples: sample_tx,
+            commands: cmd_tx,
+            levels,
+            thread,
+        }
+    }
+
+    /// `count` frames, each filled with its index.
+    fn numbered_frames(count: usize) -> Vec<f32> {
+        (0..count)
+            .flat_map(|i| std::iter::repeat_n(i as f32, FRAME_SAMPLES))
+            .collect()
+    }
+
+    #[test]
+    fn idle_audio_is_prepended_to_the_recording() {
+        let consumer = spawn_consumer(4);
+
+        consumer.feed(numbered_frames(10));
+        consumer.commands.send(Cmd::Start).unwrap();
+        let recorded = consumer.stop();
+
+        // The last four idle frames, and nothing from before them
+        assert_eq!(recorded.len(), 4 * FRAME_SAMPLES);
+        for (i, frame) in recorded.chunks(FRAME_SAMPLES).enumerate() {
+            assert!(frame.iter().all(|&s| s == (6 + i) as f32), "frame {}", i);
+        }
+        consumer.shut_down();
+    }
+
+    #[test]
+    fn no_pre_roll_when_disabled() {
+        let consumer = spawn_consumer(0);
+
+        consumer.feed(numbered_frames(10));
+        consumer.commands.send(Cmd::Start).unwrap();
+        assert!(consumer.stop().is_empty());
+        consumer.shut_down();
+    }
+}
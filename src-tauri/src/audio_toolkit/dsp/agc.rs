@@ -0,0 +1,88 @@
+use super::{rms, DspStage};
+
+/// Level speech is brought to, about -20 dBFS
+const TARGET_RMS: f32 = 0.1;
+/// At most +20 dB, enough for quiet laptop mics without lifting hiss forever
+const MAX_GAIN: f32 = 10.0;
+const MIN_GAIN: f32 = 0.1;
+/// Frames quieter than about -60 dBFS are silence; the gain is held there
+const GATE_RMS: f32 = 0.001;
+/// Per-frame approach towards the desired gain: cut quickly, boost slowly
+const ATTACK: f32 = 0.5;
+const RELEASE: f32 = 0.05;
+
+/// Automatic gain control that normalises speech level frame by frame.
+pub struct AutoGain {
+    gain: f32,
+}
+
+impl AutoGain {
+    pub fn new() -> Self {
+        Self { gain: 1.0 }
+    }
+}
+
+impl Default for AutoGain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DspStage for AutoGain {
+    fn name(&self) -> &'static str {
+        "agc"
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        if frame.is_empty() {
+            return;
+        }
+        let level = rms(frame);
+        let previous = self.gain;
+        if level > GATE_RMS {
+            let desired = (TARGET_RMS / level).clamp(MIN_GAIN, MAX_GAIN);
+            let rate = if desired < self.gain { ATTACK } else { RELEASE };
+            self.gain += (desired - self.gain) * rate;
+        }
+
+        // Ramp across the frame so gain changes do not click
+        let step = (self.gain - previous) / frame.len() as f32;
+        for (i, sample) in frame.iter_mut().enumerate() {
+            let gain = previous + step * (i + 1) as f32;
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{rms, test_signals};
+    use super::*;
+
+    #[test]
+    fn brings_quiet_speech_up_to_target() {
+        let mut agc = AutoGain::new();
+        let quiet = test_signals::tone(3 * 16_000, 300.0, 0.02);
+        let out = test_signals::run(&mut agc, &quiet);
+        let level = rms(&out[2 * 16_000..]);
+        assert!(
+            (TARGET_RMS * 0.7..TARGET_RMS * 1.4).contains(&level),
+            "{}",
+            level
+        );
+    }
+
+    #[test]
+    fn tames_loud_input_without_clipping_or_lifting_silence() {
+        let mut agc = AutoGain::new();
+        let loud = test_signals::tone(16_000, 300.0, 0.95);
+        let out = test_signals::run(&mut agc, &loud);
+        assert!(out.iter().all(|s| s.abs() <= 1.0));
+        assert!(rms(&out[8_000..]) < rms(&loud[8_000..]) * 0.5);
+
+        let mut agc = AutoGain::new();
+        let hiss = test_signals::noise(16_000, 0.0005, 3);
+        let out = test_signals::run(&mut agc, &hiss);
+        assert!(rms(&out) <= rms(&hiss) * 1.01);
+    }
+}
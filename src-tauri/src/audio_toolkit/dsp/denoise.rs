@@ -0,0 +1,169 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use super::DspStage;
+
+const WINDOW: usize = 512;
+const HOP: usize = WINDOW / 2;
+/// Frames averaged for the first noise estimate
+const INIT_FRAMES: usize = 15;
+/// Bins louder than this multiple of the noise estimate are treated as speech
+const SPEECH_RATIO: f32 = 3.0;
+const NOISE_SMOOTHING: f32 = 0.95;
+/// Under speech the estimate creeps up slowly so steady noise changes are still
+/// followed without eating sustained vowels
+const NOISE_SMOOTHING_SPEECH: f32 = 0.998;
+const OVER_SUBTRACTION: f32 = 1.5;
+/// Never attenuate by more than 20 dB; deeper gating sounds watery
+const GAIN_FLOOR: f32 = 0.1;
+const GAIN_SMOOTHING: f32 = 0.5;
+
+/// Spectral noise suppressor in the spirit of RNNoise: per-band gains computed
+/// against a running noise estimate, applied with overlapping windows. The
+/// gains come from spectral subtraction rather than a trained network, which
+/// keeps it dependency-free and cheap on stationary noise such as fans and hum.
+pub struct NoiseSuppressor {
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    /// sqrt-Hann, applied on analysis and synthesis for unity overlap-add
+    window: Vec<f32>,
+    input: Vec<f32>,
+    pending: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+    spectrum: Vec<Complex32>,
+    noise: Vec<f32>,
+    gains: Vec<f32>,
+    frames_seen: usize,
+}
+
+impl NoiseSuppressor {
+    pub fn new() -> Self {
+        let mut planner = FftPlanner::<f32>::new();
+        let window = (0..WINDOW)
+            .map(|i| {
+                (0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / WINDOW as f32).cos())).sqrt()
+            })
+            .collect();
+        let bins = WINDOW / 2 + 1;
+        Self {
+            fft: planner.plan_fft_forward(WINDOW),
+            ifft: planner.plan_fft_inverse(WINDOW),
+            window,
+            input: vec![0.0; WINDOW],
+            pending: Vec::with_capacity(HOP),
+            overlap: vec![0.0; WINDOW],
+            // Pre-filled so every call can return as many samples as it got
+            output: VecDeque::from(vec![0.0; HOP]),
+            spectrum: vec![Complex32::new(0.0, 0.0); WINDOW],
+            noise: vec![0.0; bins],
+            gains: vec![1.0; bins],
+            frames_seen: 0,
+        }
+    }
+
+    fn process_hop(&mut self) {
+        self.input.copy_within(HOP.., 0);
+        self.input[WINDOW - HOP..].copy_from_slice(&self.pending);
+        self.pending.clear();
+
+        for (i, bin) in self.spectrum.iter_mut().enumerate() {
+            *bin = Complex32::new(self.input[i] * self.window[i], 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        self.frames_seen += 1;
+        for k in 0..self.noise.len() {
+            let power = self.spectrum[k].norm_sqr();
+            let noise = &mut self.noise[k];
+            if self.frames_seen <= INIT_FRAMES {
+                *noise += (power - *noise) / self.frames_seen as f32;
+            } else {
+                let smoothing = if power < *noise * SPEECH_RATIO {
+                    NOISE_SMOOTHING
+                } else {
+                    NOISE_SMOOTHING_SPEECH
+                };
+                *noise = smoothing * *noise + (1.0 - smoothing) * power;
+            }
+
+            let gain = if power > 0.0 {
+                (1.0 - OVER_SUBTRACTION * *noise / power).max(GAIN_FLOOR)
+            } else {
+                GAIN_FLOOR
+            };
+            self.gains[k] = GAIN_SMOOTHING * self.gains[k] + (1.0 - GAIN_SMOOTHING) * gain;
+        }
+
+        // Mirror the gains onto the negative frequencies
+        for k in 0..WINDOW {
+            let bin = if k <= WINDOW / 2 { k } else { WINDOW - k };
+            self.spectrum[k] *= self.gains[bin];
+        }
+        self.ifft.process(&mut self.spectrum);
+
+        let scale = 1.0 / WINDOW as f32;
+        for i in 0..WINDOW {
+            self.overlap[i] += self.spectrum[i].re * scale * self.window[i];
+        }
+        self.output.extend(self.overlap[..HOP].iter().copied());
+        self.overlap.copy_within(HOP.., 0);
+        self.overlap[WINDOW - HOP..].fill(0.0);
+    }
+}
+
+impl Default for NoiseSuppressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DspStage for NoiseSuppressor {
+    fn name(&self) -> &'static str {
+        "denoise"
+    }
+
+    fn latency_samples(&self) -> usize {
+        WINDOW
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        for sample in frame.iter_mut() {
+            self.pending.push(*sample);
+            if self.pending.len() == HOP {
+                self.process_hop();
+            }
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{rms, test_signals};
+    use super::*;
+
+    #[test]
+    fn suppresses_steady_noise_and_keeps_the_tone() {
+        let second = 16_000;
+        let noise = test_signals::noise(3 * second, 0.05, 42);
+        let tone = test_signals::tone(second, 440.0, 0.3);
+        let mut signal = noise.clone();
+        for (s, t) in signal[2 * second..].iter_mut().zip(&tone) {
+            *s += t;
+        }
+
+        let mut suppressor = NoiseSuppressor::new();
+        let out = test_signals::run(&mut suppressor, &signal);
+        let delay = suppressor.latency_samples();
+
+        // Noise-only stretch after the estimate settled: at least 10 dB quieter
+        let quiet = rms(&out[second + delay..2 * second]) / rms(&noise[second..2 * second]);
+        assert!(quiet < 0.32, "noise kept at {}", quiet);
+
+        // Tone stretch: within 2 dB of the clean tone
+        let kept = rms(&out[2 * second + delay + 1_600..3 * second]) / rms(&tone[1_600..]);
+        assert!((0.8..1.25).contains(&kept), "tone kept at {}", kept);
+    }
+}
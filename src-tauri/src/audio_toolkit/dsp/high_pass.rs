@@ -0,0 +1,70 @@
+use super::DspStage;
+
+/// Second-order Butterworth high-pass filter for DC offset, handling noise
+/// and mains hum below the voice band.
+pub struct HighPass {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    // Transposed direct form II state
+    z1: f32,
+    z2: f32,
+}
+
+impl HighPass {
+    pub fn new(sample_rate: u32, cutoff_hz: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos_w0 = w0.cos();
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 + cos_w0) / 2.0 / a0,
+            b1: -(1.0 + cos_w0) / a0,
+            b2: (1.0 + cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+}
+
+impl DspStage for HighPass {
+    fn name(&self) -> &'static str {
+        "high_pass"
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        for sample in frame.iter_mut() {
+            let x = *sample;
+            let y = self.b0 * x + self.z1;
+            self.z1 = self.b1 * x - self.a1 * y + self.z2;
+            self.z2 = self.b2 * x - self.a2 * y;
+            *sample = y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{rms, test_signals};
+    use super::*;
+
+    #[test]
+    fn removes_dc_and_rumble_but_keeps_voice_band() {
+        let mut filter = HighPass::new(16_000, 80.0);
+        let rumble = test_signals::tone(32_000, 30.0, 0.5);
+        let with_dc: Vec<f32> = rumble.iter().map(|s| s + 0.2).collect();
+        let out = test_signals::run(&mut filter, &with_dc);
+        // Skip the settling time
+        assert!(rms(&out[16_000..]) < rms(&rumble[16_000..]) * 0.2);
+
+        let mut filter = HighPass::new(16_000, 80.0);
+        let voice = test_signals::tone(32_000, 1_000.0, 0.5);
+        let out = test_signals::run(&mut filter, &voice);
+        let ratio = rms(&out[16_000..]) / rms(&voice[16_000..]);
+        assert!((0.95..1.05).contains(&ratio), "ratio {}", ratio);
+    }
+}
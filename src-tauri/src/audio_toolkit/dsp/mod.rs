@@ -0,0 +1,195 @@
+//! Conditioning of captured audio before VAD and transcription.
+//!
+//! Stages run on the 16 kHz frames coming out of the resampler, in the order
+//! high-pass, noise suppression, gain control: rumble and DC are removed first
+//! so they neither inflate the noise estimate nor the measured level. Each
+//! stage is optional, and the chain records how long every stage takes so the
+//! cost can be shown next to the toggles.
+
+use std::time::{Duration, Instant};
+
+use crate::audio_toolkit::constants;
+
+mod agc;
+mod denoise;
+mod high_pass;
+
+pub use agc::AutoGain;
+pub use denoise::NoiseSuppressor;
+pub use high_pass::HighPass;
+
+pub trait DspStage: Send {
+    fn name(&self) -> &'static str;
+
+    /// Delay the stage adds to the signal, in samples.
+    fn latency_samples(&self) -> usize {
+        0
+    }
+
+    /// Process one frame in place.
+    fn process(&mut self, frame: &mut [f32]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DspConfig {
+    pub high_pass: bool,
+    pub denoise: bool,
+    pub agc: bool,
+}
+
+impl DspConfig {
+    pub fn is_empty(&self) -> bool {
+        !(self.high_pass || self.denoise || self.agc)
+    }
+}
+
+/// Timing of one stage since the chain was built.
+#[derive(Debug, Clone)]
+pub struct StageStats {
+    pub name: &'static str,
+    /// Delay added to the signal
+    pub latency: Duration,
+    pub frames: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl StageStats {
+    pub fn average(&self) -> Duration {
+        if self.frames == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.frames as u32
+        }
+    }
+}
+
+pub struct DspChain {
+    stages: Vec<(Box<dyn DspStage>, StageStats)>,
+}
+
+impl DspChain {
+    pub fn new(config: DspConfig) -> Self {
+        let sample_rate = constants::WHISPER_SAMPLE_RATE;
+        let mut stages: Vec<Box<dyn DspStage>> = Vec::new();
+        if config.high_pass {
+            stages.push(Box::new(HighPass::new(sample_rate, 80.0)));
+        }
+        if config.denoise {
+            stages.push(Box::new(NoiseSuppressor::new()));
+        }
+        if config.agc {
+            stages.push(Box::new(AutoGain::new()));
+        }
+        Self::from_stages(stages)
+    }
+
+    pub fn from_stages(stages: Vec<Box<dyn DspStage>>) -> Self {
+        let stages = stages
+            .into_iter()
+            .map(|stage| {
+                let stats = StageStats {
+                    name: stage.name(),
+                    latency: Duration::from_micros(
+                        stage.latency_samples() as u64 * 1_000_000
+                            / constants::WHISPER_SAMPLE_RATE as u64,
+                    ),
+                    frames: 0,
+                    total: Duration::ZERO,
+                    max: Duration::ZERO,
+                };
+                (stage, stats)
+            })
+            .collect();
+        Self { stages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Total delay of the chain, in samples.
+    pub fn latency_samples(&self) -> usize {
+        self.stages.iter().map(|(s, _)| s.latency_samples()).sum()
+    }
+
+    pub fn process(&mut self, frame: &mut [f32]) {
+        for (stage, stats) in &mut self.stages {
+            let started = Instant::now();
+            stage.process(frame);
+            let elapsed = started.elapsed();
+            stats.frames += 1;
+            stats.total += elapsed;
+            stats.max = stats.max.max(elapsed);
+        }
+    }
+
+    pub fn stats(&self) -> Vec<StageStats> {
+        self.stages.iter().map(|(_, stats)| stats.clone()).collect()
+    }
+}
+
+/// Root-mean-square level of a block of samples.
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+pub(crate) mod test_signals {
+    /// Deterministic white noise in `-amplitude..amplitude`.
+    pub fn noise(len: usize, amplitude: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed.max(1);
+        (0..len)
+            .map(|_| {
+                // xorshift32
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    pub fn tone(len: usize, hz: f32, amplitude: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * hz * i as f32 / 16_000.0).sin())
+            .collect()
+    }
+
+    /// Run a stage over a signal in recorder-sized frames.
+    pub fn run(stage: &mut dyn super::DspStage, signal: &[f32]) -> Vec<f32> {
+        let mut out = signal.to_vec();
+        for frame in out.chunks_mut(480) {
+            stage.process(frame);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_runs_enabled_stages_in_order_and_times_them() {
+        let mut chain = DspChain::new(DspConfig {
+            high_pass: true,
+            denoise: true,
+            agc: true,
+        });
+        let mut frame = test_signals::noise(480, 0.1, 7);
+        for _ in 0..10 {
+            chain.process(&mut frame);
+        }
+
+        let stats = chain.stats();
+        let names: Vec<_> = stats.iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["high_pass", "denoise", "agc"]);
+        assert!(stats.iter().all(|s| s.frames == 10 && s.max >= s.average()));
+        assert_eq!(stats[1].latency, Duration::from_millis(32));
+        assert!(DspChain::new(DspConfig::default()).is_empty());
+    }
+}
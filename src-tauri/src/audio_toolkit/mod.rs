@@ -1,11 +1,15 @@
 pub mod audio;
 pub mod constants;
+pub mod dsp;
 pub mod text;
 pub mod utils;
 pub mod vad;
+pub mod wake_word;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    decode_samples, encode_samples, encode_wav_bytes, list_input_devices, list_loopback_sources,
+    list_output_devices, read_recording_samples, AudioCodec, AudioRecorder, ChannelMix,
+    CpalDeviceInfo, LoopbackMode, LoopbackSpec, RecordingLimits, RecordingProgress, StreamFault,
 };
 pub use text::{apply_custom_words, filter_transcription_output};
 pub use utils::get_cpal_host;
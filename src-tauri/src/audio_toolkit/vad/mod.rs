@@ -27,6 +27,8 @@ pub trait VoiceActivityDetector: Send + Sync {
 
 mod silero;
 mod smoothed;
+mod utterance;
 
 pub use silero::SileroVad;
 pub use smoothed::SmoothedVad;
+pub use utterance::{UtteranceEnd, UtteranceTracker};
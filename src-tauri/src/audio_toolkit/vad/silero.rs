@@ -1,5 +1,6 @@
 use anyhow::Result;
 use std::path::Path;
+use std::sync::Arc;
 
 use vad_rs::Vad;
 
@@ -13,6 +14,7 @@ const SILERO_FRAME_SAMPLES: usize =
 pub struct SileroVad {
     engine: Vad,
     threshold: f32,
+    probability_cb: Option<Arc<dyn Fn(f32) + Send + Sync + 'static>>,
 }
 
 impl SileroVad {
@@ -25,8 +27,18 @@ impl SileroVad {
             engine: Vad::new(&model_path, constants::WHISPER_SAMPLE_RATE as usize)
                 .map_err(|e| anyhow::anyhow!("Failed to create VAD: {e}"))?,
             threshold,
+            probability_cb: None,
         })
     }
+
+    /// Called with the raw speech probability of every frame.
+    pub fn with_probability_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.probability_cb = Some(Arc::new(cb));
+        self
+    }
 }
 
 impl VoiceActivityDetector for SileroVad {
@@ -43,6 +55,10 @@ impl VoiceActivityDetector for SileroVad {
             .compute(frame)
             .map_err(|e| anyhow::anyhow!("Silero VAD error: {e}"))?;
 
+        if let Some(cb) = &self.probability_cb {
+            cb(result.prob);
+        }
+
         if result.prob > self.threshold {
             Ok(VadFrame::Speech(frame))
         } else {
@@ -0,0 +1,118 @@
+//! Splitting continuous listening into utterances.
+//!
+//! The tracker is fed the keep/drop decision of every VAD frame. An utterance
+//! starts with the first speech frame and ends once silence has lasted for the
+//! configured timeout, or when it grows past the maximum length. Silence is
+//! only seen after the smoothing hangover, so the effective pause is the
+//! hangover plus the timeout.
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UtteranceEnd {
+    Silence,
+    MaxLength,
+}
+
+pub struct UtteranceTracker {
+    frame: Duration,
+    silence_timeout: Duration,
+    max_length: Duration,
+
+    started: bool,
+    ended: bool,
+    length: Duration,
+    silence: Duration,
+}
+
+impl UtteranceTracker {
+    pub fn new(frame: Duration, silence_timeout: Duration, max_length: Duration) -> Self {
+        Self {
+            frame,
+            silence_timeout,
+            max_length,
+            started: false,
+            ended: false,
+            length: Duration::ZERO,
+            silence: Duration::ZERO,
+        }
+    }
+
+    /// Forget the current utterance and wait for the next one.
+    pub fn reset(&mut self) {
+        self.started = false;
+        self.ended = false;
+        self.length = Duration::ZERO;
+        self.silence = Duration::ZERO;
+    }
+
+    pub fn has_started(&self) -> bool {
+        self.started
+    }
+
+    /// Feed one frame. Returns why the utterance ended, exactly once.
+    pub fn push(&mut self, is_speech: bool) -> Option<UtteranceEnd> {
+        if self.ended || (!self.started && !is_speech) {
+            return None;
+        }
+        self.started = true;
+        self.length += self.frame;
+        if is_speech {
+            self.silence = Duration::ZERO;
+        } else {
+            self.silence += self.frame;
+        }
+
+        let end = if self.silence >= self.silence_timeout {
+            Some(UtteranceEnd::Silence)
+        } else if self.length >= self.max_length {
+            Some(UtteranceEnd::MaxLength)
+        } else {
+            None
+        };
+        self.ended = end.is_some();
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> UtteranceTracker {
+        UtteranceTracker::new(
+            Duration::from_millis(30),
+            Duration::from_millis(90),
+            Duration::from_millis(300),
+        )
+    }
+
+    #[test]
+    fn ends_after_silence_timeout_following_speech() {
+        let mut t = tracker();
+        // Leading silence never starts an utterance
+        assert_eq!(t.push(false), None);
+        assert!(!t.has_started());
+
+        assert_eq!(t.push(true), None);
+        assert_eq!(t.push(false), None);
+        assert_eq!(t.push(false), None);
+        // Speech resets the silence counter
+        assert_eq!(t.push(true), None);
+        assert_eq!(t.push(false), None);
+        assert_eq!(t.push(false), None);
+        assert_eq!(t.push(false), Some(UtteranceEnd::Silence));
+        assert_eq!(t.push(false), None);
+    }
+
+    #[test]
+    fn ends_at_max_length_and_restarts_after_reset() {
+        let mut t = tracker();
+        let ends: Vec<_> = (0..12).filter_map(|_| t.push(true)).collect();
+        assert_eq!(ends, vec![UtteranceEnd::MaxLength]);
+
+        t.reset();
+        assert!(!t.has_started());
+        assert_eq!(t.push(true), None);
+    }
+}
@@ -0,0 +1,196 @@
+//! Keyword spotting on the always-on microphone.
+//!
+//! A [`KeywordSpotter`] scores a fixed window of 16 kHz audio. The
+//! [`WakeWordDetector`] keeps the most recent window and only asks for a score
+//! once per hop rather than on every frame. It fires when the score crosses
+//! the threshold, then stays quiet for a cooldown so one spoken phrase does
+//! not trigger twice.
+
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::audio_toolkit::audio::read_recording_samples;
+use crate::audio_toolkit::constants;
+
+mod onnx;
+
+pub use onnx::OnnxKeywordSpotter;
+
+const HOP: Duration = Duration::from_millis(100);
+const COOLDOWN: Duration = Duration::from_secs(2);
+
+pub trait KeywordSpotter: Send + Sync {
+    /// Samples in each scored window.
+    fn window_samples(&self) -> usize;
+
+    /// Probability in `0.0..=1.0` that the window ends with the wake phrase.
+    fn score(&mut self, window: &[f32]) -> Result<f32>;
+}
+
+pub struct WakeWordDetector {
+    spotter: Box<dyn KeywordSpotter>,
+    threshold: f32,
+    hop_samples: usize,
+    cooldown_samples: usize,
+
+    window: VecDeque<f32>,
+    since_score: usize,
+    cooldown: usize,
+}
+
+fn samples_in(duration: Duration) -> usize {
+    (constants::WHISPER_SAMPLE_RATE as u128 * duration.as_millis() / 1000) as usize
+}
+
+impl WakeWordDetector {
+    pub fn new(spotter: Box<dyn KeywordSpotter>, threshold: f32) -> Self {
+        Self {
+            window: VecDeque::with_capacity(spotter.window_samples()),
+            spotter,
+            threshold,
+            hop_samples: samples_in(HOP),
+            cooldown_samples: samples_in(COOLDOWN),
+            since_score: 0,
+            cooldown: 0,
+        }
+    }
+
+    /// Drop buffered audio, e.g. after a dictation finished.
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.since_score = 0;
+    }
+
+    /// Feed one 16 kHz frame. Returns true when the wake phrase was heard.
+    pub fn push_frame(&mut self, frame: &[f32]) -> bool {
+        let window_samples = self.spotter.window_samples();
+        self.window.extend(frame.iter().copied());
+        while self.window.len() > window_samples {
+            self.window.pop_front();
+        }
+
+        if self.cooldown > 0 {
+            self.cooldown = self.cooldown.saturating_sub(frame.len());
+            return false;
+        }
+        self.since_score += frame.len();
+        if self.window.len() < window_samples || self.since_score < self.hop_samples {
+            return false;
+        }
+        self.since_score = 0;
+
+        match self.spotter.score(self.window.make_contiguous()) {
+            Ok(score) if score >= self.threshold => {
+                self.cooldown = self.cooldown_samples;
+                self.window.clear();
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::debug!("Keyword spotter failed: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Run a detector over recorded samples, returning when it fired.
+pub fn detect_in_samples(detector: &mut WakeWordDetector, samples: &[f32]) -> Vec<Duration> {
+    // Same 30 ms frames the recorder produces
+    let frame_samples = samples_in(Duration::from_millis(30));
+    let mut hits = Vec::new();
+    for (i, frame) in samples.chunks(frame_samples).enumerate() {
+        if detector.push_frame(frame) {
+            let end = (i * frame_samples + frame.len()) as u64;
+            hits.push(Duration::from_millis(
+                end * 1000 / constants::WHISPER_SAMPLE_RATE as u64,
+            ));
+        }
+    }
+    hits
+}
+
+/// Run a detector over a 16 kHz mono recording such as a history WAV file.
+pub fn detect_in_file<P: AsRef<Path>>(
+    detector: &mut WakeWordDetector,
+    path: P,
+) -> Result<Vec<Duration>> {
+    let samples = read_recording_samples(path)?;
+    Ok(detect_in_samples(detector, &samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::audio::encode_wav_bytes;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Stands in for a model: "detects" loud audio across the window's tail.
+    struct LoudTail;
+
+    impl KeywordSpotter for LoudTail {
+        fn window_samples(&self) -> usize {
+            8_000
+        }
+
+        fn score(&mut self, window: &[f32]) -> Result<f32> {
+            let tail = &window[window.len() - 1_600..];
+            Ok(tail.iter().map(|s| s.abs()).sum::<f32>() / tail.len() as f32)
+        }
+    }
+
+    fn phrase_at(samples: &mut [f32], start_secs: f32) {
+        let start = (start_secs * 16_000.0) as usize;
+        for (i, s) in samples[start..start + 8_000].iter_mut().enumerate() {
+            *s = 0.8 * (i as f32 * 0.2).sin().signum();
+        }
+    }
+
+    #[test]
+    fn fires_once_per_phrase() {
+        let mut samples = vec![0.0f32; 16_000 * 8];
+        phrase_at(&mut samples, 2.0);
+        phrase_at(&mut samples, 5.0);
+
+        let mut detector = WakeWordDetector::new(Box::new(LoudTail), 0.5);
+        let hits = detect_in_samples(&mut detector, &samples);
+
+        assert_eq!(hits.len(), 2, "hits: {:?}", hits);
+        assert!(hits[0] > Duration::from_secs(2) && hits[0] < Duration::from_millis(2_600));
+        assert!(hits[1] > Duration::from_secs(5) && hits[1] < Duration::from_millis(5_600));
+    }
+
+    #[test]
+    fn detects_phrases_in_wav_fixtures() {
+        let dir = TempDir::new().unwrap();
+        let mut phrase = vec![0.0f32; 16_000 * 3];
+        phrase_at(&mut phrase, 1.0);
+        fs::write(
+            dir.path().join("phrase.wav"),
+            encode_wav_bytes(&phrase).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("silence.wav"),
+            encode_wav_bytes(&[0.0; 16_000 * 3]).unwrap(),
+        )
+        .unwrap();
+
+        let mut detector = WakeWordDetector::new(Box::new(LoudTail), 0.5);
+        assert_eq!(
+            detect_in_file(&mut detector, dir.path().join("phrase.wav"))
+                .unwrap()
+                .len(),
+            1
+        );
+        let mut detector = WakeWordDetector::new(Box::new(LoudTail), 0.5);
+        assert!(
+            detect_in_file(&mut detector, dir.path().join("silence.wav"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
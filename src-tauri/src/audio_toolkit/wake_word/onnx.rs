@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use ndarray::Array2;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::TensorRef;
+use std::path::Path;
+
+use super::KeywordSpotter;
+use crate::audio_toolkit::constants;
+
+/// Keyword model loaded from an ONNX file.
+///
+/// The model takes one input of shape `[1, samples]` holding raw 16 kHz audio
+/// and returns the wake phrase probability as its first output, of shape `[1]`
+/// or `[1, 1]`. A fixed `samples` dimension sets the window length; a dynamic
+/// one gets one second of audio.
+pub struct OnnxKeywordSpotter {
+    session: Session,
+    input_name: String,
+    output_name: String,
+    window_samples: usize,
+}
+
+impl OnnxKeywordSpotter {
+    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        // A single thread keeps the always-on spotter from competing with
+        // transcription for cores
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .with_intra_threads(1)?
+            .commit_from_file(model_path.as_ref())
+            .map_err(|e| anyhow!("Failed to load keyword model: {e}"))?;
+
+        let input = session
+            .inputs
+            .first()
+            .ok_or_else(|| anyhow!("Keyword model has no inputs"))?;
+        let window_samples = input
+            .input_type
+            .tensor_shape()
+            .and_then(|shape| shape.last().copied())
+            .filter(|&n| n > 0)
+            .map(|n| n as usize)
+            .unwrap_or(constants::WHISPER_SAMPLE_RATE as usize);
+        let input_name = input.name.clone();
+        let output_name = session
+            .outputs
+            .first()
+            .ok_or_else(|| anyhow!("Keyword model has no outputs"))?
+            .name
+            .clone();
+
+        Ok(Self {
+            session,
+            input_name,
+            output_name,
+            window_samples,
+        })
+    }
+}
+
+impl KeywordSpotter for OnnxKeywordSpotter {
+    fn window_samples(&self) -> usize {
+        self.window_samples
+    }
+
+    fn score(&mut self, window: &[f32]) -> Result<f32> {
+        let audio = Array2::from_shape_vec((1, window.len()), window.to_vec())?;
+        let outputs = self.session.run(ort::inputs![
+            self.input_name.as_str() => TensorRef::from_array_view(audio.view())?,
+        ])?;
+        let scores = outputs
+            .get(self.output_name.as_str())
+            .ok_or_else(|| anyhow!("Keyword model output missing"))?
+            .try_extract_array::<f32>()?;
+        let score = scores
+            .iter()
+            .next()
+            .copied()
+            .ok_or_else(|| anyhow!("Keyword model returned no score"))?;
+        Ok(score.clamp(0.0, 1.0))
+    }
+}
@@ -0,0 +1,110 @@
+//! Command-line client for Handy's control socket.
+//!
+//! ```text
+//! handyctl start|stop|toggle [binding]
+//! handyctl cancel | state | last | subscribe
+//! handyctl transcribe <file> [binding]
+//! ```
+
+#![cfg_attr(not(unix), allow(dead_code, unused_imports))]
+
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::process::ExitCode;
+
+use handy_app_lib::ipc::protocol::{socket_path, Request, Response};
+
+const USAGE: &str = "usage: handyctl <start|stop|toggle> [binding]
+       handyctl <cancel|state|last|subscribe>
+       handyctl transcribe <file> [binding]";
+
+fn parse(args: &[String]) -> Option<Request> {
+    let binding = |i: usize| args.get(i).cloned();
+    let request = match args.first()?.as_str() {
+        "start" => Request::Start {
+            binding: binding(1),
+        },
+        "stop" => Request::Stop {
+            binding: binding(1),
+        },
+        "toggle" => Request::Toggle {
+            binding: binding(1),
+        },
+        "cancel" => Request::Cancel,
+        "state" => Request::State,
+        "last" => Request::LastEntry,
+        "subscribe" => Request::Subscribe,
+        "transcribe" => Request::TranscribeFile {
+            // The app has its own working directory
+            path: args
+                .get(1)
+                .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.into()))?,
+            binding: binding(2),
+        },
+        _ => return None,
+    };
+    Some(request)
+}
+
+#[cfg(unix)]
+fn run(request: &Request) -> Result<(), String> {
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path).map_err(|e| {
+        format!(
+            "Cannot connect to {} (is Handy running?): {}",
+            path.display(),
+            e
+        )
+    })?;
+    let mut line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut lines = BufReader::new(stream).lines();
+    let reply = lines
+        .next()
+        .ok_or("Connection closed without a reply")?
+        .map_err(|e| e.to_string())?;
+    let response: Response = serde_json::from_str(&reply).map_err(|e| e.to_string())?;
+    if !response.ok {
+        return Err(response
+            .error
+            .unwrap_or_else(|| "Request failed".to_string()));
+    }
+
+    match response.data {
+        Some(serde_json::Value::String(text)) => println!("{}", text),
+        Some(data) => println!("{}", data),
+        None => {}
+    }
+    if *request == Request::Subscribe {
+        // One event per line, as sent by the app
+        for event in lines {
+            println!("{}", event.map_err(|e| e.to_string())?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn run(_request: &Request) -> Result<(), String> {
+    Err("The control socket is only available on macOS and Linux".to_string())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(request) = parse(&args) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    match run(&request) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("handyctl: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
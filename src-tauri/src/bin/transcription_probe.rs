@@ -1,7 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
+use handy_app_lib::audio_toolkit::{read_recording_samples, AudioCodec};
 use transcribe_rs::{
-    audio::read_wav_samples,
     engines::{
         moonshine::{ModelVariant, MoonshineEngine, MoonshineModelParams},
         whisper::{WhisperEngine, WhisperInferenceParams, WhisperModelParams},
@@ -42,12 +42,8 @@ fn model_path(name: &str) -> PathBuf {
         .join(name)
 }
 
-fn wav_duration_seconds(path: &Path) -> Result<f32, AnyError> {
-    let reader = hound::WavReader::open(path)?;
-    let spec = reader.spec();
-    let samples = reader.duration();
-    let secs = samples as f32 / spec.sample_rate as f32;
-    Ok(secs)
+fn duration_seconds(samples: &[f32]) -> f32 {
+    samples.len() as f32 / WHISPER_SAMPLE_RATE as f32
 }
 
 fn short_text(s: &str, max_chars: usize) -> String {
@@ -163,22 +159,27 @@ fn transcribe_whisper_resilient(
 
 fn transcribe_moonshine(
     engine: &mut MoonshineEngine,
-    wav: &Path,
+    audio: Vec<f32>,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let audio = read_wav_samples(wav)?;
     let result = engine.transcribe_samples(audio, None)?;
     Ok(result.text)
 }
 
 fn main() -> Result<(), AnyError> {
-    let mut wavs: Vec<PathBuf> = std::fs::read_dir(recordings_dir())?
+    // Encrypted recordings (`*.enc`) are skipped; they need the app's key
+    let mut recordings: Vec<PathBuf> = std::fs::read_dir(recordings_dir())?
         .filter_map(|entry| entry.ok().map(|e| e.path()))
-        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("wav"))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(AudioCodec::from_file_name)
+                .is_some()
+        })
         .collect();
 
-    wavs.sort();
+    recordings.sort();
 
-    if wavs.is_empty() {
+    if recordings.is_empty() {
         println!("No recordings found.");
         return Ok(());
     }
@@ -198,13 +199,13 @@ fn main() -> Result<(), AnyError> {
     )?;
 
     println!("\n=== Transcription Probe ===");
-    for wav in wavs {
-        let duration = wav_duration_seconds(&wav).unwrap_or(0.0);
-        let name = wav
+    for recording in recordings {
+        let name = recording
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("<unknown>");
-        let audio = read_wav_samples(&wav)?;
+        let audio = read_recording_samples(&recording)?;
+        let duration = duration_seconds(&audio);
         let whisper_params = WhisperInferenceParams {
             language: Some("dv".to_string()),
             translate: false,
@@ -231,7 +232,7 @@ fn main() -> Result<(), AnyError> {
             Err(e) => println!("  whisper-small-dv (resilient): ERR {}", e),
         }
 
-        match transcribe_moonshine(&mut moonshine, &wav) {
+        match transcribe_moonshine(&mut moonshine, audio) {
             Ok(text) => println!(
                 "  moonshine-base:               OK len={} text='{}'",
                 text.chars().count(),
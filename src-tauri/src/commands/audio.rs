@@ -1,7 +1,11 @@
 use crate::audio_feedback;
-use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
-use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
-use crate::settings::{get_settings, write_settings};
+use crate::audio_toolkit::audio::{list_input_devices, list_loopback_sources, list_output_devices};
+use crate::audio_toolkit::wake_word::OnnxKeywordSpotter;
+use crate::audio_toolkit::AudioRecorder;
+use crate::ducking::DuckingStatus;
+use crate::managers::audio::AudioRecordingManager;
+use crate::settings::{get_settings, write_settings, VadParams, VadPreset};
+use crate::transcription_coordinator::sync_microphone_mode;
 use log::warn;
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -39,6 +43,14 @@ pub struct AudioDevice {
     pub is_default: bool,
 }
 
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct DspStageStats {
+    pub name: String,
+    pub latency_ms: f64,
+    pub avg_micros: f64,
+    pub max_micros: f64,
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn update_microphone_mode(app: AppHandle, always_on: bool) -> Result<(), String> {
@@ -47,16 +59,9 @@ pub fn update_microphone_mode(app: AppHandle, always_on: bool) -> Result<(), Str
     settings.always_on_microphone = always_on;
     write_settings(&app, settings);
 
-    // Update the audio manager mode
-    let rm = app.state::<Arc<AudioRecordingManager>>();
-    let new_mode = if always_on {
-        MicrophoneMode::AlwaysOn
-    } else {
-        MicrophoneMode::OnDemand
-    };
-
-    rm.update_mode(new_mode)
-        .map_err(|e| format!("Failed to update microphone mode: {}", e))
+    // Update the audio manager mode; hands-free and the wake word may still
+    // need the stream open
+    sync_microphone_mode(&app).map_err(|e| format!("Failed to update microphone mode: {}", e))
 }
 
 #[tauri::command]
@@ -66,6 +71,36 @@ pub fn get_microphone_mode(app: AppHandle) -> Result<bool, String> {
     Ok(settings.always_on_microphone)
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_settings(
+    app: AppHandle,
+    preset: VadPreset,
+    custom_params: Option<VadParams>,
+) -> Result<(), String> {
+    if let Some(params) = &custom_params {
+        params.validate()?;
+    }
+
+    let mut settings = get_settings(&app);
+    settings.vad_preset = preset;
+    if let Some(params) = custom_params {
+        settings.vad_custom_params = params;
+    }
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_vad_settings()
+        .map_err(|e| format!("Failed to update VAD settings: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_vad_debug(app: AppHandle, enabled: bool) {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.set_vad_debug(enabled);
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_available_microphones() -> Result<Vec<AudioDevice>, String> {
@@ -115,6 +150,100 @@ pub fn get_selected_microphone(app: AppHandle) -> Result<String, String> {
         .unwrap_or_else(|| "default".to_string()))
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn get_microphone_channel_count(device_name: String) -> Result<u16, String> {
+    let device = list_input_devices()
+        .map_err(|e| format!("Failed to list audio devices: {}", e))?
+        .into_iter()
+        .find(|d| d.name == device_name)
+        .ok_or_else(|| format!("Microphone not found: {}", device_name))?;
+    AudioRecorder::channel_count(&device.device)
+        .map_err(|e| format!("Failed to read channel count: {}", e))
+}
+
+/// Channels are numbered from zero; an empty list averages every channel.
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_channels(
+    app: AppHandle,
+    device_name: String,
+    channels: Vec<u16>,
+) -> Result<(), String> {
+    if let Ok(count) = get_microphone_channel_count(device_name.clone()) {
+        if let Some(c) = channels.iter().find(|&&c| c >= count) {
+            return Err(format!(
+                "{} has {} channels, there is no channel {}",
+                device_name, count, c
+            ));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    if channels.is_empty() {
+        settings.microphone_channels.remove(&device_name);
+    } else {
+        settings
+            .microphone_channels
+            .insert(device_name.clone(), channels);
+    }
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    if rm.active_device().as_deref() == Some(device_name.as_str()) {
+        rm.reload_active_device();
+    }
+    Ok(())
+}
+
+/// Monitor sources that play back what the machine outputs. Only PulseAudio
+/// and PipeWire on Linux provide them; elsewhere the list is empty.
+#[tauri::command]
+#[specta::specta]
+pub fn get_available_loopback_sources() -> Vec<AudioDevice> {
+    list_loopback_sources()
+        .into_iter()
+        .map(|s| AudioDevice {
+            index: s.name,
+            name: s.description,
+            is_default: false,
+        })
+        .collect()
+}
+
+/// Capture system audio from `source_name`, mixed with the microphone or in
+/// its place. `null` goes back to the microphone alone.
+#[tauri::command]
+#[specta::specta]
+pub fn set_loopback_source(
+    app: AppHandle,
+    source_name: Option<String>,
+    mix_with_microphone: bool,
+) -> Result<(), String> {
+    if let Some(name) = &source_name {
+        if !list_loopback_sources().iter().any(|s| &s.name == name) {
+            return Err(format!("System audio source not found: {}", name));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.loopback_source = source_name;
+    settings.loopback_mix_microphone = mix_with_microphone;
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_loopback();
+    Ok(())
+}
+
+/// Whether other audio is currently lowered, and why the last attempt failed
+/// if it did.
+#[tauri::command]
+#[specta::specta]
+pub fn get_ducking_status(app: AppHandle) -> DuckingStatus {
+    app.state::<Arc<AudioRecordingManager>>().ducking_status()
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_available_output_devices() -> Result<Vec<AudioDevice>, String> {
@@ -194,6 +323,26 @@ pub fn get_clamshell_microphone(app: AppHandle) -> Result<String, String> {
         .unwrap_or_else(|| "default".to_string()))
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_priority(app: AppHandle, devices: Vec<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.microphone_priority = devices;
+    write_settings(&app, settings);
+
+    // Return to a better-ranked device right away if one is plugged in
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.check_devices();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_active_microphone(app: AppHandle) -> Option<String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.active_device()
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn is_recording(app: AppHandle) -> bool {
@@ -211,6 +360,149 @@ pub fn start_recording(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_hands_free(app: AppHandle, armed: bool) {
+    use crate::TranscriptionCoordinator;
+    let coordinator = app.state::<TranscriptionCoordinator>();
+    coordinator.set_hands_free(armed);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn is_hands_free_armed(app: AppHandle) -> bool {
+    use crate::TranscriptionCoordinator;
+    let coordinator = app.state::<TranscriptionCoordinator>();
+    coordinator.is_hands_free_armed()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_hands_free_settings(
+    app: AppHandle,
+    silence_ms: u32,
+    max_utterance_secs: u32,
+) -> Result<(), String> {
+    if !(200..=10_000).contains(&silence_ms) {
+        return Err("Silence timeout must be between 200 ms and 10 s".to_string());
+    }
+    if !(1..=600).contains(&max_utterance_secs) {
+        return Err("Maximum utterance length must be between 1 s and 10 min".to_string());
+    }
+    let mut settings = get_settings(&app);
+    settings.hands_free_silence_ms = silence_ms;
+    settings.hands_free_max_utterance_secs = max_utterance_secs;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_wake_word_settings(
+    app: AppHandle,
+    enabled: bool,
+    model_path: Option<String>,
+    threshold: f32,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err("Wake word threshold must be between 0.0 and 1.0".to_string());
+    }
+    if let Some(path) = &model_path {
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("Wake word model not found: {}", path));
+        }
+    }
+    if enabled {
+        // Load it once here so a bad model is reported instead of leaving
+        // the microphone open with nothing listening
+        let path = model_path
+            .as_deref()
+            .ok_or("Choose a wake word model (.onnx) before turning the wake word on")?;
+        OnnxKeywordSpotter::new(path)
+            .map_err(|e| format!("Cannot use wake word model {}: {}", path, e))?;
+    }
+
+    let mut settings = get_settings(&app);
+    settings.wake_word_enabled = enabled;
+    settings.wake_word_model = model_path;
+    settings.wake_word_threshold = threshold;
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_wake_word();
+    sync_microphone_mode(&app).map_err(|e| format!("Failed to update microphone mode: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_dsp_settings(
+    app: AppHandle,
+    high_pass: bool,
+    denoise: bool,
+    agc: bool,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.dsp_high_pass = high_pass;
+    settings.dsp_denoise = denoise;
+    settings.dsp_agc = agc;
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_dsp();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_pre_roll(app: AppHandle, pre_roll_ms: u32) -> Result<(), String> {
+    if pre_roll_ms > 2000 {
+        return Err("Pre-roll must be at most 2000 ms".to_string());
+    }
+
+    let mut settings = get_settings(&app);
+    settings.pre_roll_ms = pre_roll_ms;
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_pre_roll();
+    Ok(())
+}
+
+/// Zero disables a limit. Applies to recordings started afterwards.
+#[tauri::command]
+#[specta::specta]
+pub fn change_recording_limits(app: AppHandle, max_secs: u32, max_mb: u32) -> Result<(), String> {
+    if max_secs > 4 * 60 * 60 {
+        return Err("Maximum recording length must be at most 4 hours".to_string());
+    }
+    if max_mb > 4096 {
+        return Err("Recording memory cap must be at most 4096 MB".to_string());
+    }
+
+    let mut settings = get_settings(&app);
+    settings.max_recording_secs = max_secs;
+    settings.max_recording_mb = max_mb;
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_dsp_stats(app: AppHandle) -> Result<Vec<DspStageStats>, String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    let stats = rm
+        .dsp_stats()
+        .into_iter()
+        .map(|s| DspStageStats {
+            name: s.name.to_string(),
+            latency_ms: s.latency.as_secs_f64() * 1_000.0,
+            avg_micros: s.average().as_secs_f64() * 1_000_000.0,
+            max_micros: s.max.as_secs_f64() * 1_000_000.0,
+        })
+        .collect();
+    Ok(stats)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn stop_recording(app: AppHandle) -> Result<(), String> {
@@ -1,4 +1,6 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::history::{HistoryEncryptionStatus, HistoryEntry, HistoryManager};
+use crate::managers::usage_stats::{UsageRange, UsageStats};
+use crate::settings::{HistoryKeySource, RecordingCodec};
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -34,7 +36,9 @@ pub async fn get_audio_file_path(
     history_manager: State<'_, Arc<HistoryManager>>,
     file_name: String,
 ) -> Result<String, String> {
-    let path = history_manager.get_audio_file_path(&file_name);
+    let path = history_manager
+        .get_playable_audio_path(&file_name)
+        .map_err(|e| e.to_string())?;
     path.to_str()
         .ok_or_else(|| "Invalid file path".to_string())
         .map(|s| s.to_string())
@@ -99,3 +103,80 @@ pub async fn update_recording_retention_period(
 
     Ok(())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_stats(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    range: UsageRange,
+) -> Result<UsageStats, String> {
+    history_manager
+        .get_usage_stats(range)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_recording_codec(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    codec: RecordingCodec,
+) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.recording_codec = codec;
+    crate::settings::write_settings(&app, settings);
+
+    // Existing WAVs are converted in the background; new recordings use the
+    // codec straight away
+    history_manager.inner().start_recording_conversion();
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_encryption_status(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<HistoryEncryptionStatus, String> {
+    Ok(history_manager.encryption_status())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn enable_history_encryption(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    key_source: HistoryKeySource,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    history_manager
+        .enable_encryption(key_source, passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn disable_history_encryption(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<(), String> {
+    history_manager
+        .disable_encryption()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_history(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    passphrase: String,
+) -> Result<(), String> {
+    history_manager
+        .unlock_encryption(&passphrase)
+        .map_err(|e| e.to_string())
+}
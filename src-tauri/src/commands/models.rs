@@ -1,6 +1,12 @@
 use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::model_benchmark;
+use crate::managers::model_download::MAX_DOWNLOAD_CONNECTIONS;
+use crate::managers::model_import::ModelImportPreview;
+use crate::managers::model_integrity::ModelVerification;
+use crate::managers::model_storage::ModelsDiskReport;
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings, AppSettings, PasteMethod};
+use std::path::Path;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
 
@@ -179,3 +185,187 @@ pub async fn cancel_download(
         .cancel_download(&model_id)
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_models(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<ModelVerification>, String> {
+    // Hashing several hundred MB per model is slow, keep it off the async workers
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || model_manager.verify_models())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn repair_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    model_manager
+        .repair_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_model_manifest_source(app: AppHandle, source: Option<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.model_manifest_source = source
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn refresh_model_manifest(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<ModelInfo>, String> {
+    let source = get_settings(&app_handle)
+        .model_manifest_source
+        .ok_or_else(|| "No model manifest source is configured".to_string())?;
+    model_manager
+        .refresh_manifest(&source)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn inspect_model_import(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+) -> Result<ModelImportPreview, String> {
+    model_manager
+        .inspect_import(Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn import_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    name: Option<String>,
+    languages: Option<Vec<String>>,
+) -> Result<ModelInfo, String> {
+    // Copying a model can take a while, keep it off the async workers
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.import_model(Path::new(&path), name, languages)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_download_connections_setting(app: AppHandle, connections: u8) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.download_connections = connections.clamp(1, MAX_DOWNLOAD_CONNECTIONS);
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_download_speed_limit_setting(
+    app: AppHandle,
+    limit_kbps: Option<u32>,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.download_speed_limit_kbps = limit_kbps.filter(|&kbps| kbps > 0);
+    write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn install_model_from_file(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    model_id: Option<String>,
+) -> Result<ModelInfo, String> {
+    // Copying, extracting and hashing a model is slow, keep it off the async workers
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.install_from_file(Path::new(&path), model_id.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn benchmark_models(
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    clips_dir: String,
+) -> Result<Vec<ModelInfo>, String> {
+    let clips = model_benchmark::load_clip_set(Path::new(&clips_dir)).map_err(|e| e.to_string())?;
+
+    // Transcribing every clip with every model takes minutes, keep it off the async workers
+    let transcription_manager = transcription_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || transcription_manager.benchmark_models(&clips))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn models_disk_report(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+) -> Result<ModelsDiskReport, String> {
+    let in_use = transcription_manager.loaded_models();
+    let model_manager = model_manager.inner().clone();
+    // Sizing extracted model trees walks many files, keep it off the async workers
+    tauri::async_runtime::spawn_blocking(move || model_manager.disk_report(&in_use))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cleanup_models_dir(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    names: Option<Vec<String>>,
+) -> Result<u64, String> {
+    let in_use = transcription_manager.loaded_models();
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.cleanup_models_dir(names.as_deref(), &in_use)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_models_directory(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: Option<String>,
+) -> Result<String, String> {
+    // Moving models to another drive copies gigabytes, keep it off the async workers
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager
+            .set_models_dir(path.as_deref().map(Path::new))
+            .map(|dir| dir.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -2,6 +2,7 @@ use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings, ModelUnloadTimeout};
 use serde::Serialize;
 use specta::Type;
+use std::sync::Arc;
 use tauri::{AppHandle, State};
 
 #[derive(Serialize, Type)]
@@ -18,6 +19,25 @@ pub fn set_model_unload_timeout(app: AppHandle, timeout: ModelUnloadTimeout) {
     write_settings(&app, settings);
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_model_pool_budget(
+    app: AppHandle,
+    transcription_manager: State<Arc<TranscriptionManager>>,
+    budget_mb: u32,
+) {
+    let mut settings = get_settings(&app);
+    settings.model_pool_budget_mb = budget_mb;
+    write_settings(&app, settings);
+    transcription_manager.set_pool_budget(budget_mb as u64);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_loaded_models(transcription_manager: State<Arc<TranscriptionManager>>) -> Vec<String> {
+    transcription_manager.loaded_models()
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_model_load_status(
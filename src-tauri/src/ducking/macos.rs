@@ -0,0 +1,50 @@
+//! macOS has no public per-application volume, so the system output volume
+//! is lowered instead. The start sound has finished by the time this runs.
+
+use super::{scaled, Backend, SavedLevel};
+use std::process::Command;
+
+pub struct OutputVolumeBackend;
+
+fn osascript(script: &str) -> Result<String, String> {
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+impl Backend for OutputVolumeBackend {
+    fn name(&self) -> &'static str {
+        "macos-output"
+    }
+
+    fn read_levels(&mut self) -> Result<Vec<SavedLevel>, String> {
+        let volume = osascript("output volume of (get volume settings)")?
+            .parse::<f64>()
+            // "missing value" for outputs without a volume control
+            .map_err(|_| "The output device has no volume control".to_string())?;
+        Ok(vec![SavedLevel {
+            stream: 0,
+            pid: None,
+            volume: vec![volume],
+        }])
+    }
+
+    fn apply_levels(&mut self, levels: &[SavedLevel], factor: f64) -> Result<(), String> {
+        let Some(volume) = levels
+            .first()
+            .and_then(|l| scaled(&l.volume, factor).first().copied())
+        else {
+            return Ok(());
+        };
+        osascript(&format!(
+            "set volume output volume {}",
+            volume.round().min(100.0)
+        ))
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,459 @@
+//! Lowering other applications' audio while recording.
+//!
+//! Levels are read first and written to a journal in the app data directory
+//! before anything is changed, so a crash mid-recording is undone on the next
+//! start instead of leaving the user's music at a fraction of its volume.
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "linux")]
+mod pulse;
+#[cfg(target_os = "windows")]
+mod wasapi;
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+const JOURNAL_FILE: &str = "ducking.json";
+
+/// Level of one stream before it was ducked.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedLevel {
+    /// Backend-specific stream id
+    pub stream: u32,
+    /// Owning process, checked on restore so a reused id is left alone
+    pub pid: Option<u32>,
+    /// Per-channel volume in the backend's own scale
+    pub volume: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Journal {
+    backend: String,
+    levels: Vec<SavedLevel>,
+}
+
+/// Outcome of the last duck or restore, for the settings page and the
+/// `ducking-changed` event.
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct DuckingStatus {
+    pub active: bool,
+    pub backend: Option<String>,
+    pub streams: u32,
+    pub error: Option<String>,
+}
+
+trait Backend {
+    fn name(&self) -> &'static str;
+    /// Current levels of every stream except our own.
+    fn read_levels(&mut self) -> Result<Vec<SavedLevel>, String>;
+    /// Set each stream to `factor` times its saved level. Streams that have
+    /// gone away are skipped.
+    fn apply_levels(&mut self, levels: &[SavedLevel], factor: f64) -> Result<(), String>;
+}
+
+fn connect() -> Result<Box<dyn Backend>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        pulse::PulseBackend::connect().map(|b| Box::new(b) as Box<dyn Backend>)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        wasapi::SessionBackend::connect().map(|b| Box::new(b) as Box<dyn Backend>)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Ok(Box::new(macos::OutputVolumeBackend))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        Err("Ducking is not supported on this platform".to_string())
+    }
+}
+
+/// Scale a saved level, never below silence.
+fn scaled(volume: &[f64], factor: f64) -> Vec<f64> {
+    volume.iter().map(|v| (v * factor).max(0.0)).collect()
+}
+
+/// Saved levels and their on-disk journal, kept apart from the app handle so
+/// the bookkeeping can be exercised against a fake backend.
+struct DuckState {
+    journal: Option<PathBuf>,
+    /// Levels to put back while ducked
+    saved: Option<Vec<SavedLevel>>,
+}
+
+impl DuckState {
+    fn read_journal(&self) -> Option<Journal> {
+        let data = std::fs::read(self.journal.as_ref()?).ok()?;
+        match serde_json::from_slice::<Journal>(&data) {
+            Ok(journal) => Some(journal),
+            Err(e) => {
+                warn!("Ignoring unreadable ducking journal: {}", e);
+                None
+            }
+        }
+    }
+
+    fn write_journal(&self, backend: &str, levels: &[SavedLevel]) -> Result<(), String> {
+        let Some(path) = &self.journal else {
+            return Err("No app data directory for the ducking journal".to_string());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let journal = Journal {
+            backend: backend.to_string(),
+            levels: levels.to_vec(),
+        };
+        let data = serde_json::to_vec(&journal).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| format!("Failed to write ducking journal: {}", e))
+    }
+
+    fn clear_journal(&self) {
+        if let Some(path) = &self.journal {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Put back levels a previous run left ducked. The journal is only
+    /// dropped once that worked, so a failed attempt is retried by the next
+    /// duck or start.
+    fn recover(&mut self, backend: &mut dyn Backend) -> Result<usize, String> {
+        let Some(journal) = self.read_journal() else {
+            self.clear_journal();
+            return Ok(0);
+        };
+        if journal.backend != backend.name() {
+            warn!(
+                "Ducking journal is for {}, now using {}",
+                journal.backend,
+                backend.name()
+            );
+            self.clear_journal();
+            return Ok(0);
+        }
+        backend.apply_levels(&journal.levels, 1.0)?;
+        self.clear_journal();
+        Ok(journal.levels.len())
+    }
+
+    fn duck(&mut self, backend: &mut dyn Backend, factor: f64) -> Result<usize, String> {
+        if self.saved.is_some() {
+            return Ok(0);
+        }
+
+        // A journal left by a failed restore or recovery holds the real
+        // levels; the streams now read as ducked, so those readings only
+        // count for streams the journal does not know
+        let mut levels = self
+            .read_journal()
+            .filter(|journal| journal.backend == backend.name())
+            .map(|journal| journal.levels)
+            .unwrap_or_default();
+        for current in backend.read_levels()? {
+            if !levels.iter().any(|l| l.stream == current.stream) {
+                levels.push(current);
+            }
+        }
+
+        self.write_journal(backend.name(), &levels)?;
+        if let Err(e) = backend.apply_levels(&levels, factor) {
+            // Some streams may have changed; put back what we can
+            if backend.apply_levels(&levels, 1.0).is_ok() {
+                self.clear_journal();
+            }
+            return Err(e);
+        }
+        let count = levels.len();
+        self.saved = Some(levels);
+        Ok(count)
+    }
+
+    fn restore(&mut self, backend: &mut dyn Backend) -> Result<usize, String> {
+        let Some(levels) = self.saved.take() else {
+            return Ok(0);
+        };
+        if let Err(e) = backend.apply_levels(&levels, 1.0) {
+            // Still ducked: keep the originals for the next restore, and the
+            // journal for the next start
+            self.saved = Some(levels);
+            return Err(e);
+        }
+        self.clear_journal();
+        Ok(levels.len())
+    }
+}
+
+pub struct Ducker {
+    app: AppHandle,
+    state: Mutex<DuckState>,
+    status: Mutex<DuckingStatus>,
+}
+
+impl Ducker {
+    /// Undo anything a previous run left ducked.
+    pub fn new(app: &AppHandle) -> Self {
+        let journal = app
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(JOURNAL_FILE));
+        let ducker = Self {
+            app: app.clone(),
+            state: Mutex::new(DuckState {
+                journal,
+                saved: None,
+            }),
+            status: Mutex::new(DuckingStatus::default()),
+        };
+        ducker.recover();
+        ducker
+    }
+
+    fn recover(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.read_journal().is_none() {
+            return;
+        }
+        match connect().and_then(|mut backend| state.recover(backend.as_mut())) {
+            Ok(streams) => info!("Restored {} streams ducked by a previous run", streams),
+            Err(e) => warn!("Failed to restore streams ducked by a previous run: {}", e),
+        }
+    }
+
+    fn set_status(&self, status: DuckingStatus) {
+        *self.status.lock().unwrap() = status.clone();
+        let _ = self.app.emit("ducking-changed", status);
+    }
+
+    pub fn status(&self) -> DuckingStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Lower other streams to `percent` of their level. Does nothing if
+    /// already ducked.
+    pub fn duck(&self, percent: u8) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if state.saved.is_some() {
+            return Ok(());
+        }
+
+        let factor = percent.min(100) as f64 / 100.0;
+        let result = connect().and_then(|mut backend| {
+            let streams = state.duck(backend.as_mut(), factor)?;
+            Ok((backend.name(), streams))
+        });
+
+        match result {
+            Ok((backend, streams)) => {
+                debug!("Ducked {} streams to {}% via {}", streams, percent, backend);
+                self.set_status(DuckingStatus {
+                    active: true,
+                    backend: Some(backend.to_string()),
+                    streams: streams as u32,
+                    error: None,
+                });
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to duck audio: {}", e);
+                self.set_status(DuckingStatus {
+                    error: Some(e.clone()),
+                    ..Default::default()
+                });
+                Err(e)
+            }
+        }
+    }
+
+    /// Put ducked streams back to their saved level.
+    pub fn restore(&self) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if state.saved.is_none() {
+            return Ok(());
+        }
+
+        let result = connect().and_then(|mut backend| {
+            let streams = state.restore(backend.as_mut())?;
+            Ok((backend.name(), streams))
+        });
+        match result {
+            Ok((backend, streams)) => {
+                debug!("Restored {} ducked streams", streams);
+                self.set_status(DuckingStatus {
+                    backend: Some(backend.to_string()),
+                    ..Default::default()
+                });
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to restore ducked audio: {}", e);
+                self.set_status(DuckingStatus {
+                    active: true,
+                    error: Some(e.clone()),
+                    ..Default::default()
+                });
+                Err(e)
+            }
+        }
+    }
+
+    pub fn is_ducked(&self) -> bool {
+        self.state.lock().unwrap().saved.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    /// In-memory mixer: stream id to per-channel volume.
+    #[derive(Default)]
+    struct FakeBackend {
+        streams: HashMap<u32, Vec<f64>>,
+        fail_apply: bool,
+    }
+
+    impl FakeBackend {
+        fn with_streams(streams: &[(u32, f64)]) -> Self {
+            Self {
+                streams: streams.iter().map(|&(id, v)| (id, vec![v, v])).collect(),
+                fail_apply: false,
+            }
+        }
+
+        fn level(&self, stream: u32) -> f64 {
+            self.streams[&stream][0]
+        }
+    }
+
+    impl Backend for FakeBackend {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn read_levels(&mut self) -> Result<Vec<SavedLevel>, String> {
+            let mut levels: Vec<_> = self
+                .streams
+                .iter()
+                .map(|(&stream, volume)| SavedLevel {
+                    stream,
+                    pid: None,
+                    volume: volume.clone(),
+                })
+                .collect();
+            levels.sort_by_key(|l| l.stream);
+            Ok(levels)
+        }
+
+        fn apply_levels(&mut self, levels: &[SavedLevel], factor: f64) -> Result<(), String> {
+            if self.fail_apply {
+                return Err("mixer unavailable".to_string());
+            }
+            for level in levels {
+                if let Some(volume) = self.streams.get_mut(&level.stream) {
+                    *volume = scaled(&level.volume, factor);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn state_in(dir: &TempDir) -> DuckState {
+        DuckState {
+            journal: Some(dir.path().join(JOURNAL_FILE)),
+            saved: None,
+        }
+    }
+
+    #[test]
+    fn scales_levels_without_going_negative() {
+        assert_eq!(scaled(&[65536.0, 32768.0], 0.25), vec![16384.0, 8192.0]);
+        assert_eq!(scaled(&[0.5], 0.0), vec![0.0]);
+        assert_eq!(scaled(&[-1.0, 40.0], 1.0), vec![0.0, 40.0]);
+    }
+
+    #[test]
+    fn duck_writes_journal_before_lowering_and_restore_clears_it() {
+        let dir = TempDir::new().unwrap();
+        let mut state = state_in(&dir);
+        let mut backend = FakeBackend::with_streams(&[(1, 1.0), (2, 0.5)]);
+
+        assert_eq!(state.duck(&mut backend, 0.2).unwrap(), 2);
+        assert!((backend.level(1) - 0.2).abs() < 1e-9);
+        let journal = state.read_journal().unwrap();
+        assert_eq!(journal.backend, "fake");
+        assert_eq!(journal.levels.len(), 2);
+        assert!(journal
+            .levels
+            .iter()
+            .any(|l| l.stream == 2 && l.volume == vec![0.5, 0.5]));
+
+        assert_eq!(state.restore(&mut backend).unwrap(), 2);
+        assert_eq!(backend.level(1), 1.0);
+        assert_eq!(backend.level(2), 0.5);
+        assert!(state.read_journal().is_none());
+    }
+
+    #[test]
+    fn recovers_levels_after_a_crash() {
+        let dir = TempDir::new().unwrap();
+        let mut backend = FakeBackend::with_streams(&[(1, 0.8)]);
+        {
+            let mut crashed = state_in(&dir);
+            crashed.duck(&mut backend, 0.25).unwrap();
+            // Dropped without restoring, as if the app had died
+        }
+        assert!((backend.level(1) - 0.2).abs() < 1e-9);
+
+        let mut state = state_in(&dir);
+        assert_eq!(state.recover(&mut backend).unwrap(), 1);
+        assert!((backend.level(1) - 0.8).abs() < 1e-9);
+        assert!(state.read_journal().is_none());
+    }
+
+    #[test]
+    fn failed_restore_keeps_the_original_levels() {
+        let dir = TempDir::new().unwrap();
+        let mut state = state_in(&dir);
+        let mut backend = FakeBackend::with_streams(&[(1, 1.0)]);
+        state.duck(&mut backend, 0.1).unwrap();
+
+        backend.fail_apply = true;
+        assert!(state.restore(&mut backend).is_err());
+        assert!(state.saved.is_some());
+        assert!(state.read_journal().is_some());
+
+        // Ducking again must not capture the lowered level as the original
+        backend.fail_apply = false;
+        assert_eq!(state.duck(&mut backend, 0.1).unwrap(), 0);
+        state.restore(&mut backend).unwrap();
+        assert_eq!(backend.level(1), 1.0);
+    }
+
+    #[test]
+    fn duck_reuses_a_leftover_journal() {
+        let dir = TempDir::new().unwrap();
+        let mut backend = FakeBackend::with_streams(&[(1, 1.0)]);
+        {
+            let mut crashed = state_in(&dir);
+            crashed.duck(&mut backend, 0.5).unwrap();
+        }
+        // Recovery failed at start, and a new stream appeared since
+        backend.streams.insert(2, vec![0.6, 0.6]);
+
+        let mut state = state_in(&dir);
+        assert_eq!(state.duck(&mut backend, 0.5).unwrap(), 2);
+        state.restore(&mut backend).unwrap();
+        assert_eq!(backend.level(1), 1.0);
+        assert!((backend.level(2) - 0.6).abs() < 1e-9);
+    }
+}
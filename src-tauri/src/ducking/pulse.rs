@@ -0,0 +1,140 @@
+//! Per-stream ducking through the PulseAudio protocol, which PipeWire also
+//! serves through pipewire-pulse.
+
+use super::{scaled, Backend, SavedLevel};
+use libpulse_binding::{
+    callbacks::ListResult,
+    context::{Context, FlagSet, State},
+    mainloop::standard::{IterateResult, Mainloop},
+    operation,
+    proplist::properties,
+    volume::{ChannelVolumes, Volume},
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+pub struct PulseBackend {
+    mainloop: Mainloop,
+    context: Context,
+}
+
+impl PulseBackend {
+    pub fn connect() -> Result<Self, String> {
+        let mut mainloop = Mainloop::new().ok_or("Failed to create PulseAudio main loop")?;
+        let mut context =
+            Context::new(&mainloop, "Handy").ok_or("Failed to create PulseAudio context")?;
+        context
+            .connect(None, FlagSet::NOAUTOSPAWN, None)
+            .map_err(|e| format!("Failed to connect to PulseAudio: {}", e))?;
+
+        loop {
+            iterate(&mut mainloop)?;
+            match context.get_state() {
+                State::Ready => break,
+                State::Failed | State::Terminated => {
+                    return Err("PulseAudio connection failed".to_string())
+                }
+                _ => {}
+            }
+        }
+        Ok(Self { mainloop, context })
+    }
+
+    fn wait<P: ?Sized>(&mut self, op: operation::Operation<P>) -> Result<(), String> {
+        while op.get_state() == operation::State::Running {
+            iterate(&mut self.mainloop)?;
+        }
+        Ok(())
+    }
+}
+
+fn iterate(mainloop: &mut Mainloop) -> Result<(), String> {
+    match mainloop.iterate(true) {
+        IterateResult::Success(_) => Ok(()),
+        IterateResult::Quit(_) => Err("PulseAudio main loop quit".to_string()),
+        IterateResult::Err(e) => Err(format!("PulseAudio main loop failed: {}", e)),
+    }
+}
+
+impl Backend for PulseBackend {
+    fn name(&self) -> &'static str {
+        "pulseaudio"
+    }
+
+    fn read_levels(&mut self) -> Result<Vec<SavedLevel>, String> {
+        let own_pid = std::process::id();
+        let levels = Rc::new(RefCell::new(Vec::new()));
+        let collected = levels.clone();
+        let op = self
+            .context
+            .introspect()
+            .get_sink_input_info_list(move |result| {
+                let ListResult::Item(info) = result else {
+                    return;
+                };
+                if !info.has_volume || !info.volume_writable {
+                    return;
+                }
+                let pid = info
+                    .proplist
+                    .get_str(properties::APPLICATION_PROCESS_ID)
+                    .and_then(|p| p.parse::<u32>().ok());
+                // Our own feedback sounds stay at full volume
+                if pid == Some(own_pid) {
+                    return;
+                }
+                collected.borrow_mut().push(SavedLevel {
+                    stream: info.index,
+                    pid,
+                    volume: info.volume.get().iter().map(|v| v.0 as f64).collect(),
+                });
+            });
+        self.wait(op)?;
+        Ok(levels.take())
+    }
+
+    fn apply_levels(&mut self, levels: &[SavedLevel], factor: f64) -> Result<(), String> {
+        // Only touch streams that still belong to the same process
+        let current = self.read_levels()?;
+        let failed = Rc::new(Cell::new(0usize));
+        for level in levels {
+            let still_there = current
+                .iter()
+                .any(|c| c.stream == level.stream && c.pid == level.pid);
+            if !still_there || level.volume.is_empty() {
+                continue;
+            }
+
+            let mut volume = ChannelVolumes::default();
+            volume.set_len(level.volume.len() as u8);
+            for (v, saved) in volume
+                .get_mut()
+                .iter_mut()
+                .zip(scaled(&level.volume, factor))
+            {
+                *v = Volume(saved.round() as u32);
+            }
+            let failed = failed.clone();
+            let op = self.context.introspect().set_sink_input_volume(
+                level.stream,
+                &volume,
+                Some(Box::new(move |ok| {
+                    if !ok {
+                        failed.set(failed.get() + 1);
+                    }
+                })),
+            );
+            self.wait(op)?;
+        }
+        match failed.get() {
+            0 => Ok(()),
+            n => Err(format!("PulseAudio rejected the volume of {} streams", n)),
+        }
+    }
+}
+
+impl Drop for PulseBackend {
+    fn drop(&mut self) {
+        self.context.disconnect();
+    }
+}
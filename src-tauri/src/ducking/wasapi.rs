@@ -0,0 +1,105 @@
+//! Per-application ducking through the audio sessions of the default output
+//! device. Sessions are identified by process id.
+
+use super::{scaled, Backend, SavedLevel};
+use windows::core::Interface;
+use windows::Win32::{
+    Media::Audio::{
+        eMultimedia, eRender, IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator,
+        ISimpleAudioVolume, MMDeviceEnumerator,
+    },
+    System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
+};
+
+pub struct SessionBackend {
+    manager: IAudioSessionManager2,
+}
+
+impl SessionBackend {
+    pub fn connect() -> Result<Self, String> {
+        unsafe {
+            // If already initialized (e.g., by Tauri), this does nothing.
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let devices: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                    .map_err(|e| format!("Failed to enumerate audio devices: {}", e))?;
+            let device = devices
+                .GetDefaultAudioEndpoint(eRender, eMultimedia)
+                .map_err(|e| format!("No default output device: {}", e))?;
+            let manager = device
+                .Activate::<IAudioSessionManager2>(CLSCTX_ALL, None)
+                .map_err(|e| format!("Failed to open audio sessions: {}", e))?;
+            Ok(Self { manager })
+        }
+    }
+
+    /// Every session but our own and the system sounds, with its process id.
+    fn sessions(&self) -> Result<Vec<(u32, ISimpleAudioVolume)>, String> {
+        let own_pid = std::process::id();
+        let mut sessions = Vec::new();
+        unsafe {
+            let list = self
+                .manager
+                .GetSessionEnumerator()
+                .map_err(|e| format!("Failed to list audio sessions: {}", e))?;
+            let count = list.GetCount().map_err(|e| e.to_string())?;
+            for i in 0..count {
+                let Ok(control) = list.GetSession(i) else {
+                    continue;
+                };
+                let Ok(control2) = control.cast::<IAudioSessionControl2>() else {
+                    continue;
+                };
+                let pid = control2.GetProcessId().unwrap_or(0);
+                if pid == 0 || pid == own_pid {
+                    continue;
+                }
+                if let Ok(volume) = control.cast::<ISimpleAudioVolume>() {
+                    sessions.push((pid, volume));
+                }
+            }
+        }
+        Ok(sessions)
+    }
+}
+
+impl Backend for SessionBackend {
+    fn name(&self) -> &'static str {
+        "wasapi"
+    }
+
+    fn read_levels(&mut self) -> Result<Vec<SavedLevel>, String> {
+        let mut levels = Vec::new();
+        for (pid, volume) in self.sessions()? {
+            if let Ok(level) = unsafe { volume.GetMasterVolume() } {
+                levels.push(SavedLevel {
+                    stream: pid,
+                    pid: Some(pid),
+                    volume: vec![level as f64],
+                });
+            }
+        }
+        Ok(levels)
+    }
+
+    fn apply_levels(&mut self, levels: &[SavedLevel], factor: f64) -> Result<(), String> {
+        let mut failed = 0;
+        for (pid, volume) in self.sessions()? {
+            let Some(level) = levels.iter().find(|l| l.stream == pid) else {
+                continue;
+            };
+            let Some(&target) = scaled(&level.volume, factor).first() else {
+                continue;
+            };
+            let target = target.min(1.0) as f32;
+            if unsafe { volume.SetMasterVolume(target, std::ptr::null()) }.is_err() {
+                failed += 1;
+            }
+        }
+        match failed {
+            0 => Ok(()),
+            n => Err(format!("Windows rejected the volume of {} sessions", n)),
+        }
+    }
+}
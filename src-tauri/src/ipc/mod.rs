@@ -0,0 +1,276 @@
+//! Local control API for scripts and window-manager bindings. The app
+//! listens on a Unix socket only its user can open; `handyctl` is the
+//! companion client.
+
+pub mod protocol;
+
+#[cfg(unix)]
+pub use server::start;
+
+#[cfg(unix)]
+mod server {
+    use super::protocol::{socket_path, EventMessage, Request, Response};
+    use crate::audio_toolkit::read_recording_samples;
+    use crate::managers::history::HistoryManager;
+    use crate::settings::get_settings;
+    use crate::transcription_coordinator::{is_transcribe_binding, TranscriptionCoordinator};
+    use crate::utils;
+    use log::{debug, error, info, warn};
+    use serde_json::Value;
+    use std::fs::{self, DirBuilder};
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::thread;
+    use tauri::{AppHandle, Listener, Manager};
+
+    /// Stands in for the hotkey string of inputs sent over the socket.
+    const IPC_SOURCE: &str = "ipc";
+
+    /// App events forwarded to subscribers.
+    const FORWARDED_EVENTS: &[&str] = &[
+        "pipeline-state-changed",
+        "recording-progress",
+        "hands-free-changed",
+        "microphone-changed",
+        "microphone-error",
+        "ducking-changed",
+        "transcription-empty",
+        "history-updated",
+        "paste-skipped-none",
+        crate::output::TRANSCRIPT_EVENT,
+    ];
+
+    /// Bind the control socket and serve it on a background thread. A socket
+    /// left behind by a crashed instance is replaced; one that still accepts
+    /// connections belongs to a running instance and is left alone.
+    pub fn start(app: &AppHandle) {
+        let path = socket_path();
+        if let Err(e) = prepare_socket_dir(&path) {
+            error!("Not opening control socket {}: {}", path.display(), e);
+            return;
+        }
+        if let Ok(metadata) = fs::symlink_metadata(&path) {
+            if metadata.uid() != current_uid() {
+                error!(
+                    "Control socket {} belongs to another user; not replacing it",
+                    path.display()
+                );
+                return;
+            }
+            if UnixStream::connect(&path).is_ok() {
+                warn!(
+                    "Control socket {} is in use by another instance",
+                    path.display()
+                );
+                return;
+            }
+            let _ = fs::remove_file(&path);
+        }
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to bind control socket {}: {}", path.display(), e);
+                return;
+            }
+        };
+        if let Err(e) = fs::set_permissions(&path, fs::Permissions::from_mode(0o600)) {
+            error!("Failed to restrict control socket permissions: {}", e);
+            let _ = fs::remove_file(&path);
+            return;
+        }
+        info!("Control socket listening on {}", path.display());
+
+        let app = app.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let app = app.clone();
+                        thread::spawn(move || serve(app, stream));
+                    }
+                    Err(e) => warn!("Control socket accept failed: {}", e),
+                }
+            }
+        });
+    }
+
+    fn current_uid() -> u32 {
+        // SAFETY: getuid has no preconditions and cannot fail
+        unsafe { libc::getuid() }
+    }
+
+    /// Make sure nobody else can reach into the socket's directory, so the
+    /// socket is never exposed before its permissions are tightened and no
+    /// other user can put a file in its place. The directory is created
+    /// private if it does not exist yet.
+    fn prepare_socket_dir(path: &Path) -> io::Result<()> {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+        let metadata = fs::symlink_metadata(dir)?;
+        if !metadata.is_dir() || metadata.uid() != current_uid() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is not a directory owned by this user", dir.display()),
+            ));
+        }
+        if metadata.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is accessible to other users", dir.display()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn serve(app: AppHandle, stream: UnixStream) {
+        let reader = match stream.try_clone() {
+            Ok(s) => BufReader::new(s),
+            Err(e) => {
+                warn!("Failed to clone control connection: {}", e);
+                return;
+            }
+        };
+        let mut writer = stream;
+
+        for line in reader.lines() {
+            let Ok(line) = line else { return };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let request = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => request,
+                Err(e) => {
+                    let response = Response::error(format!("Invalid request: {}", e));
+                    if write_line(&mut writer, &response).is_err() {
+                        return;
+                    }
+                    continue;
+                }
+            };
+            debug!("Control request: {:?}", request);
+
+            if request == Request::Subscribe {
+                if write_line(&mut writer, &Response::ok(None)).is_ok() {
+                    subscribe(&app, &mut writer);
+                }
+                return;
+            }
+
+            let response = match handle(&app, &request) {
+                Ok(data) => Response::ok(data),
+                Err(e) => Response::error(e),
+            };
+            if write_line(&mut writer, &response).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn handle(app: &AppHandle, request: &Request) -> Result<Option<Value>, String> {
+        let coordinator = || {
+            app.try_state::<TranscriptionCoordinator>()
+                .ok_or_else(|| "The app is still starting".to_string())
+        };
+        let binding = request.binding().unwrap_or_default();
+        if !binding.is_empty() {
+            if !get_settings(app).bindings.contains_key(binding) {
+                return Err(format!("Unknown binding '{}'", binding));
+            }
+            // Other bindings (cancel, hands-free, ...) do not record
+            if !is_transcribe_binding(binding) {
+                return Err(format!("'{}' is not a transcription binding", binding));
+            }
+        }
+
+        match request {
+            Request::Start { .. } => coordinator()?.send_input(binding, IPC_SOURCE, true, true),
+            Request::Stop { .. } => coordinator()?.send_input(binding, IPC_SOURCE, false, true),
+            Request::Toggle { .. } => coordinator()?.send_input(binding, IPC_SOURCE, true, false),
+            Request::Cancel => utils::cancel_current_operation(app),
+            Request::State => return to_value(&coordinator()?.state()),
+            Request::TranscribeFile { path, .. } => {
+                let samples = read_recording_samples(path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                let text = coordinator()?.transcribe_file(binding, samples)?;
+                return Ok(Some(Value::String(text)));
+            }
+            Request::LastEntry => {
+                let hm = app.state::<Arc<HistoryManager>>();
+                let entry = hm.get_latest_entry().map_err(|e| e.to_string())?;
+                return to_value(&entry);
+            }
+            Request::Subscribe => unreachable!("subscriptions are handled by serve"),
+        }
+        Ok(None)
+    }
+
+    /// Forward app events to the connection until the client goes away.
+    fn subscribe(app: &AppHandle, writer: &mut UnixStream) {
+        let (tx, rx) = mpsc::channel::<EventMessage>();
+        let ids: Vec<_> = FORWARDED_EVENTS
+            .iter()
+            .map(|name| {
+                let tx = tx.clone();
+                app.listen_any(*name, move |event| {
+                    let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+                    let _ = tx.send(EventMessage {
+                        event: name.to_string(),
+                        payload,
+                    });
+                })
+            })
+            .collect();
+        drop(tx);
+
+        for message in rx {
+            if write_line(writer, &message).is_err() {
+                break;
+            }
+        }
+        for id in ids {
+            app.unlisten(id);
+        }
+    }
+
+    fn to_value<T: serde::Serialize>(value: &T) -> Result<Option<Value>, String> {
+        serde_json::to_value(value)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    fn write_line<T: serde::Serialize>(writer: &mut UnixStream, value: &T) -> io::Result<()> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+        writer.write_all(&line)?;
+        writer.flush()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use tempfile::TempDir;
+
+        #[test]
+        fn socket_directory_must_be_private() {
+            let temp = TempDir::new().unwrap();
+            let created = temp.path().join("handy-test");
+            prepare_socket_dir(&created.join("handy.sock")).unwrap();
+            assert_eq!(fs::metadata(&created).unwrap().mode() & 0o777, 0o700);
+
+            let shared = temp.path().join("shared");
+            fs::create_dir(&shared).unwrap();
+            fs::set_permissions(&shared, fs::Permissions::from_mode(0o777)).unwrap();
+            let err = prepare_socket_dir(&shared.join("handy.sock")).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Wire format of the control socket: one JSON object per line in each
+//! direction. A client sends a [`Request`] and reads one [`Response`]; after
+//! `subscribe` the server instead writes an [`EventMessage`] per app event
+//! until the client disconnects.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Binding used when a request does not name one.
+pub const DEFAULT_BINDING: &str = "transcribe";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// Start recording with a binding, as if its key were pressed and held
+    Start {
+        binding: Option<String>,
+    },
+    /// Stop a recording started with the binding and transcribe it
+    Stop {
+        binding: Option<String>,
+    },
+    /// Start or stop, like a tap of a toggle shortcut
+    Toggle {
+        binding: Option<String>,
+    },
+    Cancel,
+    /// The pipeline stage and the binding being recorded
+    State,
+    /// Transcribe a recording in one of the app's own formats (16 kHz mono
+    /// WAV, FLAC or Opus) with a binding's model and language
+    TranscribeFile {
+        path: PathBuf,
+        binding: Option<String>,
+    },
+    /// The newest history entry
+    LastEntry,
+    Subscribe,
+}
+
+impl Request {
+    /// The binding a request acts on, if it acts on one.
+    pub fn binding(&self) -> Option<&str> {
+        match self {
+            Request::Start { binding }
+            | Request::Stop { binding }
+            | Request::Toggle { binding }
+            | Request::TranscribeFile { binding, .. } => {
+                Some(binding.as_deref().unwrap_or(DEFAULT_BINDING))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    pub fn ok(data: Option<Value>) -> Self {
+        Self {
+            ok: true,
+            data,
+            error: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            data: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventMessage {
+    pub event: String,
+    pub payload: Value,
+}
+
+/// Where the socket lives: `$HANDY_SOCKET` if set, else the user's runtime
+/// directory, else a per-user directory inside the temp directory. The app
+/// only listens if the socket's directory is private to the user.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("HANDY_SOCKET") {
+        return PathBuf::from(path);
+    }
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return PathBuf::from(dir).join("handy.sock");
+    }
+    let user = std::env::var("USER").unwrap_or_else(|_| "user".to_string());
+    std::env::temp_dir()
+        .join(format!("handy-{}", user))
+        .join("handy.sock")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_requests_and_defaults_the_binding() {
+        let request: Request = serde_json::from_str(r#"{"cmd":"toggle"}"#).unwrap();
+        assert_eq!(request, Request::Toggle { binding: None });
+        assert_eq!(request.binding(), Some(DEFAULT_BINDING));
+
+        let request: Request = serde_json::from_str(
+            r#"{"cmd":"transcribe_file","path":"/tmp/a.wav","binding":"custom_1"}"#,
+        )
+        .unwrap();
+        assert_eq!(request.binding(), Some("custom_1"));
+        assert_eq!(Request::State.binding(), None);
+
+        assert!(serde_json::from_str::<Request>(r#"{"cmd":"reboot"}"#).is_err());
+    }
+
+    #[test]
+    fn responses_omit_empty_fields() {
+        let line = serde_json::to_string(&Response::ok(None)).unwrap();
+        assert_eq!(line, r#"{"ok":true}"#);
+        let line = serde_json::to_string(&Response::error("busy")).unwrap();
+        assert_eq!(line, r#"{"ok":false,"error":"busy"}"#);
+    }
+}
@@ -5,10 +5,13 @@ mod audio_feedback;
 pub mod audio_toolkit;
 mod clipboard;
 mod commands;
+mod ducking;
 mod helpers;
 mod input;
+pub mod ipc;
 mod llm_client;
 mod managers;
+mod output;
 mod overlay;
 mod settings;
 mod shortcut;
@@ -126,6 +129,9 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(transcription_manager.clone());
     app_handle.manage(history_manager.clone());
 
+    // Re-encode any WAV recordings left over from before a codec change
+    history_manager.start_recording_conversion();
+
     // Note: Shortcuts are NOT initialized here.
     // The frontend is responsible for calling the `initialize_shortcuts` command
     // after permissions are confirmed (on macOS) or after onboarding completes.
@@ -286,7 +292,13 @@ pub fn run() {
         shortcut::update_custom_words,
         shortcut::suspend_binding,
         shortcut::resume_binding,
+        shortcut::change_binding_model,
+        shortcut::add_custom_binding,
+        shortcut::remove_custom_binding,
+        shortcut::change_binding_pipeline,
+        shortcut::change_binding_trigger,
         shortcut::change_mute_while_recording_setting,
+        shortcut::change_duck_volume_setting,
         shortcut::change_append_trailing_space_setting,
         shortcut::change_app_language_setting,
         shortcut::change_update_checks_setting,
@@ -314,6 +326,19 @@ pub fn run() {
         commands::models::download_model,
         commands::models::delete_model,
         commands::models::cancel_download,
+        commands::models::verify_models,
+        commands::models::repair_model,
+        commands::models::set_model_manifest_source,
+        commands::models::refresh_model_manifest,
+        commands::models::inspect_model_import,
+        commands::models::import_model,
+        commands::models::install_model_from_file,
+        commands::models::benchmark_models,
+        commands::models::models_disk_report,
+        commands::models::cleanup_models_dir,
+        commands::models::set_models_directory,
+        commands::models::change_download_connections_setting,
+        commands::models::change_download_speed_limit_setting,
         commands::models::set_active_model,
         commands::models::get_current_model,
         commands::models::get_transcription_model_status,
@@ -322,9 +347,16 @@ pub fn run() {
         commands::models::has_any_models_or_downloads,
         commands::audio::update_microphone_mode,
         commands::audio::get_microphone_mode,
+        commands::audio::change_vad_settings,
+        commands::audio::set_vad_debug,
         commands::audio::get_available_microphones,
         commands::audio::set_selected_microphone,
         commands::audio::get_selected_microphone,
+        commands::audio::get_microphone_channel_count,
+        commands::audio::set_microphone_channels,
+        commands::audio::get_available_loopback_sources,
+        commands::audio::set_loopback_source,
+        commands::audio::get_ducking_status,
         commands::audio::get_available_output_devices,
         commands::audio::set_selected_output_device,
         commands::audio::get_selected_output_device,
@@ -332,10 +364,22 @@ pub fn run() {
         commands::audio::check_custom_sounds,
         commands::audio::set_clamshell_microphone,
         commands::audio::get_clamshell_microphone,
+        commands::audio::set_microphone_priority,
+        commands::audio::get_active_microphone,
         commands::audio::is_recording,
         commands::audio::start_recording,
         commands::audio::stop_recording,
+        commands::audio::set_hands_free,
+        commands::audio::is_hands_free_armed,
+        commands::audio::change_hands_free_settings,
+        commands::audio::change_wake_word_settings,
+        commands::audio::change_dsp_settings,
+        commands::audio::get_dsp_stats,
+        commands::audio::change_pre_roll,
+        commands::audio::change_recording_limits,
         commands::transcription::set_model_unload_timeout,
+        commands::transcription::set_model_pool_budget,
+        commands::transcription::get_loaded_models,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
         commands::history::get_history_entries,
@@ -344,6 +388,12 @@ pub fn run() {
         commands::history::delete_history_entry,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
+        commands::history::update_recording_codec,
+        commands::history::get_usage_stats,
+        commands::history::get_history_encryption_status,
+        commands::history::enable_history_encryption,
+        commands::history::disable_history_encryption,
+        commands::history::unlock_history,
         helpers::clamshell::is_laptop,
     ]);
 
@@ -413,6 +463,8 @@ pub fn run() {
             app.manage(TranscriptionCoordinator::new(app_handle.clone()));
 
             initialize_core_logic(&app_handle);
+            #[cfg(unix)]
+            ipc::start(&app_handle);
 
             // Show main window only if not starting hidden
             if !settings.start_hidden {
@@ -452,6 +504,14 @@ pub fn run() {
             _ => {}
         })
         .invoke_handler(specta_builder.invoke_handler())
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Put other apps' audio back if we quit mid-recording
+                if let Some(rm) = app.try_state::<Arc<AudioRecordingManager>>() {
+                    rm.remove_ducking();
+                }
+            }
+        });
 }
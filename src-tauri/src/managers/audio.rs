@@ -1,100 +1,23 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::audio_toolkit::{
+    dsp::{DspChain, StageStats},
+    list_input_devices,
+    vad::{SmoothedVad, UtteranceTracker},
+    wake_word::{OnnxKeywordSpotter, WakeWordDetector},
+    AudioRecorder, ChannelMix, CpalDeviceInfo, RecordingProgress, SileroVad, StreamFault,
+    VoiceActivityDetector,
+};
+use crate::ducking::{Ducker, DuckingStatus};
 use crate::helpers::clamshell;
-use crate::settings::{get_settings, AppSettings};
+use crate::settings::{get_settings, AppSettings, VadParams};
 use crate::utils;
-use log::{debug, error, info};
+use crate::TranscriptionCoordinator;
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tauri::Manager;
-
-fn set_mute(mute: bool) {
-    // Expected behavior:
-    // - Windows: works on most systems using standard audio drivers.
-    // - Linux: works on many systems (PipeWire, PulseAudio, ALSA),
-    //   but some distros may lack the tools used.
-    // - macOS: works on most standard setups via AppleScript.
-    // If unsupported, fails silently.
-
-    #[cfg(target_os = "windows")]
-    {
-        unsafe {
-            use windows::Win32::{
-                Media::Audio::{
-                    eMultimedia, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator,
-                    MMDeviceEnumerator,
-                },
-                System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
-            };
-
-            macro_rules! unwrap_or_return {
-                ($expr:expr) => {
-                    match $expr {
-                        Ok(val) => val,
-                        Err(_) => return,
-                    }
-                };
-            }
-
-            // Initialize the COM library for this thread.
-            // If already initialized (e.g., by another library like Tauri), this does nothing.
-            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-
-            let all_devices: IMMDeviceEnumerator =
-                unwrap_or_return!(CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL));
-            let default_device =
-                unwrap_or_return!(all_devices.GetDefaultAudioEndpoint(eRender, eMultimedia));
-            let volume_interface = unwrap_or_return!(
-                default_device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
-            );
-
-            let _ = volume_interface.SetMute(mute, std::ptr::null());
-        }
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
-
-        let mute_val = if mute { "1" } else { "0" };
-        let amixer_state = if mute { "mute" } else { "unmute" };
-
-        // Try multiple backends to increase compatibility
-        // 1. PipeWire (wpctl)
-        if Command::new("wpctl")
-            .args(["set-mute", "@DEFAULT_AUDIO_SINK@", mute_val])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return;
-        }
-
-        // 2. PulseAudio (pactl)
-        if Command::new("pactl")
-            .args(["set-sink-mute", "@DEFAULT_SINK@", mute_val])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return;
-        }
-
-        // 3. ALSA (amixer)
-        let _ = Command::new("amixer")
-            .args(["set", "Master", amixer_state])
-            .output();
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        let script = format!(
-            "set volume output muted {}",
-            if mute { "true" } else { "false" }
-        );
-        let _ = Command::new("osascript").args(["-e", &script]).output();
-    }
-}
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 
 const WHISPER_SAMPLE_RATE: usize = 16000;
 
@@ -114,29 +37,225 @@ pub enum MicrophoneMode {
 
 /* ──────────────────────────────────────────────────────────────── */
 
-fn create_audio_recorder(
+/// Silero works on 30 ms frames.
+const VAD_FRAME: Duration = Duration::from_millis(30);
+
+/// How often the open microphone's device list is checked for hot-plugs.
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// A failing stream reports in bursts; faults this close together are
+/// handled as one.
+const FAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Payload of the `vad-debug` event, emitted for every 30 ms frame while VAD
+/// debugging is on.
+#[derive(Clone, Debug, Serialize)]
+pub struct VadDebugFrame {
+    pub probability: f32,
+    pub threshold: f32,
+}
+
+/// Payload of the `microphone-changed` event, emitted whenever the device
+/// the stream captures from changes.
+#[derive(Clone, Debug, Serialize)]
+pub struct MicrophoneChanged {
+    /// `None` once the stream is closed or no microphone is available
+    pub device: Option<String>,
+    /// Whether the first-choice microphone is not the one in use
+    pub fallback: bool,
+}
+
+/// Payload of the `recording-progress` event, emitted about twice a second
+/// while recording.
+#[derive(Clone, Debug, Serialize)]
+pub struct RecordingProgressEvent {
+    /// Time since the first speech, which is what `max_ms` limits
+    pub elapsed_ms: u64,
+    /// Audio kept as speech by the VAD
+    pub speech_ms: u64,
+    pub max_ms: Option<u64>,
+}
+
+impl From<RecordingProgress> for RecordingProgressEvent {
+    fn from(progress: RecordingProgress) -> Self {
+        Self {
+            elapsed_ms: progress.elapsed.as_millis() as u64,
+            speech_ms: progress.speech.as_millis() as u64,
+            max_ms: progress.max_duration.map(|d| d.as_millis() as u64),
+        }
+    }
+}
+
+/// Payload of the `microphone-error` event.
+#[derive(Clone, Debug, Serialize)]
+pub struct MicrophoneError {
+    pub device: String,
+    pub message: String,
+    pub device_lost: bool,
+}
+
+fn create_vad(
     vad_path: &str,
+    params: VadParams,
+    debug: Arc<AtomicBool>,
     app_handle: &tauri::AppHandle,
-) -> Result<AudioRecorder, anyhow::Error> {
-    let silero = SileroVad::new(vad_path, 0.3)
-        .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
-    let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
+) -> Result<Box<dyn VoiceActivityDetector>, anyhow::Error> {
+    let silero = SileroVad::new(vad_path, params.threshold)
+        .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?
+        .with_probability_callback({
+            let app_handle = app_handle.clone();
+            move |probability| {
+                if debug.load(Ordering::Relaxed) {
+                    let _ = app_handle.emit(
+                        "vad-debug",
+                        VadDebugFrame {
+                            probability,
+                            threshold: params.threshold,
+                        },
+                    );
+                }
+            }
+        });
+    Ok(Box::new(SmoothedVad::new(
+        Box::new(silero),
+        params.prefill_frames as usize,
+        params.hangover_frames as usize,
+        params.onset_frames as usize,
+    )))
+}
 
+fn utterance_tracker(settings: &AppSettings) -> UtteranceTracker {
+    UtteranceTracker::new(
+        VAD_FRAME,
+        Duration::from_millis(settings.hands_free_silence_ms as u64),
+        Duration::from_secs(settings.hands_free_max_utterance_secs as u64),
+    )
+}
+
+fn create_audio_recorder(
+    vad: Box<dyn VoiceActivityDetector>,
+    utterance: Arc<Mutex<UtteranceTracker>>,
+    app_handle: &tauri::AppHandle,
+) -> Result<AudioRecorder, anyhow::Error> {
     // Recorder with VAD plus a spectrum-level callback that forwards updates to
     // the frontend.
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
-        .with_vad(Box::new(smoothed_vad))
+        .with_vad(vad)
         .with_level_callback({
             let app_handle = app_handle.clone();
             move |levels| {
                 utils::emit_levels(&app_handle, &levels);
             }
+        })
+        .with_wake_word_callback({
+            let app_handle = app_handle.clone();
+            move || {
+                if let Some(c) = app_handle.try_state::<TranscriptionCoordinator>() {
+                    c.notify_wake_word();
+                }
+            }
+        })
+        .with_progress_callback({
+            let app_handle = app_handle.clone();
+            move |progress| {
+                utils::emit_recording_progress(
+                    &app_handle,
+                    &RecordingProgressEvent::from(progress),
+                );
+            }
+        })
+        .with_limit_callback({
+            let app_handle = app_handle.clone();
+            move || {
+                if let Some(c) = app_handle.try_state::<TranscriptionCoordinator>() {
+                    c.notify_limit_reached();
+                }
+            }
+        })
+        .with_fault_callback({
+            // Failing over closes streams, which cannot happen on the
+            // backend thread reporting the fault
+            let (fault_tx, fault_rx) = mpsc::channel();
+            spawn_fault_worker(fault_rx, app_handle.clone());
+            move |fault| {
+                let _ = fault_tx.send(fault);
+            }
+        })
+        .with_speech_callback({
+            let app_handle = app_handle.clone();
+            move |is_speech| {
+                let end = utterance.lock().unwrap().push(is_speech);
+                if let Some(end) = end {
+                    if let Some(c) = app_handle.try_state::<TranscriptionCoordinator>() {
+                        c.notify_utterance_end(end);
+                    }
+                }
+            }
         });
 
     Ok(recorder)
 }
 
+/// Handle stream faults on one thread, a burst at a time.
+fn spawn_fault_worker(faults: Receiver<StreamFault>, app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        while let Ok(first) = faults.recv() {
+            let deadline = Instant::now() + FAULT_DEBOUNCE;
+            let mut burst = vec![first];
+            while let Ok(fault) =
+                faults.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                burst.push(fault);
+            }
+
+            for fault in merge_faults(burst) {
+                if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                    rm.handle_stream_fault(fault);
+                }
+            }
+        }
+    });
+}
+
+/// One fault per device, lost if any report said so.
+fn merge_faults(burst: Vec<StreamFault>) -> Vec<StreamFault> {
+    let mut merged: Vec<StreamFault> = Vec::new();
+    for fault in burst {
+        match merged.iter_mut().find(|f| f.device == fault.device) {
+            Some(existing) => existing.device_lost |= fault.device_lost,
+            None => merged.push(fault),
+        }
+    }
+    merged
+}
+
+/// Orders devices best first: the preferred one (or the system default when
+/// none is chosen), then `priority`, then the system default. Devices on none
+/// of these are left out. Also returns the name of the first choice.
+fn rank_by_preference<T>(
+    mut devices: Vec<T>,
+    name_of: impl Fn(&T) -> &str,
+    is_default: impl Fn(&T) -> bool,
+    preferred: Option<&str>,
+    priority: &[String],
+) -> (Vec<T>, Option<String>) {
+    let default_name = devices
+        .iter()
+        .find(|d| is_default(d))
+        .map(|d| name_of(d).to_string());
+    let first_choice = preferred.map(str::to_string).or(default_name.clone());
+
+    let wanted = first_choice.iter().chain(priority).chain(&default_name);
+    let mut ranked = Vec::new();
+    for name in wanted {
+        if let Some(pos) = devices.iter().position(|d| name_of(d) == name) {
+            ranked.push(devices.remove(pos));
+        }
+    }
+    (ranked, first_choice)
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
 #[derive(Clone)]
@@ -148,7 +267,13 @@ pub struct AudioRecordingManager {
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
-    did_mute: Arc<Mutex<bool>>,
+    ducker: Arc<Ducker>,
+    vad_debug: Arc<AtomicBool>,
+    utterance: Arc<Mutex<UtteranceTracker>>,
+    active_device: Arc<Mutex<Option<String>>>,
+    failover: Arc<Mutex<()>>,
+    /// Whether the device watcher thread is running
+    watching: Arc<AtomicBool>,
 }
 
 impl AudioRecordingManager {
@@ -156,7 +281,7 @@ impl AudioRecordingManager {
 
     pub fn new(app: &tauri::AppHandle) -> Result<Self, anyhow::Error> {
         let settings = get_settings(app);
-        let mode = if settings.always_on_microphone {
+        let mode = if settings.keeps_microphone_open() {
             MicrophoneMode::AlwaysOn
         } else {
             MicrophoneMode::OnDemand
@@ -170,7 +295,12 @@ impl AudioRecordingManager {
             recorder: Arc::new(Mutex::new(None)),
             is_open: Arc::new(Mutex::new(false)),
             is_recording: Arc::new(Mutex::new(false)),
-            did_mute: Arc::new(Mutex::new(false)),
+            ducker: Arc::new(Ducker::new(app)),
+            vad_debug: Arc::new(AtomicBool::new(false)),
+            utterance: Arc::new(Mutex::new(utterance_tracker(&settings))),
+            active_device: Arc::new(Mutex::new(None)),
+            failover: Arc::new(Mutex::new(())),
+            watching: Arc::new(AtomicBool::new(false)),
         };
 
         // Always-on?  Open immediately.
@@ -183,7 +313,8 @@ impl AudioRecordingManager {
 
     /* ---------- helper methods --------------------------------------------- */
 
-    fn get_effective_microphone_device(&self, settings: &AppSettings) -> Option<cpal::Device> {
+    /// The microphone chosen in settings, considering clamshell mode.
+    fn preferred_microphone<'a>(&self, settings: &'a AppSettings) -> Option<&'a str> {
         // Check if we're in clamshell mode and have a clamshell microphone configured
         let use_clamshell_mic = if let Ok(is_clamshell) = clamshell::is_clamshell() {
             is_clamshell && settings.clamshell_microphone.is_some()
@@ -191,20 +322,85 @@ impl AudioRecordingManager {
             false
         };
 
-        let device_name = if use_clamshell_mic {
-            settings.clamshell_microphone.as_ref().unwrap()
+        if use_clamshell_mic {
+            settings.clamshell_microphone.as_deref()
         } else {
-            settings.selected_microphone.as_ref()?
-        };
+            settings.selected_microphone.as_deref()
+        }
+    }
 
-        // Find the device by name
-        match list_input_devices() {
-            Ok(devices) => devices
-                .into_iter()
-                .find(|d| d.name == *device_name)
-                .map(|d| d.device),
+    /// Orders input devices best first, see `rank_by_preference`.
+    fn rank_devices(
+        &self,
+        settings: &AppSettings,
+        devices: Vec<CpalDeviceInfo>,
+    ) -> (Vec<CpalDeviceInfo>, Option<String>) {
+        rank_by_preference(
+            devices,
+            |d| d.name.as_str(),
+            |d| d.is_default,
+            self.preferred_microphone(settings),
+            &settings.microphone_priority,
+        )
+    }
+
+    fn list_devices(&self) -> Vec<CpalDeviceInfo> {
+        list_input_devices().unwrap_or_else(|e| {
+            debug!("Failed to list input devices: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn set_active_device(&self, device: Option<String>, first_choice: Option<&str>) {
+        let mut active = self.active_device.lock().unwrap();
+        if *active == device {
+            return;
+        }
+        active.clone_from(&device);
+        drop(active);
+
+        let fallback = device.is_some() && device.as_deref() != first_choice;
+        let _ = self
+            .app_handle
+            .emit("microphone-changed", MicrophoneChanged { device, fallback });
+    }
+
+    fn create_vad(
+        &self,
+        settings: &AppSettings,
+    ) -> Result<Box<dyn VoiceActivityDetector>, anyhow::Error> {
+        let vad_path = self
+            .app_handle
+            .path()
+            .resolve(
+                "resources/models/silero_vad_v4.onnx",
+                tauri::path::BaseDirectory::Resource,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        create_vad(
+            vad_path.to_str().unwrap(),
+            settings.vad_params(),
+            self.vad_debug.clone(),
+            &self.app_handle,
+        )
+    }
+
+    fn create_wake_word_detector(&self, settings: &AppSettings) -> Option<WakeWordDetector> {
+        if !settings.wake_word_enabled {
+            return None;
+        }
+        // No keyword model ships with the app; the user has to pick one
+        let Some(model_path) = &settings.wake_word_model else {
+            warn!("Wake word is enabled without a model, ignoring it");
+            return None;
+        };
+        match OnnxKeywordSpotter::new(model_path) {
+            Ok(spotter) => Some(WakeWordDetector::new(
+                Box::new(spotter),
+                settings.wake_word_threshold,
+            )),
             Err(e) => {
-                debug!("Failed to list devices, using default: {}", e);
+                warn!("Wake word disabled, cannot load {:?}: {}", model_path, e);
                 None
             }
         }
@@ -212,26 +408,22 @@ impl AudioRecordingManager {
 
     /* ---------- microphone life-cycle -------------------------------------- */
 
-    /// Applies mute if mute_while_recording is enabled and stream is open
-    pub fn apply_mute(&self) {
+    /// Lowers other audio if mute_while_recording is enabled and stream is open
+    pub fn apply_ducking(&self) {
         let settings = get_settings(&self.app_handle);
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-
         if settings.mute_while_recording && *self.is_open.lock().unwrap() {
-            set_mute(true);
-            *did_mute_guard = true;
-            debug!("Mute applied");
+            // Failures are logged and reported through the ducking-changed event
+            let _ = self.ducker.duck(settings.duck_volume_percent);
         }
     }
 
-    /// Removes mute if it was applied
-    pub fn remove_mute(&self) {
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-        if *did_mute_guard {
-            set_mute(false);
-            *did_mute_guard = false;
-            debug!("Mute removed");
-        }
+    /// Restores other audio if it was lowered
+    pub fn remove_ducking(&self) {
+        let _ = self.ducker.restore();
+    }
+
+    pub fn ducking_status(&self) -> DuckingStatus {
+        self.ducker.status()
     }
 
     pub fn start_microphone_stream(&self) -> Result<(), anyhow::Error> {
@@ -243,37 +435,46 @@ impl AudioRecordingManager {
 
         let start_time = Instant::now();
 
-        // Don't mute immediately - caller will handle muting after audio feedback
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-        *did_mute_guard = false;
-
-        let vad_path = self
-            .app_handle
-            .path()
-            .resolve(
-                "resources/models/silero_vad_v4.onnx",
-                tauri::path::BaseDirectory::Resource,
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        // Don't duck immediately - caller will handle ducking after audio feedback
+        let settings = get_settings(&self.app_handle);
         let mut recorder_opt = self.recorder.lock().unwrap();
 
         if recorder_opt.is_none() {
-            *recorder_opt = Some(create_audio_recorder(
-                vad_path.to_str().unwrap(),
-                &self.app_handle,
-            )?);
+            let vad = self.create_vad(&settings)?;
+            let recorder = create_audio_recorder(vad, self.utterance.clone(), &self.app_handle)?;
+            recorder.set_wake_word(self.create_wake_word_detector(&settings));
+            recorder.set_dsp(Some(DspChain::new(settings.dsp_config())));
+            recorder.set_pre_roll(Duration::from_millis(settings.pre_roll_ms as u64));
+            recorder.set_loopback(settings.loopback_spec());
+            *recorder_opt = Some(recorder);
         }
 
-        // Get the selected device from settings, considering clamshell mode
-        let settings = get_settings(&self.app_handle);
-        let selected_device = self.get_effective_microphone_device(&settings);
-
+        let (candidates, first_choice) = self.rank_devices(&settings, self.list_devices());
+        let mut opened = None;
         if let Some(rec) = recorder_opt.as_mut() {
-            rec.open(selected_device)
-                .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+            for candidate in candidates {
+                let mix = settings.channel_mix(&candidate.name);
+                match rec.open(Some(candidate.device), mix) {
+                    Ok(()) => {
+                        opened = Some(candidate.name);
+                        break;
+                    }
+                    Err(e) => warn!("Failed to open {}: {}", candidate.name, e),
+                }
+            }
+            // Nothing usable in the ranking, let the host pick
+            if opened.is_none() {
+                rec.open(None, ChannelMix::All)
+                    .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+                opened = rec.device_name();
+            }
         }
+        drop(recorder_opt);
 
         *open_flag = true;
+        drop(open_flag);
+        self.set_active_device(opened, first_choice.as_deref());
+        self.spawn_device_watcher();
         info!(
             "Microphone stream initialized in {:?}",
             start_time.elapsed()
@@ -287,11 +488,7 @@ impl AudioRecordingManager {
             return;
         }
 
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-        if *did_mute_guard {
-            set_mute(false);
-        }
-        *did_mute_guard = false;
+        self.remove_ducking();
 
         if let Some(rec) = self.recorder.lock().unwrap().as_mut() {
             // If still recording, stop first.
@@ -303,9 +500,160 @@ impl AudioRecordingManager {
         }
 
         *open_flag = false;
+        drop(open_flag);
+        self.set_active_device(None, None);
         debug!("Microphone stream stopped");
     }
 
+    /* ---------- device failover -------------------------------------------- */
+
+    /// Name of the device the open stream captures from.
+    pub fn active_device(&self) -> Option<String> {
+        self.active_device.lock().unwrap().clone()
+    }
+
+    /// Report a capture stream error to the frontend and move to another
+    /// microphone if the device went away.
+    pub fn handle_stream_fault(&self, fault: StreamFault) {
+        let _ = self.app_handle.emit(
+            "microphone-error",
+            MicrophoneError {
+                device: fault.device.clone(),
+                message: fault.message,
+                device_lost: fault.device_lost,
+            },
+        );
+        if !fault.device_lost {
+            return;
+        }
+
+        let _guard = self.failover.lock().unwrap();
+        // Another report or the watcher may have handled it already
+        if self.active_device().as_deref() != Some(fault.device.as_str()) {
+            return;
+        }
+        let settings = get_settings(&self.app_handle);
+        let (candidates, first_choice) = self.rank_devices(&settings, self.list_devices());
+        let candidates = candidates
+            .into_iter()
+            .filter(|d| d.name != fault.device)
+            .collect();
+        self.switch_to_first_working(candidates, first_choice.as_deref(), &settings);
+    }
+
+    /// Reopen the active device so a changed channel selection applies,
+    /// without interrupting a recording in progress.
+    pub fn reload_active_device(&self) {
+        let _guard = self.failover.lock().unwrap();
+        let Some(active) = self.active_device() else {
+            return;
+        };
+        let settings = get_settings(&self.app_handle);
+        let candidates = self
+            .list_devices()
+            .into_iter()
+            .filter(|d| d.name == active)
+            .collect();
+        // Same device, so nothing is announced unless it fails to reopen
+        self.switch_to_first_working(candidates, None, &settings);
+    }
+
+    /// Compare the open stream's device with what is plugged in: move off a
+    /// device that disappeared without the stream noticing, pick up a device
+    /// when none was available, and return to a better-ranked one once it is
+    /// back, unless that would interrupt a recording.
+    pub fn check_devices(&self) {
+        if !*self.is_open.lock().unwrap() {
+            return;
+        }
+        let _guard = self.failover.lock().unwrap();
+        let settings = get_settings(&self.app_handle);
+        let devices = self.list_devices();
+        let active = self.active_device();
+        let present = active
+            .as_ref()
+            .is_some_and(|name| devices.iter().any(|d| &d.name == name));
+        let (candidates, first_choice) = self.rank_devices(&settings, devices);
+
+        let switch = match &active {
+            None => !candidates.is_empty(),
+            Some(_) if !present => true,
+            Some(name) => {
+                let recording = *self.is_recording.lock().unwrap();
+                let rank = candidates.iter().position(|d| &d.name == name);
+                !recording && rank.is_some_and(|rank| rank > 0)
+            }
+        };
+        if switch {
+            if let Some(lost) = active.filter(|_| !present) {
+                info!("Microphone {} disappeared", lost);
+            }
+            self.switch_to_first_working(candidates, first_choice.as_deref(), &settings);
+        }
+    }
+
+    /// Move the open stream to the first candidate that opens. Callers hold
+    /// the failover lock.
+    fn switch_to_first_working(
+        &self,
+        candidates: Vec<CpalDeviceInfo>,
+        first_choice: Option<&str>,
+        settings: &AppSettings,
+    ) {
+        let open_flag = self.is_open.lock().unwrap();
+        if !*open_flag {
+            return;
+        }
+        let mut recorder = self.recorder.lock().unwrap();
+        let Some(rec) = recorder.as_mut() else {
+            return;
+        };
+
+        let mut switched = None;
+        for candidate in candidates {
+            let mix = settings.channel_mix(&candidate.name);
+            match rec.switch_device(candidate.device, mix) {
+                Ok(()) => {
+                    switched = Some(candidate.name);
+                    break;
+                }
+                Err(e) => warn!("Failed to switch to {}: {}", candidate.name, e),
+            }
+        }
+        drop(recorder);
+        drop(open_flag);
+
+        match &switched {
+            Some(name) => info!("Microphone switched to {}", name),
+            None => warn!("No microphone available, waiting for one to be plugged in"),
+        }
+        self.set_active_device(switched, first_choice);
+    }
+
+    /// Poll for hot-plugs while the stream is open. The thread exits once
+    /// the stream closes and is started again when it reopens.
+    fn spawn_device_watcher(&self) {
+        if self.watching.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let manager = self.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(DEVICE_POLL_INTERVAL);
+            if !*manager.is_open.lock().unwrap() {
+                manager.watching.store(false, Ordering::SeqCst);
+                // The stream may have reopened before the flag was cleared
+                if !*manager.is_open.lock().unwrap()
+                    || manager.watching.swap(true, Ordering::SeqCst)
+                {
+                    debug!("Device watcher stopped");
+                    return;
+                }
+                continue;
+            }
+            manager.check_devices();
+        });
+    }
+
     /* ---------- mode switching --------------------------------------------- */
 
     pub fn update_mode(&self, new_mode: MicrophoneMode) -> Result<(), anyhow::Error> {
@@ -344,7 +692,12 @@ impl AudioRecordingManager {
                 }
             }
 
+            // Fresh utterance limits for hands-free listening
+            let settings = get_settings(&self.app_handle);
+            *self.utterance.lock().unwrap() = utterance_tracker(&settings);
+
             if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                rec.set_limits(settings.recording_limits());
                 if rec.start().is_ok() {
                     *self.is_recording.lock().unwrap() = true;
                     *state = RecordingState::Recording {
@@ -370,6 +723,68 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    /// Rebuild the VAD from the current settings. An open stream picks it up
+    /// from the next frame, so there is no need to reopen the microphone.
+    pub fn update_vad_settings(&self) -> Result<(), anyhow::Error> {
+        let settings = get_settings(&self.app_handle);
+        if let Some(rec) = self.recorder.lock().unwrap().as_mut() {
+            rec.set_vad(self.create_vad(&settings)?);
+            debug!("VAD rebuilt with {:?}", settings.vad_params());
+        }
+        Ok(())
+    }
+
+    /// Reload the wake word model and threshold from the current settings.
+    pub fn update_wake_word(&self) {
+        let detector = self.create_wake_word_detector(&get_settings(&self.app_handle));
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_wake_word(detector);
+        }
+    }
+
+    /// Rebuild the conditioning chain from the current settings. Stage state and
+    /// timings start over.
+    pub fn update_dsp(&self) {
+        let config = get_settings(&self.app_handle).dsp_config();
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_dsp(Some(DspChain::new(config)));
+            debug!("Capture DSP rebuilt with {:?}", config);
+        }
+    }
+
+    pub fn update_pre_roll(&self) {
+        let pre_roll = get_settings(&self.app_handle).pre_roll_ms;
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_pre_roll(Duration::from_millis(pre_roll as u64));
+        }
+    }
+
+    pub fn update_loopback(&self) {
+        let spec = get_settings(&self.app_handle).loopback_spec();
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_loopback(spec);
+        }
+    }
+
+    pub fn dsp_stats(&self) -> Vec<StageStats> {
+        self.recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|rec| rec.dsp_stats())
+            .unwrap_or_default()
+    }
+
+    /// Whether speech has been heard since recording started.
+    pub fn utterance_started(&self) -> bool {
+        self.utterance.lock().unwrap().has_started()
+    }
+
+    /// Stream per-frame speech probabilities as `vad-debug` events.
+    pub fn set_vad_debug(&self, enabled: bool) {
+        self.vad_debug.store(enabled, Ordering::Relaxed);
+    }
+
     pub fn stop_recording(&self, binding_id: &str) -> Option<Vec<f32>> {
         let mut state = self.state.lock().unwrap();
 
@@ -434,6 +849,7 @@ impl AudioRecordingManager {
             }
 
             *self.is_recording.lock().unwrap() = false;
+            self.remove_ducking();
 
             // In on-demand mode turn the mic off again
             if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
@@ -442,3 +858,80 @@ impl AudioRecordingManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(
+        devices: &[(&'static str, bool)],
+        preferred: Option<&str>,
+        priority: &[&str],
+    ) -> (Vec<&'static str>, Option<String>) {
+        let priority: Vec<String> = priority.iter().map(|p| p.to_string()).collect();
+        let (ranked, first) =
+            rank_by_preference(devices.to_vec(), |d| d.0, |d| d.1, preferred, &priority);
+        (ranked.into_iter().map(|d| d.0).collect(), first)
+    }
+
+    #[test]
+    fn preferred_then_priority_then_default() {
+        let devices = [
+            ("Built-in", true),
+            ("USB", false),
+            ("Headset", false),
+            ("HDMI", false),
+        ];
+        assert_eq!(
+            rank(&devices, Some("USB"), &["Headset"]),
+            (vec!["USB", "Headset", "Built-in"], Some("USB".to_string()))
+        );
+        // Without a choice the default comes first, and only once
+        assert_eq!(
+            rank(&devices, None, &["Headset", "Built-in"]),
+            (vec!["Built-in", "Headset"], Some("Built-in".to_string()))
+        );
+    }
+
+    #[test]
+    fn falls_back_in_priority_order_when_devices_go_away() {
+        let priority = ["Headset", "Webcam"];
+        // The preferred USB microphone was unplugged
+        let devices = [("Built-in", true), ("Webcam", false), ("Headset", false)];
+        assert_eq!(
+            rank(&devices, Some("USB"), &priority),
+            (
+                vec!["Headset", "Webcam", "Built-in"],
+                Some("USB".to_string())
+            )
+        );
+        // Then the headset too
+        let devices = [("Built-in", true), ("Webcam", false)];
+        assert_eq!(
+            rank(&devices, Some("USB"), &priority).0,
+            ["Webcam", "Built-in"]
+        );
+        // Nothing known is left
+        assert!(rank(&[("HDMI", false)], Some("USB"), &priority)
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn merges_a_burst_of_faults_per_device() {
+        let fault = |device: &str, device_lost| StreamFault {
+            device: device.to_string(),
+            message: "stream error".to_string(),
+            device_lost,
+        };
+        let merged = merge_faults(vec![
+            fault("USB", false),
+            fault("USB", true),
+            fault("USB", false),
+            fault("Built-in", false),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert!(merged[0].device == "USB" && merged[0].device_lost);
+        assert!(merged[1].device == "Built-in" && !merged[1].device_lost);
+    }
+}
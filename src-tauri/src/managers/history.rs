@@ -1,15 +1,24 @@
 use anyhow::Result;
 use chrono::{DateTime, Local, Utc};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use rusqlite::{params, Connection, OptionalExtension};
 use rusqlite_migration::{Migrations, M};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::audio_toolkit::save_wav_file;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::{decode_samples, encode_samples, encode_wav_bytes, AudioCodec};
+use crate::managers::history_crypto::{
+    self, is_encrypted_file_name, is_encrypted_text, secure_remove_file, HistoryCipher,
+    ENCRYPTED_FILE_SUFFIX,
+};
+use crate::managers::usage_stats::{self, UsageRange, UsageRecord, UsageStats};
+use crate::settings::{get_settings, write_settings, HistoryKeySource, RecordingCodec};
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -31,6 +40,21 @@ static MIGRATIONS: &[M] = &[
     ),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_processed_text TEXT;"),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_process_prompt TEXT;"),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS history_encryption (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            key_source TEXT NOT NULL,
+            salt BLOB,
+            verifier TEXT NOT NULL
+        );",
+    ),
+    M::up("ALTER TABLE transcription_history ADD COLUMN model_id TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN word_count INTEGER;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN audio_duration_ms INTEGER;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN transcription_ms INTEGER;"),
+    M::up(usage_stats::CREATE_ROLLUPS_SQL),
+    M::up("ALTER TABLE transcription_history ADD COLUMN output_target TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN output_error TEXT;"),
 ];
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -43,12 +67,43 @@ pub struct HistoryEntry {
     pub transcription_text: String,
     pub post_processed_text: Option<String>,
     pub post_process_prompt: Option<String>,
+    /// Where the transcript was sent, for bindings that do not paste
+    pub output_target: Option<String>,
+    /// Why sending it there failed
+    pub output_error: Option<String>,
+}
+
+/// Measurements from the transcription pipeline, kept for usage statistics.
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionMetrics {
+    pub model_id: Option<String>,
+    pub transcription_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryEncryptionStatus {
+    pub enabled: bool,
+    pub key_source: HistoryKeySource,
+    /// False while a passphrase-protected history is waiting to be unlocked.
+    pub unlocked: bool,
+}
+
+/// Key metadata stored with an encrypted history. Present exactly when the
+/// store is encrypted.
+struct EncryptionMeta {
+    key_source: HistoryKeySource,
+    /// Argon2 salt, only for passphrase keys
+    salt: Option<Vec<u8>>,
+    verifier: String,
 }
 
 pub struct HistoryManager {
     app_handle: AppHandle,
     recordings_dir: PathBuf,
+    playback_dir: PathBuf,
     db_path: PathBuf,
+    cipher: Mutex<Option<HistoryCipher>>,
+    conversion_running: AtomicBool,
 }
 
 impl HistoryManager {
@@ -64,15 +119,28 @@ impl HistoryManager {
             debug!("Created recordings directory: {:?}", recordings_dir);
         }
 
+        // Decrypted copies of encrypted recordings for the history player live
+        // here and are wiped on every start.
+        let playback_dir = app_handle.path().app_cache_dir()?.join("history-playback");
+        if playback_dir.exists() {
+            Self::clear_playback_dir(&playback_dir);
+        }
+
         let manager = Self {
             app_handle: app_handle.clone(),
             recordings_dir,
+            playback_dir,
             db_path,
+            cipher: Mutex::new(None),
+            conversion_running: AtomicBool::new(false),
         };
 
         // Initialize database and run migrations synchronously
         manager.init_database()?;
 
+        // Keyring-backed encryption can be unlocked without user input
+        manager.init_encryption();
+
         Ok(manager)
     }
 
@@ -176,24 +244,454 @@ impl HistoryManager {
         Ok(Connection::open(&self.db_path)?)
     }
 
-    /// Save a transcription to history (both database and WAV file)
+    /* ---------- encryption ------------------------------------------------- */
+
+    fn init_encryption(&self) {
+        let mut settings = get_settings(&self.app_handle);
+        if !settings.history_encryption_enabled {
+            // The store is encrypted whenever the key metadata exists. If the
+            // app stopped right after encrypting it, the setting was never
+            // saved, so finish turning encryption on now.
+            match self.read_encryption_meta() {
+                Ok(Some(meta)) => {
+                    warn!("History store is encrypted but the setting was off, re-enabling");
+                    settings.history_encryption_enabled = true;
+                    settings.history_encryption_key_source = meta.key_source;
+                    write_settings(&self.app_handle, settings.clone());
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    error!("Failed to read history encryption metadata: {}", e);
+                    return;
+                }
+            }
+        }
+
+        match settings.history_encryption_key_source {
+            HistoryKeySource::Keyring => match self.load_keyring_cipher() {
+                Ok(cipher) => {
+                    *self.cipher.lock().unwrap() = Some(cipher);
+                    info!("History encryption unlocked via OS keyring");
+                }
+                Err(e) => error!("Failed to unlock history with keyring key: {}", e),
+            },
+            HistoryKeySource::Passphrase => {
+                info!("History is encrypted with a passphrase and stays locked until unlocked");
+            }
+        }
+    }
+
+    fn load_keyring_cipher(&self) -> Result<HistoryCipher> {
+        let cipher = HistoryCipher::from_keyring()?;
+        if let Some(meta) = self.read_encryption_meta()? {
+            if !cipher.check_verifier(&meta.verifier) {
+                anyhow::bail!("Keyring key does not match the encrypted history");
+            }
+        }
+        Ok(cipher)
+    }
+
+    fn read_encryption_meta(&self) -> Result<Option<EncryptionMeta>> {
+        let conn = self.get_connection()?;
+        let meta = conn
+            .query_row(
+                "SELECT key_source, salt, verifier FROM history_encryption WHERE id = 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, String>("key_source")?,
+                        row.get("salt")?,
+                        row.get("verifier")?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        meta.map(|(key_source, salt, verifier)| {
+            Ok(EncryptionMeta {
+                key_source: serde_json::from_str(&key_source)?,
+                salt,
+                verifier,
+            })
+        })
+        .transpose()
+    }
+
+    /// Returns the cipher to use for new data, `None` when encryption is off,
+    /// or an error when the history is encrypted but still locked.
+    fn current_cipher(&self) -> Result<Option<HistoryCipher>> {
+        if !get_settings(&self.app_handle).history_encryption_enabled {
+            return Ok(None);
+        }
+        match self.cipher.lock().unwrap().clone() {
+            Some(cipher) => Ok(Some(cipher)),
+            None => Err(anyhow::anyhow!(
+                "History is encrypted and locked. Unlock it before continuing."
+            )),
+        }
+    }
+
+    pub fn encryption_status(&self) -> HistoryEncryptionStatus {
+        let settings = get_settings(&self.app_handle);
+        HistoryEncryptionStatus {
+            enabled: settings.history_encryption_enabled,
+            key_source: settings.history_encryption_key_source,
+            unlocked: !settings.history_encryption_enabled || self.cipher.lock().unwrap().is_some(),
+        }
+    }
+
+    /// Unlock a passphrase-protected history for the rest of the session.
+    pub fn unlock_encryption(&self, passphrase: &str) -> Result<()> {
+        let meta = self
+            .read_encryption_meta()?
+            .ok_or_else(|| anyhow::anyhow!("History encryption is not set up"))?;
+        let salt = meta
+            .salt
+            .ok_or_else(|| anyhow::anyhow!("History key is not passphrase based"))?;
+
+        let cipher = HistoryCipher::from_passphrase(passphrase, &salt)?;
+        if !cipher.check_verifier(&meta.verifier) {
+            anyhow::bail!("Incorrect passphrase");
+        }
+
+        *self.cipher.lock().unwrap() = Some(cipher);
+        info!("History unlocked with passphrase");
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Turn on encryption and encrypt the existing store in place.
+    pub async fn enable_encryption(
+        &self,
+        key_source: HistoryKeySource,
+        passphrase: Option<String>,
+    ) -> Result<()> {
+        let settings = get_settings(&self.app_handle);
+        if settings.history_encryption_enabled {
+            anyhow::bail!("History encryption is already enabled");
+        }
+
+        // Reuse the key of an earlier run so rows it already encrypted stay
+        // readable
+        let existing = self.read_encryption_meta()?;
+        if let Some(meta) = &existing {
+            if meta.key_source != key_source {
+                anyhow::bail!(
+                    "History is already encrypted with a {:?} key; use the same key source",
+                    meta.key_source
+                );
+            }
+        }
+
+        let (cipher, salt) = match key_source {
+            HistoryKeySource::Keyring => (HistoryCipher::from_keyring()?, None),
+            HistoryKeySource::Passphrase => {
+                let passphrase = passphrase
+                    .filter(|p| !p.is_empty())
+                    .ok_or_else(|| anyhow::anyhow!("A passphrase is required"))?;
+                let salt = existing
+                    .as_ref()
+                    .and_then(|meta| meta.salt.clone())
+                    .unwrap_or_else(history_crypto::generate_salt);
+                (
+                    HistoryCipher::from_passphrase(&passphrase, &salt)?,
+                    Some(salt),
+                )
+            }
+        };
+        let verifier = match &existing {
+            Some(meta) if !cipher.check_verifier(&meta.verifier) => {
+                anyhow::bail!("This key does not match the one the history is encrypted with")
+            }
+            Some(meta) => meta.verifier.clone(),
+            None => cipher.make_verifier()?,
+        };
+
+        // Rows and key metadata change together, so the store is never left
+        // half encrypted. Plaintext recordings go only after the commit.
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO history_encryption (id, key_source, salt, verifier) VALUES (1, ?1, ?2, ?3)",
+            params![serde_json::to_string(&key_source)?, salt, verifier],
+        )?;
+        let (entries, superseded) =
+            Self::encrypt_existing_store(&tx, &self.recordings_dir, &cipher)?;
+        tx.commit()?;
+        for path in &superseded {
+            if let Err(e) = secure_remove_file(path) {
+                error!("Failed to remove plaintext recording {:?}: {}", path, e);
+            }
+        }
+        info!(
+            "Encrypted {} history entries and {} recordings in place",
+            entries,
+            superseded.len()
+        );
+
+        let mut settings = get_settings(&self.app_handle);
+        settings.history_encryption_enabled = true;
+        settings.history_encryption_key_source = key_source;
+        write_settings(&self.app_handle, settings);
+        *self.cipher.lock().unwrap() = Some(cipher);
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Turn off encryption, decrypting the store in place. The history must be
+    /// unlocked first.
+    pub async fn disable_encryption(&self) -> Result<()> {
+        let cipher = self
+            .current_cipher()?
+            .ok_or_else(|| anyhow::anyhow!("History encryption is not enabled"))?;
+
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+        let (entries, superseded) =
+            Self::decrypt_existing_store(&tx, &self.recordings_dir, &cipher)?;
+        tx.execute("DELETE FROM history_encryption", [])?;
+        tx.commit()?;
+        for path in &superseded {
+            if let Err(e) = fs::remove_file(path) {
+                error!("Failed to remove encrypted recording {:?}: {}", path, e);
+            }
+        }
+        info!(
+            "Decrypted {} history entries and {} recordings in place",
+            entries,
+            superseded.len()
+        );
+
+        let mut settings = get_settings(&self.app_handle);
+        settings.history_encryption_enabled = false;
+        write_settings(&self.app_handle, settings);
+        *self.cipher.lock().unwrap() = None;
+        Self::clear_playback_dir(&self.playback_dir);
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Encrypt every plaintext column and recording. Rows that are already
+    /// encrypted are skipped. Meant to run inside a transaction: recordings
+    /// are written next to the plaintext originals, which are returned for
+    /// the caller to remove once the transaction has committed.
+    fn encrypt_existing_store(
+        conn: &Connection,
+        recordings_dir: &Path,
+        cipher: &HistoryCipher,
+    ) -> Result<(usize, Vec<PathBuf>)> {
+        let seal = |value: String| -> Result<String> {
+            if is_encrypted_text(&value) {
+                Ok(value)
+            } else {
+                cipher.encrypt_text(&value)
+            }
+        };
+
+        let mut entry_count = 0;
+        let mut superseded = Vec::new();
+        for entry in Self::read_raw_entries(conn)? {
+            let mut file_name = entry.file_name.clone();
+            let plain_path = recordings_dir.join(&entry.file_name);
+            if !is_encrypted_file_name(&entry.file_name) && plain_path.exists() {
+                file_name = format!("{}{}", entry.file_name, ENCRYPTED_FILE_SUFFIX);
+                let data = fs::read(&plain_path)?;
+                Self::write_atomically(
+                    &recordings_dir.join(&file_name),
+                    &cipher.encrypt_bytes(&data)?,
+                )?;
+                superseded.push(plain_path);
+            }
+
+            conn.execute(
+                "UPDATE transcription_history SET file_name = ?1, transcription_text = ?2, post_processed_text = ?3, post_process_prompt = ?4 WHERE id = ?5",
+                params![
+                    file_name,
+                    seal(entry.transcription_text)?,
+                    entry.post_processed_text.map(seal).transpose()?,
+                    entry.post_process_prompt.map(seal).transpose()?,
+                    entry.id
+                ],
+            )?;
+            entry_count += 1;
+        }
+
+        Ok((entry_count, superseded))
+    }
+
+    /// Reverse of [`Self::encrypt_existing_store`], with the same transaction
+    /// contract.
+    fn decrypt_existing_store(
+        conn: &Connection,
+        recordings_dir: &Path,
+        cipher: &HistoryCipher,
+    ) -> Result<(usize, Vec<PathBuf>)> {
+        let mut entry_count = 0;
+        let mut superseded = Vec::new();
+        for entry in Self::read_raw_entries(conn)? {
+            let mut file_name = entry.file_name.clone();
+            let sealed_path = recordings_dir.join(&entry.file_name);
+            if is_encrypted_file_name(&entry.file_name) && sealed_path.exists() {
+                file_name = entry
+                    .file_name
+                    .trim_end_matches(ENCRYPTED_FILE_SUFFIX)
+                    .to_string();
+                let data = cipher.decrypt_bytes(&fs::read(&sealed_path)?)?;
+                Self::write_atomically(&recordings_dir.join(&file_name), &data)?;
+                superseded.push(sealed_path);
+            }
+
+            conn.execute(
+                "UPDATE transcription_history SET file_name = ?1, transcription_text = ?2, post_processed_text = ?3, post_process_prompt = ?4 WHERE id = ?5",
+                params![
+                    file_name,
+                    cipher.decrypt_text(&entry.transcription_text)?,
+                    entry
+                        .post_processed_text
+                        .map(|t| cipher.decrypt_text(&t))
+                        .transpose()?,
+                    entry
+                        .post_process_prompt
+                        .map(|t| cipher.decrypt_text(&t))
+                        .transpose()?,
+                    entry.id
+                ],
+            )?;
+            entry_count += 1;
+        }
+
+        Ok((entry_count, superseded))
+    }
+
+    fn read_raw_entries(conn: &Connection) -> Result<Vec<HistoryEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, output_target, output_error FROM transcription_history",
+        )?;
+        let rows = stmt.query_map([], Self::map_entry)?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        Ok(entries)
+    }
+
+    fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn clear_playback_dir(playback_dir: &Path) {
+        let Ok(entries) = fs::read_dir(playback_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if let Err(e) = secure_remove_file(&entry.path()) {
+                error!("Failed to remove playback file {:?}: {}", entry.path(), e);
+            }
+        }
+    }
+
+    /// Decrypt the text columns of an entry read from the database.
+    fn open_entry(&self, mut entry: HistoryEntry) -> Result<HistoryEntry> {
+        let needs_key = is_encrypted_text(&entry.transcription_text)
+            || entry
+                .post_processed_text
+                .as_deref()
+                .is_some_and(is_encrypted_text)
+            || entry
+                .post_process_prompt
+                .as_deref()
+                .is_some_and(is_encrypted_text);
+        if !needs_key {
+            return Ok(entry);
+        }
+
+        let cipher =
+            self.cipher.lock().unwrap().clone().ok_or_else(|| {
+                anyhow::anyhow!("History is encrypted and locked. Unlock it first.")
+            })?;
+        entry.transcription_text = cipher.decrypt_text(&entry.transcription_text)?;
+        entry.post_processed_text = entry
+            .post_processed_text
+            .map(|t| cipher.decrypt_text(&t))
+            .transpose()?;
+        entry.post_process_prompt = entry
+            .post_process_prompt
+            .map(|t| cipher.decrypt_text(&t))
+            .transpose()?;
+        Ok(entry)
+    }
+
+    fn map_entry(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+        Ok(HistoryEntry {
+            id: row.get("id")?,
+            file_name: row.get("file_name")?,
+            timestamp: row.get("timestamp")?,
+            saved: row.get("saved")?,
+            title: row.get("title")?,
+            transcription_text: row.get("transcription_text")?,
+            post_processed_text: row.get("post_processed_text")?,
+            post_process_prompt: row.get("post_process_prompt")?,
+            output_target: row.get("output_target")?,
+            output_error: row.get("output_error")?,
+        })
+    }
+
+    /// Save a transcription to history (both database and WAV file) and
+    /// return the new entry's id
     pub async fn save_transcription(
         &self,
         audio_samples: Vec<f32>,
         transcription_text: String,
         post_processed_text: Option<String>,
         post_process_prompt: Option<String>,
-    ) -> Result<()> {
+        metrics: TranscriptionMetrics,
+    ) -> Result<i64> {
         let timestamp = Utc::now().timestamp();
-        let file_name = format!("handy-{}.wav", timestamp);
+        let word_count = usage_stats::count_words(&transcription_text);
+        let audio_duration_ms = audio_samples.len() as u64 * 1000 / WHISPER_SAMPLE_RATE as u64;
         let title = self.format_timestamp_title(timestamp);
-
-        // Save WAV file
-        let file_path = self.recordings_dir.join(&file_name);
-        save_wav_file(file_path, &audio_samples).await?;
+        let cipher = self.current_cipher()?;
+        let codec = storage_codec(get_settings(&self.app_handle).recording_codec);
+
+        // Save the recording in the configured codec, encrypted when history
+        // encryption is enabled
+        let mut file_name = format!("handy-{}.{}", timestamp, codec.extension());
+        let mut audio_data = encode_samples(&audio_samples, codec)?;
+        if let Some(cipher) = &cipher {
+            file_name.push_str(ENCRYPTED_FILE_SUFFIX);
+            audio_data = cipher.encrypt_bytes(&audio_data)?;
+        }
+        fs::write(self.recordings_dir.join(&file_name), audio_data)?;
+        debug!("Saved recording: {}", file_name);
+
+        let (transcription_text, post_processed_text, post_process_prompt) = match &cipher {
+            Some(cipher) => (
+                cipher.encrypt_text(&transcription_text)?,
+                post_processed_text
+                    .map(|t| cipher.encrypt_text(&t))
+                    .transpose()?,
+                post_process_prompt
+                    .map(|t| cipher.encrypt_text(&t))
+                    .transpose()?,
+            ),
+            None => (transcription_text, post_processed_text, post_process_prompt),
+        };
 
         // Save to database
-        self.save_to_database(
+        let id = self.save_to_database(
             file_name,
             timestamp,
             title,
@@ -201,6 +699,7 @@ impl HistoryManager {
             post_processed_text,
             post_process_prompt,
         )?;
+        self.save_metrics(id, word_count, audio_duration_ms, metrics)?;
 
         // Clean up old entries
         self.cleanup_old_entries()?;
@@ -210,6 +709,19 @@ impl HistoryManager {
             error!("Failed to emit history-updated event: {}", e);
         }
 
+        Ok(id)
+    }
+
+    /// Record where an entry's transcript was sent and whether that failed.
+    pub fn record_output(&self, id: i64, target: &str, error: Option<&str>) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET output_target = ?1, output_error = ?2 WHERE id = ?3",
+            params![target, error, id],
+        )?;
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
         Ok(())
     }
 
@@ -221,7 +733,7 @@ impl HistoryManager {
         transcription_text: String,
         post_processed_text: Option<String>,
         post_process_prompt: Option<String>,
-    ) -> Result<()> {
+    ) -> Result<i64> {
         let conn = self.get_connection()?;
         conn.execute(
             "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
@@ -229,10 +741,36 @@ impl HistoryManager {
         )?;
 
         debug!("Saved transcription to database");
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn save_metrics(
+        &self,
+        id: i64,
+        word_count: u64,
+        audio_duration_ms: u64,
+        metrics: TranscriptionMetrics,
+    ) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET model_id = ?1, word_count = ?2, audio_duration_ms = ?3, transcription_ms = ?4 WHERE id = ?5",
+            params![
+                metrics.model_id,
+                word_count,
+                audio_duration_ms,
+                metrics.transcription_ms,
+                id
+            ],
+        )?;
         Ok(())
     }
 
     pub fn cleanup_old_entries(&self) -> Result<()> {
+        // Roll up completed days first so statistics outlive the rows
+        if let Err(e) = self.refresh_usage_rollups() {
+            error!("Failed to refresh usage rollups: {}", e);
+        }
+
         let retention_period = crate::settings::get_recording_retention_period(&self.app_handle);
 
         match retention_period {
@@ -270,7 +808,7 @@ impl HistoryManager {
             // Delete WAV file
             let file_path = self.recordings_dir.join(file_name);
             if file_path.exists() {
-                if let Err(e) = fs::remove_file(&file_path) {
+                if let Err(e) = Self::remove_recording(&file_path) {
                     error!("Failed to delete WAV file {}: {}", file_name, e);
                 } else {
                     debug!("Deleted old WAV file: {}", file_name);
@@ -282,6 +820,21 @@ impl HistoryManager {
         Ok(deleted_count)
     }
 
+    /// Plaintext recordings are overwritten before removal; encrypted ones are
+    /// simply unlinked.
+    fn remove_recording(path: &Path) -> Result<()> {
+        let is_encrypted = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(is_encrypted_file_name);
+        if is_encrypted {
+            fs::remove_file(path)?;
+        } else {
+            secure_remove_file(path)?;
+        }
+        Ok(())
+    }
+
     fn cleanup_by_count(&self, limit: usize) -> Result<()> {
         let conn = self.get_connection()?;
 
@@ -352,28 +905,70 @@ impl HistoryManager {
         Ok(())
     }
 
-    pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
+    pub fn get_usage_stats(&self, range: UsageRange) -> Result<UsageStats> {
         let conn = self.get_connection()?;
+        let records = self.load_usage_records(&conn)?;
+        let today = Local::now().date_naive();
+        usage_stats::store_rollups(&conn, &records, today)?;
+        usage_stats::compute_usage_stats(&conn, &records, range, today)
+    }
+
+    fn refresh_usage_rollups(&self) -> Result<()> {
+        let conn = self.get_connection()?;
+        let records = self.load_usage_records(&conn)?;
+        usage_stats::store_rollups(&conn, &records, Local::now().date_naive())
+    }
+
+    fn load_usage_records(&self, conn: &Connection) -> Result<Vec<UsageRecord>> {
+        // Rows saved before word counts were stored need their text, which
+        // can only be counted while the history is unlocked
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt FROM transcription_history ORDER BY timestamp DESC"
+            "SELECT timestamp, model_id, word_count, audio_duration_ms, transcription_ms,
+                    post_process_prompt IS NOT NULL AS post_processed,
+                    CASE WHEN word_count IS NULL THEN transcription_text END AS legacy_text
+             FROM transcription_history",
         )?;
+        let cipher = self.cipher.lock().unwrap().clone();
 
         let rows = stmt.query_map([], |row| {
-            Ok(HistoryEntry {
-                id: row.get("id")?,
-                file_name: row.get("file_name")?,
+            let legacy_text: Option<String> = row.get("legacy_text")?;
+            let word_count: Option<u64> = row.get("word_count")?;
+            let words = word_count.unwrap_or_else(|| {
+                let text = legacy_text.unwrap_or_default();
+                match &cipher {
+                    _ if !is_encrypted_text(&text) => usage_stats::count_words(&text),
+                    Some(cipher) => cipher
+                        .decrypt_text(&text)
+                        .map(|t| usage_stats::count_words(&t))
+                        .unwrap_or(0),
+                    None => 0,
+                }
+            });
+
+            Ok(UsageRecord {
                 timestamp: row.get("timestamp")?,
-                saved: row.get("saved")?,
-                title: row.get("title")?,
-                transcription_text: row.get("transcription_text")?,
-                post_processed_text: row.get("post_processed_text")?,
-                post_process_prompt: row.get("post_process_prompt")?,
+                model_id: row.get("model_id")?,
+                words,
+                audio_ms: row.get::<_, Option<u64>>("audio_duration_ms")?.unwrap_or(0),
+                transcription_ms: row.get("transcription_ms")?,
+                post_processed: row.get("post_processed")?,
             })
         })?;
 
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, output_target, output_error FROM transcription_history ORDER BY timestamp DESC"
+        )?;
+
+        let rows = stmt.query_map([], Self::map_entry)?;
+
         let mut entries = Vec::new();
         for row in rows {
-            entries.push(row?);
+            entries.push(self.open_entry(row?)?);
         }
 
         Ok(entries)
@@ -381,31 +976,20 @@ impl HistoryManager {
 
     pub fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
-        Self::get_latest_entry_with_conn(&conn)
+        Self::get_latest_entry_with_conn(&conn)?
+            .map(|entry| self.open_entry(entry))
+            .transpose()
     }
 
     fn get_latest_entry_with_conn(conn: &Connection) -> Result<Option<HistoryEntry>> {
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, output_target, output_error
              FROM transcription_history
              ORDER BY timestamp DESC
              LIMIT 1",
         )?;
 
-        let entry = stmt
-            .query_row([], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                    post_processed_text: row.get("post_processed_text")?,
-                    post_process_prompt: row.get("post_process_prompt")?,
-                })
-            })
-            .optional()?;
+        let entry = stmt.query_row([], Self::map_entry).optional()?;
 
         Ok(entry)
     }
@@ -441,41 +1025,190 @@ impl HistoryManager {
         self.recordings_dir.join(file_name)
     }
 
+    /// Path the history player can load directly. Encrypted or compressed
+    /// recordings are decoded to a WAV in the playback cache, which only ever
+    /// holds one file.
+    pub fn get_playable_audio_path(&self, file_name: &str) -> Result<PathBuf> {
+        let path = self.get_audio_file_path(file_name);
+        let stored_name = file_name.trim_end_matches(ENCRYPTED_FILE_SUFFIX);
+        let codec = AudioCodec::from_file_name(stored_name)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported recording format: {}", file_name))?;
+        if !is_encrypted_file_name(file_name) && codec == AudioCodec::Wav {
+            return Ok(path);
+        }
+
+        let wav = self.read_recording(file_name).and_then(|data| {
+            if codec == AudioCodec::Wav {
+                Ok(data)
+            } else {
+                encode_wav_bytes(&decode_samples(&data, codec)?)
+            }
+        })?;
+
+        Self::clear_playback_dir(&self.playback_dir);
+        fs::create_dir_all(&self.playback_dir)?;
+        let playback_name = Path::new(stored_name).with_extension(AudioCodec::Wav.extension());
+        let playback_path = self.playback_dir.join(playback_name);
+        fs::write(&playback_path, wav)?;
+        Ok(playback_path)
+    }
+
+    /// Read a stored recording, decrypting it if needed. The result is still
+    /// in the recording's own codec.
+    fn read_recording(&self, file_name: &str) -> Result<Vec<u8>> {
+        let data = fs::read(self.get_audio_file_path(file_name))?;
+        if !is_encrypted_file_name(file_name) {
+            return Ok(data);
+        }
+
+        let cipher =
+            self.cipher.lock().unwrap().clone().ok_or_else(|| {
+                anyhow::anyhow!("History is encrypted and locked. Unlock it first.")
+            })?;
+        cipher.decrypt_bytes(&data)
+    }
+
+    /// Convert existing WAV recordings to the configured codec on a
+    /// background thread.
+    pub fn start_recording_conversion(self: &Arc<Self>) {
+        let manager = Arc::clone(self);
+        std::thread::spawn(move || {
+            if let Err(e) = manager.convert_recordings() {
+                error!("Failed to convert recordings: {}", e);
+            }
+        });
+    }
+
+    /// Re-encode existing WAV recordings with the configured storage codec.
+    /// Runs row by row so it can be interrupted at any point; entries that
+    /// are encrypted while the history is locked are left for a later run.
+    fn convert_recordings(&self) -> Result<usize> {
+        let codec = storage_codec(get_settings(&self.app_handle).recording_codec);
+        if codec == AudioCodec::Wav {
+            return Ok(0);
+        }
+        if self.conversion_running.swap(true, Ordering::SeqCst) {
+            debug!("Recording conversion already running");
+            return Ok(0);
+        }
+
+        let result = self.convert_recordings_to(codec);
+        self.conversion_running.store(false, Ordering::SeqCst);
+
+        if let Ok(converted) = result {
+            if converted > 0 {
+                info!(
+                    "Converted {} recordings to {}",
+                    converted,
+                    codec.extension()
+                );
+                if let Err(e) = self.app_handle.emit("history-updated", ()) {
+                    error!("Failed to emit history-updated event: {}", e);
+                }
+            }
+        }
+        result
+    }
+
+    fn convert_recordings_to(&self, codec: AudioCodec) -> Result<usize> {
+        let conn = self.get_connection()?;
+        let rows: Vec<(i64, String)> = {
+            let mut stmt = conn.prepare("SELECT id, file_name FROM transcription_history")?;
+            let rows = stmt.query_map([], |row| Ok((row.get("id")?, row.get("file_name")?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut converted = 0;
+        for (id, file_name) in rows {
+            let encrypted = is_encrypted_file_name(&file_name);
+            let stored_name = file_name.trim_end_matches(ENCRYPTED_FILE_SUFFIX);
+            if AudioCodec::from_file_name(stored_name) != Some(AudioCodec::Wav) {
+                continue;
+            }
+            let source = self.get_audio_file_path(&file_name);
+            if !source.exists() {
+                continue;
+            }
+            if encrypted && self.cipher.lock().unwrap().is_none() {
+                continue;
+            }
+
+            let mut new_name = Path::new(stored_name)
+                .with_extension(codec.extension())
+                .to_string_lossy()
+                .into_owned();
+            let encoded = self
+                .read_recording(&file_name)
+                .and_then(|wav| decode_samples(&wav, AudioCodec::Wav))
+                .and_then(|samples| encode_samples(&samples, codec))
+                .and_then(|data| match self.cipher.lock().unwrap().as_ref() {
+                    Some(cipher) if encrypted => {
+                        new_name.push_str(ENCRYPTED_FILE_SUFFIX);
+                        cipher.encrypt_bytes(&data)
+                    }
+                    _ => Ok(data),
+                });
+            let encoded = match encoded {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("Failed to convert recording {}: {}", file_name, e);
+                    continue;
+                }
+            };
+
+            let target = self.get_audio_file_path(&new_name);
+            Self::write_atomically(&target, &encoded)?;
+
+            // The row may have been deleted or rewritten meanwhile; only swap
+            // files when it still points at the WAV we converted.
+            let updated = conn.execute(
+                "UPDATE transcription_history SET file_name = ?1 WHERE id = ?2 AND file_name = ?3",
+                params![new_name, id, file_name],
+            )?;
+            if updated == 0 {
+                fs::remove_file(&target)?;
+                continue;
+            }
+
+            if let Err(e) = Self::remove_recording(&source) {
+                error!("Failed to remove converted recording {}: {}", file_name, e);
+            }
+            converted += 1;
+        }
+
+        Ok(converted)
+    }
+
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, output_target, output_error
              FROM transcription_history WHERE id = ?1",
         )?;
 
-        let entry = stmt
-            .query_row([id], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                    post_processed_text: row.get("post_processed_text")?,
-                    post_process_prompt: row.get("post_process_prompt")?,
-                })
-            })
-            .optional()?;
+        let entry = stmt.query_row([id], Self::map_entry).optional()?;
 
-        Ok(entry)
+        entry.map(|entry| self.open_entry(entry)).transpose()
     }
 
     pub async fn delete_entry(&self, id: i64) -> Result<()> {
         let conn = self.get_connection()?;
 
-        // Get the entry to find the file name
-        if let Some(entry) = self.get_entry_by_id(id).await? {
+        // Get the file name (without decrypting, so locked entries can still be deleted)
+        let file_name: Option<String> = conn
+            .query_row(
+                "SELECT file_name FROM transcription_history WHERE id = ?1",
+                params![id],
+                |row| row.get("file_name"),
+            )
+            .optional()?;
+
+        if let Some(file_name) = file_name {
             // Delete the audio file first
-            let file_path = self.get_audio_file_path(&entry.file_name);
+            let file_path = self.get_audio_file_path(&file_name);
             if file_path.exists() {
-                if let Err(e) = fs::remove_file(&file_path) {
-                    error!("Failed to delete audio file {}: {}", entry.file_name, e);
+                if let Err(e) = Self::remove_recording(&file_path) {
+                    error!("Failed to delete audio file {}: {}", file_name, e);
                     // Continue with database deletion even if file deletion fails
                 }
             }
@@ -508,6 +1241,14 @@ impl HistoryManager {
     }
 }
 
+fn storage_codec(codec: RecordingCodec) -> AudioCodec {
+    match codec {
+        RecordingCodec::Wav => AudioCodec::Wav,
+        RecordingCodec::Flac => AudioCodec::Flac,
+        RecordingCodec::Opus => AudioCodec::Opus,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -524,7 +1265,9 @@ mod tests {
                 title TEXT NOT NULL,
                 transcription_text TEXT NOT NULL,
                 post_processed_text TEXT,
-                post_process_prompt TEXT
+                post_process_prompt TEXT,
+                output_target TEXT,
+                output_error TEXT
             );",
         )
         .expect("create transcription_history table");
@@ -548,6 +1291,41 @@ mod tests {
         .expect("insert history entry");
     }
 
+    #[test]
+    fn interrupted_encryption_leaves_store_untouched() {
+        let conn = setup_conn();
+        let dir = tempfile::TempDir::new().unwrap();
+        insert_entry(&conn, 100, "first", None);
+        insert_entry(&conn, 200, "second", Some("Second"));
+        fs::write(dir.path().join("handy-100.wav"), b"RIFF").unwrap();
+        let cipher = HistoryCipher::from_key(&[3u8; 32]).unwrap();
+
+        // Dropping the transaction without committing rolls every row back
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            HistoryManager::encrypt_existing_store(&tx, dir.path(), &cipher).unwrap();
+        }
+        let entries = HistoryManager::read_raw_entries(&conn).unwrap();
+        assert!(entries
+            .iter()
+            .all(|e| !is_encrypted_text(&e.transcription_text)));
+        assert!(dir.path().join("handy-100.wav").exists());
+
+        let tx = conn.unchecked_transaction().unwrap();
+        let (count, superseded) =
+            HistoryManager::encrypt_existing_store(&tx, dir.path(), &cipher).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(superseded, vec![dir.path().join("handy-100.wav")]);
+
+        let entries = HistoryManager::read_raw_entries(&conn).unwrap();
+        assert!(entries
+            .iter()
+            .all(|e| cipher.decrypt_text(&e.transcription_text).is_ok()
+                && is_encrypted_text(&e.transcription_text)));
+        assert!(entries.iter().any(|e| e.file_name == "handy-100.wav.enc"));
+    }
+
     #[test]
     fn get_latest_entry_returns_none_when_empty() {
         let conn = setup_conn();
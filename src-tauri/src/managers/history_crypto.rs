@@ -0,0 +1,262 @@
+//! At-rest encryption for the transcription history.
+//!
+//! Text columns are stored as `enc:v1:<base64(nonce || ciphertext)>` and
+//! recordings get an extra `.enc` suffix, with a small magic header in front
+//! of the nonce and ciphertext. Both use AES-256-GCM with a key that
+//! either lives in the OS keyring or is derived from a user passphrase.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::Result;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::{debug, warn};
+use rand::RngCore;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+pub const ENCRYPTED_TEXT_PREFIX: &str = "enc:v1:";
+pub const ENCRYPTED_FILE_SUFFIX: &str = ".enc";
+
+const FILE_MAGIC: &[u8; 8] = b"BKLENC01";
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+
+const KEYRING_SERVICE: &str = "com.pais.handy.history";
+const KEYRING_ACCOUNT: &str = "history-key";
+
+/// Known plaintext stored (encrypted) alongside the salt so a passphrase can be
+/// checked before anything is decrypted with it.
+const VERIFIER_PLAINTEXT: &str = "baukalo-history";
+
+#[derive(Clone)]
+pub struct HistoryCipher {
+    cipher: Aes256Gcm,
+}
+
+impl HistoryCipher {
+    pub fn from_key(key: &[u8]) -> Result<Self> {
+        if key.len() != KEY_LEN {
+            anyhow::bail!("history key must be {} bytes, got {}", KEY_LEN, key.len());
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        })
+    }
+
+    /// Derive a key from a passphrase with Argon2id.
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            anyhow::bail!("passphrase cannot be empty");
+        }
+        let mut key = [0u8; KEY_LEN];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Failed to derive history key: {}", e))?;
+        Self::from_key(&key)
+    }
+
+    /// Load the key from the OS keyring, creating and storing a new random key
+    /// the first time.
+    pub fn from_keyring() -> Result<Self> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT)
+            .map_err(|e| anyhow::anyhow!("Failed to open keyring entry: {}", e))?;
+
+        match entry.get_password() {
+            Ok(encoded) => {
+                let key = BASE64
+                    .decode(encoded.trim())
+                    .map_err(|e| anyhow::anyhow!("Stored history key is invalid: {}", e))?;
+                Self::from_key(&key)
+            }
+            Err(keyring::Error::NoEntry) => {
+                debug!("No history key in keyring, generating a new one");
+                let mut key = [0u8; KEY_LEN];
+                OsRng.fill_bytes(&mut key);
+                entry
+                    .set_password(&BASE64.encode(key))
+                    .map_err(|e| anyhow::anyhow!("Failed to store history key: {}", e))?;
+                Self::from_key(&key)
+            }
+            Err(e) => Err(anyhow::anyhow!("Failed to read history key: {}", e)),
+        }
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+        let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            anyhow::bail!("Encrypted payload is truncated");
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow::anyhow!("Decryption failed (wrong key or corrupted data)"))
+    }
+
+    pub fn encrypt_text(&self, plaintext: &str) -> Result<String> {
+        let sealed = self.seal(plaintext.as_bytes())?;
+        Ok(format!(
+            "{}{}",
+            ENCRYPTED_TEXT_PREFIX,
+            BASE64.encode(sealed)
+        ))
+    }
+
+    /// Decrypt a text column. Values without the prefix are returned as-is so
+    /// rows written before encryption was enabled keep working.
+    pub fn decrypt_text(&self, value: &str) -> Result<String> {
+        let Some(encoded) = value.strip_prefix(ENCRYPTED_TEXT_PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let sealed = BASE64
+            .decode(encoded)
+            .map_err(|e| anyhow::anyhow!("Encrypted text is not valid base64: {}", e))?;
+        let plaintext = self.open(&sealed)?;
+        String::from_utf8(plaintext)
+            .map_err(|e| anyhow::anyhow!("Decrypted text is not valid UTF-8: {}", e))
+    }
+
+    pub fn encrypt_bytes(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut out = FILE_MAGIC.to_vec();
+        out.extend(self.seal(plaintext)?);
+        Ok(out)
+    }
+
+    pub fn decrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let sealed = data
+            .strip_prefix(FILE_MAGIC.as_slice())
+            .ok_or_else(|| anyhow::anyhow!("File is not an encrypted recording"))?;
+        self.open(sealed)
+    }
+
+    pub fn make_verifier(&self) -> Result<String> {
+        self.encrypt_text(VERIFIER_PLAINTEXT)
+    }
+
+    pub fn check_verifier(&self, verifier: &str) -> bool {
+        matches!(self.decrypt_text(verifier), Ok(text) if text == VERIFIER_PLAINTEXT)
+    }
+}
+
+pub fn generate_salt() -> Vec<u8> {
+    let mut salt = vec![0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+pub fn is_encrypted_text(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_TEXT_PREFIX)
+}
+
+pub fn is_encrypted_file_name(file_name: &str) -> bool {
+    file_name.ends_with(ENCRYPTED_FILE_SUFFIX)
+}
+
+/// Overwrite a file with zeros and sync it before unlinking, so the old
+/// plaintext does not linger in the freed blocks. This is best effort: SSD
+/// wear levelling and copy-on-write filesystems can still keep old copies.
+pub fn secure_remove_file(path: &Path) -> Result<()> {
+    let len = fs::metadata(path)?.len();
+
+    match OpenOptions::new().write(true).open(path) {
+        Ok(mut file) => {
+            let zeros = [0u8; 8192];
+            let mut remaining = len;
+            while remaining > 0 {
+                let n = remaining.min(zeros.len() as u64) as usize;
+                file.write_all(&zeros[..n])?;
+                remaining -= n as u64;
+            }
+            file.sync_all()?;
+        }
+        Err(e) => {
+            warn!(
+                "Could not overwrite {:?} before removal, deleting only: {}",
+                path, e
+            );
+        }
+    }
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_cipher() -> HistoryCipher {
+        HistoryCipher::from_key(&[7u8; KEY_LEN]).unwrap()
+    }
+
+    #[test]
+    fn text_roundtrip_uses_prefix() {
+        let cipher = test_cipher();
+        let encrypted = cipher.encrypt_text("ދިވެހި ބަސް").unwrap();
+        assert!(is_encrypted_text(&encrypted));
+        assert_eq!(cipher.decrypt_text(&encrypted).unwrap(), "ދިވެހި ބަސް");
+    }
+
+    #[test]
+    fn plaintext_passes_through_decrypt() {
+        let cipher = test_cipher();
+        assert_eq!(cipher.decrypt_text("hello").unwrap(), "hello");
+    }
+
+    #[test]
+    fn wrong_key_fails_to_decrypt() {
+        let encrypted = test_cipher().encrypt_text("secret").unwrap();
+        let other = HistoryCipher::from_key(&[9u8; KEY_LEN]).unwrap();
+        assert!(other.decrypt_text(&encrypted).is_err());
+        assert!(!other.check_verifier(&test_cipher().make_verifier().unwrap()));
+    }
+
+    #[test]
+    fn bytes_roundtrip_and_reject_tampering() {
+        let cipher = test_cipher();
+        let mut encrypted = cipher.encrypt_bytes(b"RIFF....WAVE").unwrap();
+        assert_eq!(cipher.decrypt_bytes(&encrypted).unwrap(), b"RIFF....WAVE");
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0xff;
+        assert!(cipher.decrypt_bytes(&encrypted).is_err());
+        assert!(cipher.decrypt_bytes(b"RIFF....WAVE").is_err());
+    }
+
+    #[test]
+    fn passphrase_derivation_is_deterministic_per_salt() {
+        let salt = [1u8; SALT_LEN];
+        let a = HistoryCipher::from_passphrase("correct horse", &salt).unwrap();
+        let b = HistoryCipher::from_passphrase("correct horse", &salt).unwrap();
+        assert!(b.check_verifier(&a.make_verifier().unwrap()));
+
+        let c = HistoryCipher::from_passphrase("wrong horse", &salt).unwrap();
+        assert!(!c.check_verifier(&a.make_verifier().unwrap()));
+        assert!(HistoryCipher::from_passphrase("", &salt).is_err());
+    }
+
+    #[test]
+    fn secure_remove_deletes_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("handy-1.wav");
+        fs::write(&path, b"plaintext audio").unwrap();
+        secure_remove_file(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -1,4 +1,13 @@
 pub mod audio;
 pub mod history;
+pub mod history_crypto;
 pub mod model;
+pub mod model_benchmark;
+pub mod model_download;
+pub mod model_import;
+pub mod model_integrity;
+pub mod model_manifest;
+pub mod model_pool;
+pub mod model_storage;
 pub mod transcription;
+pub mod usage_stats;
@@ -1,3 +1,9 @@
+use crate::managers::model_benchmark::{self, ModelBenchmark};
+use crate::managers::model_download::{self, DownloadOptions, DownloadOutcome};
+use crate::managers::model_import::{self, ModelImportPreview};
+use crate::managers::model_integrity::{self, ModelIntegrity, ModelVerification};
+use crate::managers::model_manifest::{self, ManifestModel, ModelManifest};
+use crate::managers::model_storage::{self, DiskEntryKind, ModelFileRef, ModelsDiskReport};
 use crate::settings::{get_settings, write_settings};
 use anyhow::Result;
 use flate2::read::GzDecoder;
@@ -9,12 +15,10 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-#[cfg(test)]
-use std::path::Path;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 use tar::Archive;
 use tauri::{AppHandle, Emitter, Manager};
 
@@ -33,6 +37,8 @@ pub struct ModelInfo {
     pub description: String,
     pub filename: String,
     pub url: Option<String>,
+    #[serde(default)]
+    pub mirrors: Vec<String>, // Fallback URLs tried in order when `url` fails
     pub size_mb: u64,
     pub is_downloaded: bool,
     pub is_downloading: bool,
@@ -45,6 +51,10 @@ pub struct ModelInfo {
     pub is_recommended: bool,       // Whether this is the recommended model for new users
     pub supported_languages: Vec<String>, // Languages this model can transcribe
     pub is_custom: bool,            // Whether this is a user-provided custom model
+    #[serde(default)]
+    pub sha256: Option<String>, // Expected digest of the file, or of the extracted directory tree
+    #[serde(default)]
+    pub benchmark: Option<ModelBenchmark>, // Last measured run; its scores replace the catalogue ones
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -55,154 +65,75 @@ pub struct DownloadProgress {
     pub percentage: f64,
 }
 
+/// Progress events are sent at most this often while downloading.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How a model's payload is fetched.
+enum DownloadPlan {
+    /// Byte ranges over several connections and mirrors, see `model_download`
+    Chunked(DownloadOptions),
+    /// One request, appending to what an earlier attempt left
+    SingleStream { resume_from: u64 },
+}
+
 pub struct ModelManager {
     app_handle: AppHandle,
-    models_dir: PathBuf,
+    /// Changes at runtime when the user relocates the models directory
+    models_dir: RwLock<PathBuf>,
     available_models: Mutex<HashMap<String, ModelInfo>>,
     cancel_flags: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     extracting_models: Arc<Mutex<HashSet<String>>>,
+    /// Models that failed verification; they stay not downloaded until
+    /// reinstalled or deleted
+    corrupted_models: Mutex<HashSet<String>>,
+    /// Set while the models directory is being moved
+    relocating: AtomicBool,
 }
 
 impl ModelManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
-        // Create models directory in app data
-        let models_dir = app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
-            .join("models");
+        // Use the configured models directory, falling back to app data
+        let default_dir = Self::default_models_dir(app_handle)?;
+        let models_dir = match get_settings(app_handle).models_directory {
+            Some(custom) => match fs::create_dir_all(&custom) {
+                Ok(()) => PathBuf::from(custom),
+                Err(e) => {
+                    warn!(
+                        "Models directory {} is unavailable ({}), using {:?}",
+                        custom, e, default_dir
+                    );
+                    default_dir
+                }
+            },
+            None => default_dir,
+        };
 
         if !models_dir.exists() {
             fs::create_dir_all(&models_dir)?;
         }
 
-        let mut available_models = HashMap::new();
-
-        // Whisper model optimized for Dhivehi transcription.
-        available_models.insert(
-            "whisper-small-dv".to_string(),
-            ModelInfo {
-                id: "whisper-small-dv".to_string(),
-                name: "Whisper Small Dhivehi".to_string(),
-                description: "Optimized for Dhivehi transcription.".to_string(),
-                filename: "ggml-whisper-small-dv.bin".to_string(),
-                url: Some(
-                    "https://huggingface.co/mohamedrayyan/whisper-small-dv-ggml/resolve/main/ggml-whisper-small-dv.bin"
-                        .to_string(),
-                ),
-                size_mb: 465,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.90,
-                speed_score: 0.82,
-                supports_translation: false,
-                is_recommended: true,
-                supported_languages: vec!["dv".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "moonshine-base".to_string(),
-            ModelInfo {
-                id: "moonshine-base".to_string(),
-                name: "Moonshine Base".to_string(),
-                description: "Very fast, English only. Handles accents well.".to_string(),
-                filename: "moonshine-base".to_string(),
-                url: Some("https://blob.handy.computer/moonshine-base.tar.gz".to_string()),
-                size_mb: 58,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Moonshine,
-                accuracy_score: 0.70,
-                speed_score: 0.90,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "whisper-small-en".to_string(),
-            ModelInfo {
-                id: "whisper-small-en".to_string(),
-                name: "Whisper Small English".to_string(),
-                description: "Fast and accurate for English transcription.".to_string(),
-                filename: "ggml-small.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-small.bin".to_string()),
-                size_mb: 487,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.78,
-                speed_score: 0.88,
-                supports_translation: true,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "whisper-medium-en".to_string(),
-            ModelInfo {
-                id: "whisper-medium-en".to_string(),
-                name: "Whisper Medium English".to_string(),
-                description: "Higher English accuracy with moderate speed.".to_string(),
-                filename: "whisper-medium-q4_1.bin".to_string(),
-                url: Some("https://blob.handy.computer/whisper-medium-q4_1.bin".to_string()),
-                size_mb: 492,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.84,
-                speed_score: 0.62,
-                supports_translation: true,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "parakeet-tdt-0.6b-v2".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v2".to_string(),
-                name: "Parakeet V2 English".to_string(),
-                description: "Fast and accurate English-only model.".to_string(),
-                filename: "parakeet-tdt-0.6b-v2-int8".to_string(),
-                url: Some("https://blob.handy.computer/parakeet-v2-int8.tar.gz".to_string()),
-                size_mb: 473,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.85,
-                speed_score: 0.85,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
+        let manifest = model_manifest::load_manifest(&models_dir);
+        info!(
+            "Loaded model manifest revision {} with {} models",
+            manifest.revision,
+            manifest.models.len()
         );
+        let mut available_models: HashMap<String, ModelInfo> = manifest
+            .models
+            .iter()
+            .map(|m| (m.id.clone(), m.to_model_info()))
+            .collect();
+        Self::register_imported_models(&models_dir, &mut available_models);
+        Self::attach_benchmarks(&models_dir, &mut available_models);
 
         let manager = Self {
             app_handle: app_handle.clone(),
-            models_dir,
+            models_dir: RwLock::new(models_dir),
             available_models: Mutex::new(available_models),
             cancel_flags: Arc::new(Mutex::new(HashMap::new())),
             extracting_models: Arc::new(Mutex::new(HashSet::new())),
+            corrupted_models: Mutex::new(HashSet::new()),
+            relocating: AtomicBool::new(false),
         };
 
         // Migrate any bundled models to user directory
@@ -227,6 +158,432 @@ impl ModelManager {
         models.get(model_id).cloned()
     }
 
+    fn default_models_dir(app_handle: &AppHandle) -> Result<PathBuf> {
+        Ok(app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
+            .join("models"))
+    }
+
+    pub fn models_dir(&self) -> PathBuf {
+        self.models_dir.read().unwrap().clone()
+    }
+
+    /// Size and kind of every entry in the models directory. Models listed in
+    /// `in_use` (e.g. loaded ones) are never offered for cleanup.
+    pub fn disk_report(&self, in_use: &[String]) -> Result<ModelsDiskReport> {
+        let models_dir = self.models_dir();
+        let catalogue: HashSet<String> = model_manifest::load_manifest(&models_dir)
+            .models
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        let selected_model = get_settings(&self.app_handle).selected_model;
+        let extracting = self.extracting_models.lock().unwrap().clone();
+
+        let refs: Vec<ModelFileRef> = self
+            .get_available_models()
+            .into_iter()
+            .map(|m| ModelFileRef {
+                listed: m.is_custom || catalogue.contains(&m.id),
+                busy: m.is_downloading || extracting.contains(&m.id),
+                protected: m.id == selected_model || in_use.contains(&m.id),
+                model_id: m.id,
+                filename: m.filename,
+            })
+            .collect();
+
+        model_storage::scan_models_dir(&models_dir, &refs, SystemTime::now())
+    }
+
+    /// Remove reclaimable entries from the models directory: all of them, or
+    /// only those named in `names`. Returns the number of bytes freed.
+    pub fn cleanup_models_dir(&self, names: Option<&[String]>, in_use: &[String]) -> Result<u64> {
+        let report = self.disk_report(in_use)?;
+        let models_dir = self.models_dir();
+        let mut freed = 0;
+
+        for entry in report.entries.iter().filter(|e| e.reclaimable) {
+            if names.is_some_and(|names| !names.contains(&entry.name)) {
+                continue;
+            }
+
+            let result = match (&entry.kind, &entry.model_id) {
+                (DiskEntryKind::UncataloguedModel, Some(model_id)) => {
+                    // Goes through delete_model so the model leaves the list too
+                    self.delete_model(model_id).map(|()| {
+                        self.available_models.lock().unwrap().remove(model_id);
+                    })
+                }
+                _ => {
+                    let path = models_dir.join(&entry.name);
+                    if entry.is_directory {
+                        fs::remove_dir_all(&path)
+                    } else {
+                        fs::remove_file(&path)
+                    }
+                    .map_err(anyhow::Error::from)
+                }
+            };
+
+            match result {
+                Ok(()) => {
+                    info!("Cleaned up {} ({} bytes)", entry.name, entry.size_bytes);
+                    freed += entry.size_bytes;
+                }
+                Err(e) => warn!("Failed to clean up {}: {}", entry.name, e),
+            }
+        }
+
+        self.update_download_status()?;
+        Ok(freed)
+    }
+
+    /// Move every model to `new_dir` (or back to the default location when
+    /// `None`) and keep using them from there. Only entries the app manages
+    /// are moved, so user files in either directory stay where they are.
+    pub fn set_models_dir(&self, new_dir: Option<&Path>) -> Result<PathBuf> {
+        if self.relocating.swap(true, Ordering::SeqCst) {
+            return Err(anyhow::anyhow!(
+                "The models directory is already being moved"
+            ));
+        }
+        let result = self.relocate_models_dir(new_dir);
+        self.relocating.store(false, Ordering::SeqCst);
+        result
+    }
+
+    fn relocate_models_dir(&self, new_dir: Option<&Path>) -> Result<PathBuf> {
+        if self
+            .available_models
+            .lock()
+            .unwrap()
+            .values()
+            .any(|m| m.is_downloading)
+            || !self.extracting_models.lock().unwrap().is_empty()
+        {
+            return Err(anyhow::anyhow!(
+                "Cannot move the models directory while a download is in progress"
+            ));
+        }
+
+        let target = match new_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => Self::default_models_dir(&self.app_handle)?,
+        };
+        fs::create_dir_all(&target)?;
+        let target = target.canonicalize()?;
+        let current = self.models_dir();
+        let current_canonical = current.canonicalize()?;
+        if current_canonical == target {
+            return Ok(target);
+        }
+        if target.starts_with(&current_canonical) {
+            return Err(anyhow::anyhow!(
+                "The new models directory cannot be inside the current one"
+            ));
+        }
+
+        let refs: Vec<ModelFileRef> = self
+            .get_available_models()
+            .into_iter()
+            .map(|m| ModelFileRef {
+                model_id: m.id,
+                filename: m.filename,
+                listed: true,
+                busy: false,
+                protected: false,
+            })
+            .collect();
+
+        // Copying can take minutes, so the path lock is only taken for the
+        // final swap; `relocating` keeps downloads out in the meantime
+        let mut moved = Vec::new();
+        for entry in fs::read_dir(&current)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if !model_storage::is_managed_entry(&entry.path(), &refs) {
+                debug!(
+                    "Leaving unrelated entry {:?} in the old models directory",
+                    name
+                );
+                continue;
+            }
+            let dest = target.join(&name);
+            if dest.exists() {
+                warn!(
+                    "{:?} already exists in the new models directory, leaving the old copy",
+                    name
+                );
+                continue;
+            }
+            if let Err(e) = model_storage::move_entry(&entry.path(), &dest) {
+                // Put back what was already moved so no model goes missing
+                for (src, dest) in moved {
+                    if let Err(e) = model_storage::move_entry(&dest, &src) {
+                        warn!("Failed to move {:?} back: {}", dest, e);
+                    }
+                }
+                return Err(anyhow::anyhow!("Failed to move {:?}: {}", name, e));
+            }
+            moved.push((entry.path(), dest));
+        }
+        *self.models_dir.write().unwrap() = target.clone();
+
+        let mut settings = get_settings(&self.app_handle);
+        settings.models_directory = new_dir.map(|_| target.to_string_lossy().into_owned());
+        write_settings(&self.app_handle, settings);
+
+        info!("Models directory moved from {:?} to {:?}", current, target);
+        self.update_download_status()?;
+        Ok(target)
+    }
+
+    /// Load a manifest from `source` (an HTTP(S) URL or a local file path),
+    /// cache it and replace the catalogue with its models.
+    pub async fn refresh_manifest(&self, source: &str) -> Result<Vec<ModelInfo>> {
+        let json = if source.starts_with("https://") || source.starts_with("http://") {
+            let response = reqwest::Client::new().get(source).send().await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!(
+                    "Failed to fetch model manifest: HTTP {}",
+                    response.status()
+                ));
+            }
+            response.text().await?
+        } else {
+            let path = source.strip_prefix("file://").unwrap_or(source);
+            fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read model manifest {}: {}", path, e))?
+        };
+
+        let manifest = model_manifest::parse_manifest(&json)?;
+        // An older cache would be ignored on the next start anyway
+        let bundled_revision = model_manifest::bundled_manifest().revision;
+        if manifest.revision < bundled_revision {
+            return Err(anyhow::anyhow!(
+                "Model manifest revision {} is older than the bundled revision {}",
+                manifest.revision,
+                bundled_revision
+            ));
+        }
+        self.apply_manifest(&manifest)?;
+        fs::write(
+            self.models_dir().join(model_manifest::CACHED_MANIFEST_FILE),
+            &json,
+        )?;
+
+        info!(
+            "Refreshed model manifest to revision {} ({} models)",
+            manifest.revision,
+            manifest.models.len()
+        );
+        let _ = self
+            .app_handle
+            .emit("model-manifest-updated", manifest.revision);
+
+        Ok(self.get_available_models())
+    }
+
+    fn apply_manifest(&self, manifest: &ModelManifest) -> Result<()> {
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if models.values().any(|m| m.is_downloading) {
+                return Err(anyhow::anyhow!(
+                    "Cannot refresh the model catalogue while a download is in progress"
+                ));
+            }
+
+            let mut updated: HashMap<String, ModelInfo> = manifest
+                .models
+                .iter()
+                .map(|m| (m.id.clone(), m.to_model_info()))
+                .collect();
+            // Keep custom models and downloaded models the new manifest no
+            // longer lists, so the active model does not vanish
+            for (id, model) in models.drain() {
+                if !updated.contains_key(&id) && (model.is_custom || model.is_downloaded) {
+                    updated.insert(id, model);
+                }
+            }
+            Self::attach_benchmarks(&self.models_dir(), &mut updated);
+            *models = updated;
+        }
+
+        self.update_download_status()
+    }
+
+    /// Show measured scores from earlier benchmark runs instead of the
+    /// catalogue estimates.
+    fn attach_benchmarks(models_dir: &Path, available_models: &mut HashMap<String, ModelInfo>) {
+        for (id, benchmark) in model_benchmark::load_benchmarks(models_dir) {
+            if let Some(model) = available_models.get_mut(&id) {
+                apply_benchmark(model, benchmark);
+            }
+        }
+    }
+
+    /// Store a benchmark run for a model and surface it in the model list.
+    pub fn record_benchmark(&self, model_id: &str, benchmark: ModelBenchmark) -> Result<ModelInfo> {
+        let mut benchmarks = model_benchmark::load_benchmarks(&self.models_dir());
+        benchmarks.insert(model_id.to_string(), benchmark.clone());
+        model_benchmark::save_benchmarks(&self.models_dir(), &benchmarks)?;
+
+        let mut models = self.available_models.lock().unwrap();
+        let model = models
+            .get_mut(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        apply_benchmark(model, benchmark);
+        Ok(model.clone())
+    }
+
+    /// Add the models the user imported in earlier sessions.
+    fn register_imported_models(
+        models_dir: &Path,
+        available_models: &mut HashMap<String, ModelInfo>,
+    ) {
+        let imported = match model_import::load_custom_models(models_dir) {
+            Ok(imported) => imported,
+            Err(e) => {
+                warn!("Failed to read imported models: {}", e);
+                return;
+            }
+        };
+
+        for entry in imported {
+            if available_models.contains_key(&entry.id) {
+                warn!(
+                    "Imported model {} clashes with a catalogue model, skipping",
+                    entry.id
+                );
+                continue;
+            }
+            if !models_dir.join(&entry.filename).exists() {
+                warn!("Imported model {} is missing from disk, skipping", entry.id);
+                continue;
+            }
+            let mut info = entry.to_model_info();
+            info.is_custom = true;
+            available_models.insert(entry.id.clone(), info);
+        }
+    }
+
+    /// Inspect a file or directory the user wants to import.
+    pub fn inspect_import(&self, path: &Path) -> Result<ModelImportPreview> {
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Path does not exist: {:?}", path));
+        }
+        let engine_type = model_import::detect_engine(path)?;
+        let suggested_name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Custom model")
+            .to_string();
+        let supported_languages = model_import::infer_languages(&engine_type, &suggested_name);
+
+        Ok(ModelImportPreview {
+            suggested_name,
+            engine_type,
+            is_directory: path.is_dir(),
+            size_mb: model_import::size_mb(path)?,
+            supported_languages,
+        })
+    }
+
+    /// Copy a model into the models directory and register it as a custom
+    /// model. `name` and `languages` default to the inferred values.
+    pub fn import_model(
+        &self,
+        path: &Path,
+        name: Option<String>,
+        languages: Option<Vec<String>>,
+    ) -> Result<ModelInfo> {
+        let preview = self.inspect_import(path)?;
+        let name = name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or(preview.suggested_name);
+        let supported_languages = languages.unwrap_or(preview.supported_languages);
+
+        let extension = if preview.is_directory {
+            String::new()
+        } else {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .map(|e| format!(".{}", e.to_ascii_lowercase()))
+                .unwrap_or_default()
+        };
+        let base = format!("custom-{}", model_import::slugify(&name));
+        let (id, filename) = {
+            let models = self.available_models.lock().unwrap();
+            (1..)
+                .map(|n| {
+                    let id = if n == 1 {
+                        base.clone()
+                    } else {
+                        format!("{}-{}", base, n)
+                    };
+                    let filename = format!("{}{}", id, extension);
+                    (id, filename)
+                })
+                .find(|(id, filename)| {
+                    !models.contains_key(id)
+                        && !models.values().any(|m| &m.filename == filename)
+                        && !self.models_dir().join(filename).exists()
+                })
+                .expect("an unused model id exists")
+        };
+
+        let dest = self.models_dir().join(&filename);
+        info!(
+            "Importing model {:?} as {} ({:?})",
+            path, id, preview.engine_type
+        );
+        if let Err(e) = model_import::copy_model(path, &dest) {
+            if dest.is_dir() {
+                let _ = fs::remove_dir_all(&dest);
+            } else {
+                let _ = fs::remove_file(&dest);
+            }
+            return Err(anyhow::anyhow!("Failed to copy model: {}", e));
+        }
+
+        let entry = ManifestModel {
+            id: id.clone(),
+            name,
+            description: "Imported model".to_string(),
+            filename,
+            url: None,
+            mirrors: Vec::new(),
+            size_mb: preview.size_mb,
+            is_directory: preview.is_directory,
+            engine_type: preview.engine_type,
+            accuracy_score: 0.0, // Sentinel: UI hides score bars when both are 0
+            speed_score: 0.0,
+            supports_translation: false,
+            is_recommended: false,
+            supported_languages,
+            sha256: None,
+        };
+
+        let mut imported = model_import::load_custom_models(&self.models_dir())?;
+        imported.retain(|m| m.id != id);
+        imported.push(entry.clone());
+        model_import::save_custom_models(&self.models_dir(), &imported)?;
+
+        let mut info = entry.to_model_info();
+        info.is_custom = true;
+        info.is_downloaded = true;
+        {
+            let mut models = self.available_models.lock().unwrap();
+            models.insert(id.clone(), info.clone());
+        }
+
+        let _ = self.app_handle.emit("model-imported", &id);
+        Ok(info)
+    }
+
     fn migrate_bundled_models(&self) -> Result<()> {
         // No bundled transcription models are shipped in this fork.
         let bundled_models: [&str; 0] = [];
@@ -239,7 +596,7 @@ impl ModelManager {
 
             if let Ok(bundled_path) = bundled_path {
                 if bundled_path.exists() {
-                    let user_path = self.models_dir.join(filename);
+                    let user_path = self.models_dir().join(filename);
 
                     // Only copy if user doesn't already have the model
                     if !user_path.exists() {
@@ -255,15 +612,18 @@ impl ModelManager {
     }
 
     fn update_download_status(&self) -> Result<()> {
+        let corrupted = self.corrupted_models.lock().unwrap().clone();
         let mut models = self.available_models.lock().unwrap();
 
         for model in models.values_mut() {
             if model.is_directory {
                 // For directory-based models, check if the directory exists
-                let model_path = self.models_dir.join(&model.filename);
-                let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
+                let model_path = self.models_dir().join(&model.filename);
+                let partial_path = self
+                    .models_dir()
+                    .join(format!("{}.partial", &model.filename));
                 let extracting_path = self
-                    .models_dir
+                    .models_dir()
                     .join(format!("{}.extracting", &model.filename));
 
                 // Clean up any leftover .extracting directories from interrupted extractions
@@ -277,7 +637,8 @@ impl ModelManager {
                     let _ = fs::remove_dir_all(&extracting_path);
                 }
 
-                model.is_downloaded = model_path.exists() && model_path.is_dir();
+                model.is_downloaded =
+                    model_path.exists() && model_path.is_dir() && !corrupted.contains(&model.id);
                 model.is_downloading = false;
 
                 // Get partial file size if it exists (for the .tar.gz being downloaded)
@@ -288,10 +649,12 @@ impl ModelManager {
                 }
             } else {
                 // For file-based models (existing logic)
-                let model_path = self.models_dir.join(&model.filename);
-                let partial_path = self.models_dir.join(format!("{}.partial", &model.filename));
+                let model_path = self.models_dir().join(&model.filename);
+                let partial_path = self
+                    .models_dir()
+                    .join(format!("{}.partial", &model.filename));
 
-                model.is_downloaded = model_path.exists();
+                model.is_downloaded = model_path.exists() && !corrupted.contains(&model.id);
                 model.is_downloading = false;
 
                 // Get partial file size if it exists
@@ -454,6 +817,7 @@ impl ModelManager {
                     description: "Not officially supported".to_string(),
                     filename,
                     url: None, // Custom models have no download URL
+                    mirrors: Vec::new(),
                     size_mb,
                     is_downloaded: true, // Already present on disk
                     is_downloading: false,
@@ -466,6 +830,8 @@ impl ModelManager {
                     is_recommended: false,
                     supported_languages: vec![],
                     is_custom: true,
+                    sha256: None,
+                    benchmark: None,
                 },
             );
         }
@@ -481,13 +847,19 @@ impl ModelManager {
 
         let model_info =
             model_info.ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if self.relocating.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!(
+                "Cannot download while the models directory is being moved"
+            ));
+        }
 
         let url = model_info
             .url
+            .clone()
             .ok_or_else(|| anyhow::anyhow!("No download URL for model"))?;
-        let model_path = self.models_dir.join(&model_info.filename);
+        let model_path = self.models_dir().join(&model_info.filename);
         let partial_path = self
-            .models_dir
+            .models_dir()
             .join(format!("{}.partial", &model_info.filename));
 
         // Don't download if complete version already exists
@@ -500,8 +872,88 @@ impl ModelManager {
             return Ok(());
         }
 
+        let plan = self.plan_download(model_id, &model_info, &url, &partial_path)?;
+
+        self.set_downloading(model_id, true);
+        // Create cancellation flag for this download
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        {
+            let mut flags = self.cancel_flags.lock().unwrap();
+            flags.insert(model_id.to_string(), cancel_flag.clone());
+        }
+
+        let result = match plan {
+            DownloadPlan::Chunked(options) => {
+                let mut urls = vec![url];
+                urls.extend(model_info.mirrors.iter().cloned());
+                self.download_chunked(model_id, &urls, &partial_path, &options, &cancel_flag)
+                    .await
+            }
+            DownloadPlan::SingleStream { resume_from } => {
+                self.download_single_stream(
+                    model_id,
+                    &url,
+                    &partial_path,
+                    resume_from,
+                    &cancel_flag,
+                )
+                .await
+            }
+        };
+
+        let result = match result {
+            Ok(DownloadOutcome::Completed) => {
+                self.install_payload(model_id, &model_info, &partial_path)
+            }
+            Ok(DownloadOutcome::Cancelled) => {
+                // Keep the partial file (and chunk state) for resuming
+                info!("Download cancelled for: {}", model_id);
+                self.set_downloading(model_id, false);
+                Ok(())
+            }
+            Err(e) => {
+                self.set_downloading(model_id, false);
+                Err(e)
+            }
+        };
+
+        {
+            let mut flags = self.cancel_flags.lock().unwrap();
+            flags.remove(model_id);
+        }
+        result
+    }
+
+    /// Pick how to fetch a model and clear partial state the chosen way
+    /// cannot resume from.
+    fn plan_download(
+        &self,
+        model_id: &str,
+        model_info: &ModelInfo,
+        url: &str,
+        partial_path: &Path,
+    ) -> Result<DownloadPlan> {
+        let options = DownloadOptions::from_settings(&get_settings(&self.app_handle));
+        let chunked = options.connections > 1
+            || options.max_bytes_per_sec.is_some()
+            || !model_info.mirrors.is_empty();
+        if chunked {
+            // A contiguous partial file from a single-stream attempt is
+            // adopted by the chunked downloader
+            info!("Downloading model {} in chunks", model_id);
+            return Ok(DownloadPlan::Chunked(options));
+        }
+
+        // A partial file from the chunked downloader has gaps, so it can't be
+        // resumed by appending
+        let chunk_state_path = model_download::chunk_state_path(partial_path);
+        if chunk_state_path.exists() {
+            let _ = fs::remove_file(partial_path);
+            let _ = fs::remove_file(&chunk_state_path);
+        }
+
         // Check if we have a partial download to resume
-        let mut resume_from = if partial_path.exists() {
+        let resume_from = if partial_path.exists() {
             let size = partial_path.metadata()?.len();
             info!("Resuming download of model {} from byte {}", model_id, size);
             size
@@ -509,25 +961,54 @@ impl ModelManager {
             info!("Starting fresh download of model {} from {}", model_id, url);
             0
         };
+        Ok(DownloadPlan::SingleStream { resume_from })
+    }
 
-        // Mark as downloading
-        {
-            let mut models = self.available_models.lock().unwrap();
-            if let Some(model) = models.get_mut(model_id) {
-                model.is_downloading = true;
+    /// Fetch byte ranges over several connections, falling back to mirrors.
+    async fn download_chunked(
+        &self,
+        model_id: &str,
+        urls: &[String],
+        partial_path: &Path,
+        options: &DownloadOptions,
+        cancel_flag: &AtomicBool,
+    ) -> Result<DownloadOutcome> {
+        // Throttle progress events to max 10/sec, always sending the last one
+        let last_emit: Mutex<Option<Instant>> = Mutex::new(None);
+        let report = |downloaded: u64, total: u64| {
+            let mut last_emit = last_emit.lock().unwrap();
+            let recent = last_emit.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL);
+            if downloaded < total && recent {
+                return;
             }
-        }
+            *last_emit = Some(Instant::now());
+            self.emit_download_progress(model_id, downloaded, total);
+        };
 
-        // Create cancellation flag for this download
-        let cancel_flag = Arc::new(AtomicBool::new(false));
-        {
-            let mut flags = self.cancel_flags.lock().unwrap();
-            flags.insert(model_id.to_string(), cancel_flag.clone());
-        }
+        model_download::download_chunked(
+            &reqwest::Client::new(),
+            urls,
+            partial_path,
+            options,
+            cancel_flag,
+            &report,
+        )
+        .await
+    }
 
+    /// Fetch the whole file over one connection, appending to what an
+    /// earlier attempt left if the server supports ranges.
+    async fn download_single_stream(
+        &self,
+        model_id: &str,
+        url: &str,
+        partial_path: &Path,
+        mut resume_from: u64,
+        cancel_flag: &AtomicBool,
+    ) -> Result<DownloadOutcome> {
         // Create HTTP client with range request for resuming
         let client = reqwest::Client::new();
-        let mut request = client.get(&url);
+        let mut request = client.get(url);
 
         if resume_from > 0 {
             request = request.header("Range", format!("bytes={}-", resume_from));
@@ -544,26 +1025,19 @@ impl ModelManager {
                 model_id
             );
             drop(response);
-            let _ = fs::remove_file(&partial_path);
+            let _ = fs::remove_file(partial_path);
 
             // Reset resume_from since we're starting fresh
             resume_from = 0;
 
             // Restart download without range header
-            response = client.get(&url).send().await?;
+            response = client.get(url).send().await?;
         }
 
         // Check for success or partial content status
         if !response.status().is_success()
             && response.status() != reqwest::StatusCode::PARTIAL_CONTENT
         {
-            // Mark as not downloading on error
-            {
-                let mut models = self.available_models.lock().unwrap();
-                if let Some(model) = models.get_mut(model_id) {
-                    model.is_downloading = false;
-                }
-            }
             return Err(anyhow::anyhow!(
                 "Failed to download model: HTTP {}",
                 response.status()
@@ -585,103 +1059,37 @@ impl ModelManager {
             std::fs::OpenOptions::new()
                 .create(true)
                 .append(true)
-                .open(&partial_path)?
+                .open(partial_path)?
         } else {
-            std::fs::File::create(&partial_path)?
+            std::fs::File::create(partial_path)?
         };
 
-        // Emit initial progress
-        let initial_progress = DownloadProgress {
-            model_id: model_id.to_string(),
-            downloaded,
-            total: total_size,
-            percentage: if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                0.0
-            },
-        };
-        let _ = self
-            .app_handle
-            .emit("model-download-progress", &initial_progress);
+        self.emit_download_progress(model_id, downloaded, total_size);
 
         // Throttle progress events to max 10/sec (100ms intervals)
         let mut last_emit = Instant::now();
-        let throttle_duration = Duration::from_millis(100);
 
         // Download with progress
         while let Some(chunk) = stream.next().await {
             // Check if download was cancelled
             if cancel_flag.load(Ordering::Relaxed) {
-                // Close the file before returning
-                drop(file);
-                info!("Download cancelled for: {}", model_id);
-
-                // Update state to mark as not downloading
-                {
-                    let mut models = self.available_models.lock().unwrap();
-                    if let Some(model) = models.get_mut(model_id) {
-                        model.is_downloading = false;
-                    }
-                }
-
-                // Remove cancel flag
-                {
-                    let mut flags = self.cancel_flags.lock().unwrap();
-                    flags.remove(model_id);
-                }
-
-                // Keep partial file for resume functionality
-                return Ok(());
+                return Ok(DownloadOutcome::Cancelled);
             }
 
-            let chunk = chunk.map_err(|e| {
-                // Mark as not downloading on error
-                {
-                    let mut models = self.available_models.lock().unwrap();
-                    if let Some(model) = models.get_mut(model_id) {
-                        model.is_downloading = false;
-                    }
-                }
-                e
-            })?;
-
+            let chunk = chunk?;
             file.write_all(&chunk)?;
             downloaded += chunk.len() as u64;
 
-            let percentage = if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                0.0
-            };
-
             // Emit progress event (throttled to avoid UI freeze)
-            if last_emit.elapsed() >= throttle_duration {
-                let progress = DownloadProgress {
-                    model_id: model_id.to_string(),
-                    downloaded,
-                    total: total_size,
-                    percentage,
-                };
-                let _ = self.app_handle.emit("model-download-progress", &progress);
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                self.emit_download_progress(model_id, downloaded, total_size);
                 last_emit = Instant::now();
             }
         }
 
-        // Emit final progress to ensure 100% is shown
-        let final_progress = DownloadProgress {
-            model_id: model_id.to_string(),
-            downloaded,
-            total: total_size,
-            percentage: if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                100.0
-            },
-        };
-        let _ = self
-            .app_handle
-            .emit("model-download-progress", &final_progress);
+        // Emit final progress to ensure 100% is shown, also when the server
+        // did not send a length
+        self.emit_download_progress(model_id, downloaded, total_size.max(downloaded));
 
         file.flush()?;
         drop(file); // Ensure file is closed before moving
@@ -691,13 +1099,7 @@ impl ModelManager {
             let actual_size = partial_path.metadata()?.len();
             if actual_size != total_size {
                 // Download is incomplete/corrupted - delete partial and return error
-                let _ = fs::remove_file(&partial_path);
-                {
-                    let mut models = self.available_models.lock().unwrap();
-                    if let Some(model) = models.get_mut(model_id) {
-                        model.is_downloading = false;
-                    }
-                }
+                let _ = fs::remove_file(partial_path);
                 return Err(anyhow::anyhow!(
                     "Download incomplete: expected {} bytes, got {} bytes",
                     total_size,
@@ -705,6 +1107,40 @@ impl ModelManager {
                 ));
             }
         }
+        Ok(DownloadOutcome::Completed)
+    }
+
+    fn set_downloading(&self, model_id: &str, downloading: bool) {
+        let mut models = self.available_models.lock().unwrap();
+        if let Some(model) = models.get_mut(model_id) {
+            model.is_downloading = downloading;
+        }
+    }
+
+    fn emit_download_progress(&self, model_id: &str, downloaded: u64, total: u64) {
+        let progress = DownloadProgress {
+            model_id: model_id.to_string(),
+            downloaded,
+            total,
+            percentage: if total > 0 {
+                (downloaded as f64 / total as f64) * 100.0
+            } else {
+                0.0
+            },
+        };
+        let _ = self.app_handle.emit("model-download-progress", &progress);
+    }
+
+    /// Turn a complete `.partial` payload into an installed model: extract
+    /// archives, check the digest, move it into place and mark the model as
+    /// downloaded. Shared by network downloads and offline installs.
+    fn install_payload(
+        &self,
+        model_id: &str,
+        model_info: &ModelInfo,
+        partial_path: &Path,
+    ) -> Result<()> {
+        let model_path = self.models_dir().join(&model_info.filename);
 
         // Handle directory-based models (extract tar.gz) vs file-based models
         if model_info.is_directory {
@@ -720,9 +1156,9 @@ impl ModelManager {
 
             // Use a temporary extraction directory to ensure atomic operations
             let temp_extract_dir = self
-                .models_dir
+                .models_dir()
                 .join(format!("{}.extracting", &model_info.filename));
-            let final_model_dir = self.models_dir.join(&model_info.filename);
+            let final_model_dir = self.models_dir().join(&model_info.filename);
 
             // Clean up any previous incomplete extraction
             if temp_extract_dir.exists() {
@@ -733,7 +1169,7 @@ impl ModelManager {
             fs::create_dir_all(&temp_extract_dir)?;
 
             // Open the downloaded tar.gz file
-            let tar_gz = File::open(&partial_path)?;
+            let tar_gz = File::open(partial_path)?;
             let tar = GzDecoder::new(tar_gz);
             let mut archive = Archive::new(tar);
 
@@ -757,27 +1193,26 @@ impl ModelManager {
                 anyhow::anyhow!(error_msg)
             })?;
 
-            // Find the actual extracted directory (archive might have a nested structure)
-            let extracted_dirs: Vec<_> = fs::read_dir(&temp_extract_dir)?
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-                .collect();
+            let source_dir = extracted_model_root(&temp_extract_dir)?;
 
-            if extracted_dirs.len() == 1 {
-                // Single directory extracted, move it to the final location
-                let source_dir = extracted_dirs[0].path();
-                if final_model_dir.exists() {
-                    fs::remove_dir_all(&final_model_dir)?;
-                }
-                fs::rename(&source_dir, &final_model_dir)?;
-                // Clean up temp directory
+            // Check the extracted tree before it replaces anything on disk
+            if let Err(e) = self.verify_download(model_id, model_info, &source_dir) {
                 let _ = fs::remove_dir_all(&temp_extract_dir);
-            } else {
-                // Multiple items or no directories, rename the temp directory itself
-                if final_model_dir.exists() {
-                    fs::remove_dir_all(&final_model_dir)?;
+                let _ = fs::remove_file(partial_path);
+                {
+                    let mut extracting = self.extracting_models.lock().unwrap();
+                    extracting.remove(model_id);
                 }
-                fs::rename(&temp_extract_dir, &final_model_dir)?;
+                return Err(e);
+            }
+
+            if final_model_dir.exists() {
+                fs::remove_dir_all(&final_model_dir)?;
+            }
+            fs::rename(&source_dir, &final_model_dir)?;
+            // Clean up temp directory if the model was nested inside it
+            if temp_extract_dir.exists() {
+                let _ = fs::remove_dir_all(&temp_extract_dir);
             }
 
             info!("Successfully extracted archive for model: {}", model_id);
@@ -790,12 +1225,19 @@ impl ModelManager {
             let _ = self.app_handle.emit("model-extraction-completed", model_id);
 
             // Remove the downloaded tar.gz file
-            let _ = fs::remove_file(&partial_path);
+            let _ = fs::remove_file(partial_path);
         } else {
+            if let Err(e) = self.verify_download(model_id, model_info, partial_path) {
+                let _ = fs::remove_file(partial_path);
+                return Err(e);
+            }
             // Move partial file to final location for file-based models
-            fs::rename(&partial_path, &model_path)?;
+            fs::rename(partial_path, &model_path)?;
         }
 
+        self.record_checksum(model_id, model_info, &model_path);
+        self.corrupted_models.lock().unwrap().remove(model_id);
+
         // Update download status
         {
             let mut models = self.available_models.lock().unwrap();
@@ -806,23 +1248,330 @@ impl ModelManager {
             }
         }
 
-        // Remove cancel flag on successful completion
-        {
-            let mut flags = self.cancel_flags.lock().unwrap();
-            flags.remove(model_id);
-        }
-
         // Emit completion event
         let _ = self.app_handle.emit("model-download-complete", model_id);
 
         info!(
-            "Successfully downloaded model {} to {:?}",
+            "Successfully installed model {} to {:?}",
             model_id, model_path
         );
 
         Ok(())
     }
 
+    /// Check a freshly downloaded model against its expected digest. On
+    /// mismatch the download state is reset and `model-verification-failed`
+    /// is emitted; the caller removes the bad files.
+    fn verify_download(&self, model_id: &str, model_info: &ModelInfo, path: &Path) -> Result<()> {
+        let Some(expected) = model_info.sha256.as_deref() else {
+            debug!(
+                "No checksum known for model {}, skipping verification",
+                model_id
+            );
+            return Ok(());
+        };
+
+        let mismatch = model_integrity::check_model(path, model_info.is_directory, expected)
+            .map_err(|e| anyhow::anyhow!("Failed to verify model {}: {}", model_id, e))
+            .and_then(|actual| match actual {
+                None => Ok(()),
+                Some(actual) => Err(anyhow::anyhow!(
+                    "Checksum mismatch for model {}: expected {}, got {}",
+                    model_id,
+                    expected,
+                    actual
+                )),
+            });
+
+        match mismatch {
+            Ok(()) => {
+                info!("Verified checksum for model {}", model_id);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("{}", e);
+                {
+                    let mut models = self.available_models.lock().unwrap();
+                    if let Some(model) = models.get_mut(model_id) {
+                        model.is_downloading = false;
+                        model.partial_size = 0;
+                    }
+                }
+                let _ = self.app_handle.emit(
+                    "model-verification-failed",
+                    &serde_json::json!({
+                        "model_id": model_id,
+                        "error": e.to_string()
+                    }),
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Remember the digest of a freshly installed model the catalogue has no
+    /// digest for, so later integrity checks can still detect corruption.
+    fn record_checksum(&self, model_id: &str, model_info: &ModelInfo, path: &Path) {
+        if model_info.sha256.is_some() {
+            return;
+        }
+        let digest = match model_integrity::sha256_model(path, model_info.is_directory) {
+            Ok(digest) => digest,
+            Err(e) => {
+                warn!("Failed to hash model {} after install: {}", model_id, e);
+                return;
+            }
+        };
+        let models_dir = self.models_dir();
+        let mut recorded = model_integrity::load_recorded_checksums(&models_dir);
+        recorded.insert(model_id.to_string(), digest);
+        if let Err(e) = model_integrity::save_recorded_checksums(&models_dir, &recorded) {
+            warn!("Failed to record checksum for model {}: {}", model_id, e);
+        }
+    }
+
+    fn forget_checksum(&self, model_id: &str) {
+        let models_dir = self.models_dir();
+        let mut recorded = model_integrity::load_recorded_checksums(&models_dir);
+        if recorded.remove(model_id).is_some() {
+            if let Err(e) = model_integrity::save_recorded_checksums(&models_dir, &recorded) {
+                warn!("Failed to forget checksum for model {}: {}", model_id, e);
+            }
+        }
+    }
+
+    /// Re-hash every downloaded model against its catalogue digest, or the
+    /// digest recorded when it was installed. Each model gets its own result,
+    /// so one unreadable model does not stop the others from being checked.
+    /// Corrupted models are marked as not downloaded so the UI offers a fresh
+    /// download.
+    pub fn verify_models(&self) -> Result<Vec<ModelVerification>> {
+        let downloaded: Vec<ModelInfo> = {
+            let models = self.available_models.lock().unwrap();
+            models
+                .values()
+                .filter(|m| m.is_downloaded && !m.is_downloading)
+                .cloned()
+                .collect()
+        };
+        let recorded = model_integrity::load_recorded_checksums(&self.models_dir());
+
+        let mut results = Vec::new();
+        for model in downloaded {
+            let Some(expected) = model
+                .sha256
+                .clone()
+                .or_else(|| recorded.get(&model.id).cloned())
+            else {
+                results.push(ModelVerification {
+                    model_id: model.id,
+                    status: ModelIntegrity::Unverified,
+                    expected_sha256: None,
+                    actual_sha256: None,
+                    error: None,
+                });
+                continue;
+            };
+
+            let path = self.models_dir().join(&model.filename);
+            let actual = match model_integrity::sha256_model(&path, model.is_directory) {
+                Ok(actual) => actual,
+                Err(e) => {
+                    warn!("Failed to read model {} for verification: {}", model.id, e);
+                    results.push(ModelVerification {
+                        model_id: model.id,
+                        status: ModelIntegrity::Unreadable,
+                        expected_sha256: Some(expected),
+                        actual_sha256: None,
+                        error: Some(e.to_string()),
+                    });
+                    continue;
+                }
+            };
+            let status = if actual.eq_ignore_ascii_case(expected.trim()) {
+                self.corrupted_models.lock().unwrap().remove(&model.id);
+                ModelIntegrity::Ok
+            } else {
+                warn!(
+                    "Model {} failed verification: expected {}, got {}",
+                    model.id, expected, actual
+                );
+                self.corrupted_models
+                    .lock()
+                    .unwrap()
+                    .insert(model.id.clone());
+                let mut models = self.available_models.lock().unwrap();
+                if let Some(entry) = models.get_mut(&model.id) {
+                    entry.is_downloaded = false;
+                }
+                ModelIntegrity::Corrupted
+            };
+
+            results.push(ModelVerification {
+                model_id: model.id,
+                status,
+                expected_sha256: Some(expected),
+                actual_sha256: Some(actual),
+                error: None,
+            });
+        }
+
+        results.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        Ok(results)
+    }
+
+    /// Delete a corrupted model's files and download it again.
+    pub async fn repair_model(&self, model_id: &str) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if model_info.url.is_none() {
+            return Err(anyhow::anyhow!(
+                "Model {} has no download URL and cannot be repaired",
+                model_id
+            ));
+        }
+
+        let path = self.models_dir().join(&model_info.filename);
+        if model_info.is_directory {
+            if path.exists() {
+                fs::remove_dir_all(&path)?;
+            }
+        } else if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        let partial_path = self
+            .models_dir()
+            .join(format!("{}.partial", &model_info.filename));
+        let _ = fs::remove_file(&partial_path);
+
+        info!("Re-downloading corrupted model {}", model_id);
+        self.download_model(model_id).await
+    }
+
+    /// Install a model from a file already on this machine, e.g. copied from
+    /// USB media. Unless `model_id` is given, the file is matched to a
+    /// catalogue entry by file name, then by checksum. It then goes through
+    /// the same extraction and verification as a download.
+    pub fn install_from_file(&self, path: &Path, model_id: Option<&str>) -> Result<ModelInfo> {
+        if self.relocating.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!(
+                "Cannot install while the models directory is being moved"
+            ));
+        }
+        if !path.is_file() {
+            return Err(anyhow::anyhow!("Not a file: {:?}", path));
+        }
+
+        let model_info = match model_id {
+            Some(id) => self
+                .get_model_info(id)
+                .ok_or_else(|| anyhow::anyhow!("Model not found: {}", id))?,
+            None => self.match_local_file(path)?,
+        };
+        let model_id = model_info.id.clone();
+
+        if model_info.is_downloading {
+            return Err(anyhow::anyhow!(
+                "Model {} is currently downloading",
+                model_id
+            ));
+        }
+        if self.models_dir().join(&model_info.filename).exists() {
+            return Err(anyhow::anyhow!("Model {} is already installed", model_id));
+        }
+        if model_info.is_directory != is_tar_gz(path) {
+            return Err(anyhow::anyhow!(
+                "Model {} is installed from {}",
+                model_id,
+                if model_info.is_directory {
+                    "a .tar.gz archive"
+                } else {
+                    "a single model file"
+                }
+            ));
+        }
+
+        info!("Installing model {} from {:?}", model_id, path);
+        let partial_path = self
+            .models_dir()
+            .join(format!("{}.partial", &model_info.filename));
+        let _ = fs::remove_file(model_download::chunk_state_path(&partial_path));
+        fs::copy(path, &partial_path)?;
+
+        {
+            let mut models = self.available_models.lock().unwrap();
+            if let Some(model) = models.get_mut(&model_id) {
+                model.is_downloading = true;
+            }
+        }
+
+        if let Err(e) = self.install_payload(&model_id, &model_info, &partial_path) {
+            let _ = fs::remove_file(&partial_path);
+            let mut models = self.available_models.lock().unwrap();
+            if let Some(model) = models.get_mut(&model_id) {
+                model.is_downloading = false;
+            }
+            return Err(e);
+        }
+
+        self.update_download_status()?;
+        Ok(self.get_model_info(&model_id).unwrap_or(model_info))
+    }
+
+    fn match_local_file(&self, path: &Path) -> Result<ModelInfo> {
+        let catalogue: Vec<ModelInfo> = self
+            .get_available_models()
+            .into_iter()
+            .filter(|m| !m.is_custom)
+            .collect();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        if let Some(model) = match_by_file_name(&catalogue, file_name) {
+            return Ok(model.clone());
+        }
+
+        // Renamed files can still be recognised by their checksum
+        let digest = if is_tar_gz(path) {
+            if !catalogue
+                .iter()
+                .any(|m| m.is_directory && m.sha256.is_some())
+            {
+                None
+            } else {
+                let probe_dir = tempfile::tempdir_in(self.models_dir())?;
+                Archive::new(GzDecoder::new(File::open(path)?)).unpack(probe_dir.path())?;
+                Some(model_integrity::sha256_dir(&extracted_model_root(
+                    probe_dir.path(),
+                )?)?)
+            }
+        } else if catalogue
+            .iter()
+            .any(|m| !m.is_directory && m.sha256.is_some())
+        {
+            Some(model_integrity::sha256_file(path)?)
+        } else {
+            None
+        };
+
+        let by_digest = digest.and_then(|digest| {
+            catalogue.iter().find(|m| {
+                m.is_directory == is_tar_gz(path)
+                    && m.sha256
+                        .as_deref()
+                        .is_some_and(|expected| expected.eq_ignore_ascii_case(&digest))
+            })
+        });
+        if let Some(model) = by_digest {
+            return Ok(model.clone());
+        }
+
+        Err(anyhow::anyhow!(
+            "Could not match {} to a known model; choose which model it is",
+            file_name
+        ))
+    }
+
     pub fn delete_model(&self, model_id: &str) -> Result<()> {
         debug!("ModelManager: delete_model called for: {}", model_id);
 
@@ -836,9 +1585,9 @@ impl ModelManager {
 
         debug!("ModelManager: Found model info: {:?}", model_info);
 
-        let model_path = self.models_dir.join(&model_info.filename);
+        let model_path = self.models_dir().join(&model_info.filename);
         let partial_path = self
-            .models_dir
+            .models_dir()
             .join(format!("{}.partial", &model_info.filename));
         debug!("ModelManager: Model path: {:?}", model_path);
         debug!("ModelManager: Partial path: {:?}", partial_path);
@@ -863,6 +1612,8 @@ impl ModelManager {
             }
         }
 
+        let _ = fs::remove_file(model_download::chunk_state_path(&partial_path));
+
         // Delete partial file if it exists (same for both types)
         if partial_path.exists() {
             info!("Deleting partial file at: {:?}", partial_path);
@@ -875,11 +1626,19 @@ impl ModelManager {
             return Err(anyhow::anyhow!("No model files found to delete"));
         }
 
+        self.corrupted_models.lock().unwrap().remove(model_id);
+        self.forget_checksum(model_id);
+
         // Custom models should be removed from the list entirely since they
         // have no download URL and can't be re-downloaded
         if model_info.is_custom {
-            let mut models = self.available_models.lock().unwrap();
-            models.remove(model_id);
+            {
+                let mut models = self.available_models.lock().unwrap();
+                models.remove(model_id);
+            }
+            let mut imported = model_import::load_custom_models(&self.models_dir())?;
+            imported.retain(|m| m.id != model_id);
+            model_import::save_custom_models(&self.models_dir(), &imported)?;
             debug!("ModelManager: removed custom model from available models");
         } else {
             // Update download status (marks predefined models as not downloaded)
@@ -910,9 +1669,9 @@ impl ModelManager {
             ));
         }
 
-        let model_path = self.models_dir.join(&model_info.filename);
+        let model_path = self.models_dir().join(&model_info.filename);
         let partial_path = self
-            .models_dir
+            .models_dir()
             .join(format!("{}.partial", &model_info.filename));
 
         if model_info.is_directory {
@@ -971,6 +1730,54 @@ impl ModelManager {
     }
 }
 
+fn apply_benchmark(model: &mut ModelInfo, benchmark: ModelBenchmark) {
+    model.accuracy_score = benchmark.accuracy_score();
+    model.speed_score = benchmark.speed_score();
+    model.benchmark = Some(benchmark);
+}
+
+fn is_tar_gz(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Find the catalogue entry a downloaded file belongs to, by its model file
+/// name, `<model>.tar.gz`, or the last path segment of one of its URLs.
+fn match_by_file_name<'a>(models: &'a [ModelInfo], file_name: &str) -> Option<&'a ModelInfo> {
+    models.iter().find(|m| {
+        let archive_name = format!("{}.tar.gz", m.filename);
+        let url_names = m
+            .url
+            .iter()
+            .chain(&m.mirrors)
+            .filter_map(|u| u.split(['?', '#']).next()?.rsplit('/').next());
+        let matches = [m.filename.as_str(), archive_name.as_str()]
+            .into_iter()
+            .chain(url_names)
+            .any(|name| !name.is_empty() && name.eq_ignore_ascii_case(file_name));
+        matches
+    })
+}
+
+/// Find the model inside an extracted archive. Archives either wrap the model
+/// in a single top-level directory or hold its files directly.
+fn extracted_model_root(extract_dir: &Path) -> Result<PathBuf> {
+    let extracted_dirs: Vec<_> = fs::read_dir(extract_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+        .collect();
+
+    if extracted_dirs.len() == 1 {
+        Ok(extracted_dirs[0].path())
+    } else {
+        Ok(extract_dir.to_path_buf())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1005,6 +1812,7 @@ mod tests {
                 description: "Test".to_string(),
                 filename: "ggml-small.bin".to_string(),
                 url: Some("https://example.com".to_string()),
+                mirrors: Vec::new(),
                 size_mb: 100,
                 is_downloaded: false,
                 is_downloading: false,
@@ -1017,6 +1825,8 @@ mod tests {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                sha256: None,
+                benchmark: None,
             },
         );
 
@@ -1048,6 +1858,28 @@ mod tests {
         assert!(!models.contains_key("some-directory"));
     }
 
+    #[test]
+    fn test_match_by_file_name() {
+        let catalogue: Vec<ModelInfo> = model_manifest::bundled_manifest()
+            .models
+            .iter()
+            .map(|m| m.to_model_info())
+            .collect();
+        let matched = |name: &str| match_by_file_name(&catalogue, name).map(|m| m.id.as_str());
+
+        assert_eq!(matched("ggml-small.bin"), Some("whisper-small-en"));
+        assert_eq!(
+            matched("GGML-WHISPER-SMALL-DV.BIN"),
+            Some("whisper-small-dv")
+        );
+        assert_eq!(
+            matched("parakeet-v2-int8.tar.gz"),
+            Some("parakeet-tdt-0.6b-v2")
+        );
+        assert_eq!(matched("moonshine-base.tar.gz"), Some("moonshine-base"));
+        assert_eq!(matched("notes.txt"), None);
+    }
+
     #[test]
     fn test_discover_custom_models_empty_dir() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,324 @@
+//! Measuring models against a set of reference clips.
+//!
+//! A clip set is a directory of `.wav` files, each with a `.txt` file of the
+//! same name holding the reference transcript. Results are aggregated over the
+//! whole set: WER and CER are total edits divided by total reference words or
+//! characters, and the real-time factor is processing time divided by audio
+//! duration (lower is faster).
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::audio_toolkit::audio::FrameResampler;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+/// File name of the stored results inside the models directory.
+pub const BENCHMARKS_FILE: &str = "model_benchmarks.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ModelBenchmark {
+    pub word_error_rate: f64,
+    pub char_error_rate: f64,
+    /// Processing time divided by audio duration; below 1.0 is faster than real time
+    pub real_time_factor: f64,
+    pub clips: u32,
+    pub audio_seconds: f64,
+    /// Unix timestamp (seconds) of the run
+    pub measured_at: i64,
+}
+
+impl ModelBenchmark {
+    /// Measured replacement for the hand-typed `accuracy_score`.
+    pub fn accuracy_score(&self) -> f32 {
+        (1.0 - self.word_error_rate).clamp(0.0, 1.0) as f32
+    }
+
+    /// Measured replacement for the hand-typed `speed_score`.
+    pub fn speed_score(&self) -> f32 {
+        (1.0 - self.real_time_factor).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// Emitted as `model-benchmark-progress` before each clip is transcribed.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BenchmarkProgress {
+    pub model_id: String,
+    pub model_index: u32,
+    pub model_count: u32,
+    pub clip_index: u32,
+    pub clip_count: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkClip {
+    pub audio_path: PathBuf,
+    pub reference: String,
+}
+
+/// Outcome of transcribing one clip.
+#[derive(Debug, Clone)]
+pub struct ClipResult {
+    pub reference: String,
+    pub hypothesis: String,
+    pub audio_seconds: f64,
+    pub processing: Duration,
+}
+
+/// Collect `name.wav` + `name.txt` pairs from a directory, sorted by name.
+pub fn load_clip_set(dir: &Path) -> Result<Vec<BenchmarkClip>> {
+    let mut clips = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_wav = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+        if !is_wav {
+            continue;
+        }
+        let reference_path = path.with_extension("txt");
+        if !reference_path.exists() {
+            log::warn!("Skipping benchmark clip without reference: {:?}", path);
+            continue;
+        }
+        clips.push(BenchmarkClip {
+            reference: fs::read_to_string(&reference_path)?.trim().to_string(),
+            audio_path: path,
+        });
+    }
+
+    if clips.is_empty() {
+        bail!("No .wav clips with matching .txt references in {:?}", dir);
+    }
+    clips.sort_by(|a, b| a.audio_path.cmp(&b.audio_path));
+    Ok(clips)
+}
+
+/// Read a WAV file of any common layout as 16 kHz mono samples.
+pub fn read_clip_samples(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+
+    if spec.sample_rate == WHISPER_SAMPLE_RATE {
+        return Ok(mono);
+    }
+
+    let expected = mono.len() * WHISPER_SAMPLE_RATE as usize / spec.sample_rate as usize;
+    let mut resampler = FrameResampler::new(
+        spec.sample_rate as usize,
+        WHISPER_SAMPLE_RATE as usize,
+        Duration::from_millis(30),
+    );
+    let mut out = Vec::with_capacity(expected);
+    resampler.push(&mono, |frame| out.extend_from_slice(frame));
+    // The resampler holds back a few milliseconds; trailing silence flushes them
+    let flush = vec![0.0; spec.sample_rate as usize / 10];
+    resampler.push(&flush, |frame| out.extend_from_slice(frame));
+    resampler.finish(|frame| out.extend_from_slice(frame));
+    out.truncate(expected);
+    Ok(out)
+}
+
+/// Lowercase and strip punctuation so formatting differences are not errors.
+/// Thaana vowel signs are combining marks, so only punctuation is removed.
+pub fn normalize_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| {
+            !(c.is_ascii_punctuation()
+                || matches!(
+                    c,
+                    '،' | '؟' | '؛' | '“' | '”' | '‘' | '’' | '…' | '«' | '»' | '—' | '–'
+                ))
+        })
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn edit_distance<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut current = vec![0; hypothesis.len() + 1];
+    for (i, r) in reference.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(r != h);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[hypothesis.len()]
+}
+
+/// Word edits and reference word count.
+pub fn word_errors(reference: &str, hypothesis: &str) -> (usize, usize) {
+    let reference = normalize_text(reference);
+    let hypothesis = normalize_text(hypothesis);
+    let r: Vec<&str> = reference.split_whitespace().collect();
+    let h: Vec<&str> = hypothesis.split_whitespace().collect();
+    (edit_distance(&r, &h), r.len())
+}
+
+/// Character edits (spaces excluded) and reference character count.
+pub fn char_errors(reference: &str, hypothesis: &str) -> (usize, usize) {
+    let strip = |t: &str| -> Vec<char> {
+        normalize_text(t)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect()
+    };
+    let r = strip(reference);
+    let h = strip(hypothesis);
+    (edit_distance(&r, &h), r.len())
+}
+
+pub fn aggregate(results: &[ClipResult], measured_at: i64) -> ModelBenchmark {
+    let (mut word_edits, mut words, mut char_edits, mut chars) = (0, 0, 0, 0);
+    let mut audio_seconds = 0.0;
+    let mut processing_seconds = 0.0;
+    for result in results {
+        let (edits, total) = word_errors(&result.reference, &result.hypothesis);
+        word_edits += edits;
+        words += total;
+        let (edits, total) = char_errors(&result.reference, &result.hypothesis);
+        char_edits += edits;
+        chars += total;
+        audio_seconds += result.audio_seconds;
+        processing_seconds += result.processing.as_secs_f64();
+    }
+
+    let ratio = |num: usize, den: usize| {
+        if den == 0 {
+            0.0
+        } else {
+            num as f64 / den as f64
+        }
+    };
+    ModelBenchmark {
+        word_error_rate: ratio(word_edits, words),
+        char_error_rate: ratio(char_edits, chars),
+        real_time_factor: if audio_seconds > 0.0 {
+            processing_seconds / audio_seconds
+        } else {
+            0.0
+        },
+        clips: results.len() as u32,
+        audio_seconds,
+        measured_at,
+    }
+}
+
+pub fn load_benchmarks(models_dir: &Path) -> HashMap<String, ModelBenchmark> {
+    let path = models_dir.join(BENCHMARKS_FILE);
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| match serde_json::from_str(&json) {
+            Ok(results) => Some(results),
+            Err(e) => {
+                log::warn!("Ignoring unreadable benchmark results: {}", e);
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_benchmarks(
+    models_dir: &Path,
+    benchmarks: &HashMap<String, ModelBenchmark>,
+) -> Result<()> {
+    fs::write(
+        models_dir.join(BENCHMARKS_FILE),
+        serde_json::to_string_pretty(benchmarks)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn error_rates_ignore_case_and_punctuation() {
+        assert_eq!(word_errors("Hello, world!", "hello world"), (0, 2));
+        assert_eq!(word_errors("the cat sat", "the bat sat down"), (2, 3));
+        assert_eq!(char_errors("abc", "abd"), (1, 3));
+        // Thaana vowel signs must survive normalisation
+        assert_eq!(word_errors("ދިވެހި، ބަސް", "ދިވެހި ބަސް"), (0, 2));
+        assert_eq!(word_errors("ދިވެހި", "ދވހ").0, 1);
+    }
+
+    #[test]
+    fn aggregate_weights_by_reference_length() {
+        let results = vec![
+            ClipResult {
+                reference: "one two three four".into(),
+                hypothesis: "one two three four".into(),
+                audio_seconds: 3.0,
+                processing: Duration::from_millis(600),
+            },
+            ClipResult {
+                reference: "five six".into(),
+                hypothesis: "five".into(),
+                audio_seconds: 1.0,
+                processing: Duration::from_millis(200),
+            },
+        ];
+        let benchmark = aggregate(&results, 0);
+
+        assert!((benchmark.word_error_rate - 1.0 / 6.0).abs() < 1e-9);
+        assert!((benchmark.real_time_factor - 0.2).abs() < 1e-9);
+        assert_eq!(benchmark.clips, 2);
+        assert!((benchmark.speed_score() - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn loads_clips_and_resamples_audio() {
+        let dir = TempDir::new().unwrap();
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(dir.path().join("a.wav"), spec).unwrap();
+        for _ in 0..48_000 {
+            writer.write_sample(1000i16).unwrap();
+            writer.write_sample(3000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        fs::write(dir.path().join("a.txt"), "reference\n").unwrap();
+        fs::write(dir.path().join("orphan.wav"), b"").unwrap();
+
+        let clips = load_clip_set(dir.path()).unwrap();
+        assert_eq!(clips.len(), 1);
+        assert_eq!(clips[0].reference, "reference");
+
+        let samples = read_clip_samples(&clips[0].audio_path).unwrap();
+        assert_eq!(samples.len(), 16_000);
+        let mid = samples[8000];
+        assert!((mid - 2000.0 / 32768.0).abs() < 0.01, "got {}", mid);
+    }
+}
@@ -0,0 +1,600 @@
+//! Multi-connection model downloader.
+//!
+//! The file is split into fixed-size byte ranges that are fetched over several
+//! connections at once and written in place into the `.partial` file. Finished
+//! ranges are recorded in a `.parts` file next to it, so a cancelled or failed
+//! download resumes where it stopped, even when it resumes from a different
+//! mirror. Sources are tried in order; servers without range support are
+//! downloaded as a single stream instead.
+
+use anyhow::{bail, Result};
+use futures_util::StreamExt;
+use log::{info, warn};
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::settings::AppSettings;
+
+const DEFAULT_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+pub const MAX_DOWNLOAD_CONNECTIONS: u8 = 8;
+
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    pub connections: usize,
+    pub chunk_size: u64,
+    /// `None` downloads at full speed
+    pub max_bytes_per_sec: Option<u64>,
+}
+
+impl DownloadOptions {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            connections: settings
+                .download_connections
+                .clamp(1, MAX_DOWNLOAD_CONNECTIONS) as usize,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_bytes_per_sec: settings
+                .download_speed_limit_kbps
+                .filter(|&kbps| kbps > 0)
+                .map(|kbps| kbps as u64 * 1024),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadOutcome {
+    Completed,
+    Cancelled,
+}
+
+/// Path of the file recording finished ranges for `partial`.
+pub fn chunk_state_path(partial: &Path) -> PathBuf {
+    let mut name = partial.as_os_str().to_owned();
+    name.push(".parts");
+    PathBuf::from(name)
+}
+
+/// Download `urls[0]` into `dest`, falling back to the following mirrors on
+/// error. `progress` receives `(downloaded, total)` in bytes.
+pub async fn download_chunked(
+    client: &reqwest::Client,
+    urls: &[String],
+    dest: &Path,
+    options: &DownloadOptions,
+    cancel: &AtomicBool,
+    progress: &(dyn Fn(u64, u64) + Sync),
+) -> Result<DownloadOutcome> {
+    let limiter = RateLimiter::new(options.max_bytes_per_sec);
+    let mut last_error = None;
+
+    for url in urls {
+        info!(
+            "Downloading {} with up to {} connections",
+            url, options.connections
+        );
+        match download_from(client, url, dest, options, &limiter, cancel, progress).await {
+            Ok(outcome) => return Ok(outcome),
+            Err(e) => {
+                warn!("Download from {} failed: {}", url, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(match last_error {
+        Some(e) => e.context("All download sources failed"),
+        None => anyhow::anyhow!("No download URL"),
+    })
+}
+
+async fn download_from(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    options: &DownloadOptions,
+    limiter: &RateLimiter,
+    cancel: &AtomicBool,
+    progress: &(dyn Fn(u64, u64) + Sync),
+) -> Result<DownloadOutcome> {
+    // Asking for the first byte tells us both the size and whether ranges work
+    let probe = client.get(url).header(RANGE, "bytes=0-0").send().await?;
+    let total = match probe.status() {
+        StatusCode::PARTIAL_CONTENT => content_range_total(&probe)?,
+        status if status.is_success() => {
+            warn!(
+                "{} does not support range requests, using a single stream",
+                url
+            );
+            return stream_whole(probe, dest, limiter, cancel, progress).await;
+        }
+        status => bail!("HTTP {}", status),
+    };
+    drop(probe);
+
+    let mut state = ChunkState::open(dest, total, options.chunk_size)?;
+    let pending: Vec<(u64, (u64, u64))> = (0..state.chunk_count())
+        .filter(|index| !state.done.contains(index))
+        .map(|index| (index, state.chunk_range(index)))
+        .collect();
+    let downloaded = AtomicU64::new(state.done.iter().map(|&index| state.chunk_len(index)).sum());
+    progress(downloaded.load(Ordering::Relaxed), total);
+
+    let mut fetches =
+        futures_util::stream::iter(pending.into_iter().map(|(index, (start, end))| {
+            let downloaded = &downloaded;
+            async move {
+                fetch_range(
+                    client, url, dest, start, end, limiter, cancel, downloaded, total, progress,
+                )
+                .await
+                .map(|outcome| (index, outcome))
+            }
+        }))
+        .buffer_unordered(options.connections.max(1));
+
+    while let Some(result) = fetches.next().await {
+        match result? {
+            (index, DownloadOutcome::Completed) => state.mark_done(index)?,
+            (_, DownloadOutcome::Cancelled) => return Ok(DownloadOutcome::Cancelled),
+        }
+    }
+    drop(fetches);
+
+    let actual = dest.metadata()?.len();
+    if actual != total {
+        bail!(
+            "Download incomplete: expected {} bytes, got {} bytes",
+            total,
+            actual
+        );
+    }
+    state.finish();
+    Ok(DownloadOutcome::Completed)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn fetch_range(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    start: u64,
+    end: u64,
+    limiter: &RateLimiter,
+    cancel: &AtomicBool,
+    downloaded: &AtomicU64,
+    total: u64,
+    progress: &(dyn Fn(u64, u64) + Sync),
+) -> Result<DownloadOutcome> {
+    let response = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .await?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        bail!("Range request returned HTTP {}", response.status());
+    }
+
+    let mut file = OpenOptions::new().write(true).open(dest)?;
+    file.seek(SeekFrom::Start(start))?;
+
+    let expected = end - start + 1;
+    let mut received = 0u64;
+    let mut stream = response.bytes_stream();
+    while let Some(bytes) = stream.next().await {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(DownloadOutcome::Cancelled);
+        }
+        let bytes = bytes?;
+        if received + bytes.len() as u64 > expected {
+            bail!(
+                "Server sent more data than requested for bytes {}-{}",
+                start,
+                end
+            );
+        }
+        limiter.acquire(bytes.len() as u64).await;
+        file.write_all(&bytes)?;
+        received += bytes.len() as u64;
+        let done = downloaded.fetch_add(bytes.len() as u64, Ordering::Relaxed) + bytes.len() as u64;
+        progress(done, total);
+    }
+
+    if received != expected {
+        bail!("Connection closed early for bytes {}-{}", start, end);
+    }
+    file.flush()?;
+    Ok(DownloadOutcome::Completed)
+}
+
+/// Fallback for servers that ignore `Range`: write the whole body in order.
+async fn stream_whole(
+    response: reqwest::Response,
+    dest: &Path,
+    limiter: &RateLimiter,
+    cancel: &AtomicBool,
+    progress: &(dyn Fn(u64, u64) + Sync),
+) -> Result<DownloadOutcome> {
+    let _ = fs::remove_file(chunk_state_path(dest));
+    let total = response.content_length().unwrap_or(0);
+    let mut file = File::create(dest)?;
+    let mut downloaded = 0u64;
+    let mut stream = response.bytes_stream();
+    while let Some(bytes) = stream.next().await {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(DownloadOutcome::Cancelled);
+        }
+        let bytes = bytes?;
+        limiter.acquire(bytes.len() as u64).await;
+        file.write_all(&bytes)?;
+        downloaded += bytes.len() as u64;
+        progress(downloaded, total);
+    }
+    file.flush()?;
+
+    if total > 0 && downloaded != total {
+        bail!(
+            "Download incomplete: expected {} bytes, got {} bytes",
+            total,
+            downloaded
+        );
+    }
+    Ok(DownloadOutcome::Completed)
+}
+
+fn content_range_total(response: &reqwest::Response) -> Result<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit('/').next())
+        .and_then(|total| total.trim().parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Server did not report the file size"))
+}
+
+/// Finished ranges of an in-progress download, persisted as a header line
+/// (`<total> <chunk size>`) followed by one chunk index per line.
+struct ChunkState {
+    path: PathBuf,
+    total: u64,
+    chunk_size: u64,
+    done: HashSet<u64>,
+}
+
+impl ChunkState {
+    fn open(dest: &Path, total: u64, chunk_size: u64) -> Result<Self> {
+        let path = chunk_state_path(dest);
+        let header = format!("{} {}", total, chunk_size);
+
+        let existing = fs::read_to_string(&path).ok().and_then(|content| {
+            let mut lines = content.lines();
+            (lines.next() == Some(header.as_str()) && dest.exists())
+                .then(|| lines.filter_map(|l| l.trim().parse().ok()).collect())
+        });
+
+        if let Some(done) = existing {
+            return Ok(Self {
+                path,
+                total,
+                chunk_size,
+                done,
+            });
+        }
+
+        // A single-stream attempt leaves a contiguous prefix and no state
+        // file; keep the chunks it fully covers. A state file for another
+        // size or a prefix longer than the file means a different download.
+        let stale = path.exists();
+        let prefix = fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(dest)?;
+        let prefix = if stale || prefix > total {
+            file.set_len(0)?;
+            0
+        } else {
+            prefix
+        };
+        fs::write(&path, format!("{}\n", header))?;
+
+        let mut state = Self {
+            path,
+            total,
+            chunk_size,
+            done: HashSet::new(),
+        };
+        for index in 0..state.chunk_count() {
+            if state.chunk_range(index).1 < prefix {
+                state.mark_done(index)?;
+            }
+        }
+        Ok(state)
+    }
+
+    fn chunk_count(&self) -> u64 {
+        self.total.div_ceil(self.chunk_size)
+    }
+
+    /// Inclusive byte range of a chunk.
+    fn chunk_range(&self, index: u64) -> (u64, u64) {
+        let start = index * self.chunk_size;
+        let end = (start + self.chunk_size).min(self.total) - 1;
+        (start, end)
+    }
+
+    fn chunk_len(&self, index: u64) -> u64 {
+        let (start, end) = self.chunk_range(index);
+        end - start + 1
+    }
+
+    fn mark_done(&mut self, index: u64) -> Result<()> {
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        writeln!(file, "{}", index)?;
+        self.done.insert(index);
+        Ok(())
+    }
+
+    fn finish(self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Caps combined throughput across all connections.
+struct RateLimiter {
+    bytes_per_sec: Option<u64>,
+    start: Instant,
+    consumed: AtomicU64,
+}
+
+impl RateLimiter {
+    fn new(bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            bytes_per_sec,
+            start: Instant::now(),
+            consumed: AtomicU64::new(0),
+        }
+    }
+
+    async fn acquire(&self, bytes: u64) {
+        let Some(rate) = self.bytes_per_sec else {
+            return;
+        };
+        let consumed = self.consumed.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let due = Duration::from_secs_f64(consumed as f64 / rate as f64);
+        let elapsed = self.start.elapsed();
+        if due > elapsed {
+            tokio::time::sleep(due - elapsed).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Minimal HTTP/1.1 server standing in for a model host.
+    fn serve(body: Vec<u8>, ranges: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let body = body.clone();
+                std::thread::spawn(move || {
+                    let mut range = None;
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                            break;
+                        }
+                        if let Some(value) = line.to_ascii_lowercase().strip_prefix("range: bytes=")
+                        {
+                            let (start, end) = value.trim().split_once('-').unwrap();
+                            range = Some((
+                                start.parse::<usize>().unwrap(),
+                                end.parse::<usize>().unwrap(),
+                            ));
+                        }
+                    }
+
+                    let (status, extra, slice) = match range.filter(|_| ranges) {
+                        Some((start, end)) => (
+                            "206 Partial Content",
+                            format!("Content-Range: bytes {}-{}/{}\r\n", start, end, body.len()),
+                            &body[start..=end],
+                        ),
+                        None => ("200 OK", String::new(), &body[..]),
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+                        status,
+                        slice.len(),
+                        extra
+                    );
+                    let _ = stream.write_all(head.as_bytes());
+                    let _ = stream.write_all(slice);
+                });
+            }
+        });
+        url
+    }
+
+    fn unreachable_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/model.bin", listener.local_addr().unwrap())
+    }
+
+    fn body(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    fn run<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn options(max_bytes_per_sec: Option<u64>) -> DownloadOptions {
+        DownloadOptions {
+            connections: 4,
+            chunk_size: 16 * 1024,
+            max_bytes_per_sec,
+        }
+    }
+
+    #[test]
+    fn parallel_download_falls_back_to_mirror() {
+        let data = body(100_000);
+        let urls = vec![unreachable_url(), serve(data.clone(), true)];
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("model.bin.partial");
+        let last = Mutex::new((0, 0));
+
+        let outcome = run(download_chunked(
+            &reqwest::Client::new(),
+            &urls,
+            &dest,
+            &options(None),
+            &AtomicBool::new(false),
+            &|done, total| *last.lock().unwrap() = (done, total),
+        ))
+        .unwrap();
+
+        assert_eq!(outcome, DownloadOutcome::Completed);
+        assert_eq!(fs::read(&dest).unwrap(), data);
+        assert_eq!(*last.lock().unwrap(), (100_000, 100_000));
+        assert!(!chunk_state_path(&dest).exists());
+    }
+
+    #[test]
+    fn resumes_from_recorded_chunks() {
+        let data = body(50_000);
+        let url = serve(data.clone(), true);
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("model.bin.partial");
+
+        // First chunk already on disk from an earlier attempt
+        let mut partial = data[..16 * 1024].to_vec();
+        partial.resize(data.len(), 0);
+        fs::write(&dest, &partial).unwrap();
+        fs::write(
+            chunk_state_path(&dest),
+            format!("{} {}\n0\n", data.len(), 16 * 1024),
+        )
+        .unwrap();
+        let first = Mutex::new(None);
+
+        run(download_chunked(
+            &reqwest::Client::new(),
+            &[url],
+            &dest,
+            &options(None),
+            &AtomicBool::new(false),
+            &|done, _| {
+                first.lock().unwrap().get_or_insert(done);
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(*first.lock().unwrap(), Some(16 * 1024));
+        assert_eq!(fs::read(&dest).unwrap(), data);
+    }
+
+    #[test]
+    fn adopts_a_partial_file_from_a_single_stream() {
+        let data = body(50_000);
+        let url = serve(data.clone(), true);
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("model.bin.partial");
+
+        // 20 000 contiguous bytes cover the first 16 KiB chunk
+        fs::write(&dest, &data[..20_000]).unwrap();
+        let first = Mutex::new(None);
+
+        run(download_chunked(
+            &reqwest::Client::new(),
+            &[url],
+            &dest,
+            &options(None),
+            &AtomicBool::new(false),
+            &|done, _| {
+                first.lock().unwrap().get_or_insert(done);
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(*first.lock().unwrap(), Some(16 * 1024));
+        assert_eq!(fs::read(&dest).unwrap(), data);
+    }
+
+    #[test]
+    fn streams_when_ranges_are_unsupported() {
+        let data = body(40_000);
+        let url = serve(data.clone(), false);
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("model.bin.partial");
+
+        let outcome = run(download_chunked(
+            &reqwest::Client::new(),
+            &[url],
+            &dest,
+            &options(None),
+            &AtomicBool::new(false),
+            &|_, _| {},
+        ))
+        .unwrap();
+
+        assert_eq!(outcome, DownloadOutcome::Completed);
+        assert_eq!(fs::read(&dest).unwrap(), data);
+    }
+
+    #[test]
+    fn bandwidth_cap_slows_download() {
+        let data = body(64 * 1024);
+        let url = serve(data.clone(), true);
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("model.bin.partial");
+
+        let started = Instant::now();
+        run(download_chunked(
+            &reqwest::Client::new(),
+            &[url],
+            &dest,
+            &options(Some(128 * 1024)),
+            &AtomicBool::new(false),
+            &|_, _| {},
+        ))
+        .unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(450));
+        assert_eq!(fs::read(&dest).unwrap(), data);
+    }
+
+    #[test]
+    fn all_sources_failing_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let result = run(download_chunked(
+            &reqwest::Client::new(),
+            &[unreachable_url(), unreachable_url()],
+            &dir.path().join("model.bin.partial"),
+            &options(None),
+            &AtomicBool::new(false),
+            &|_, _| {},
+        ));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Importing user-provided models of any engine type.
+//!
+//! Whisper models are single GGML/GGUF files recognised by their magic bytes.
+//! ONNX engines ship as directories and are recognised by the files their
+//! loaders in transcribe-rs expect. Imported models are copied into the models
+//! directory and their metadata is kept in `custom_models.json` so they are
+//! listed like built-in models on the next start.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use super::model::EngineType;
+use super::model_manifest::ManifestModel;
+
+/// File name of the imported model registry inside the models directory.
+pub const CUSTOM_MODELS_FILE: &str = "custom_models.json";
+
+/// `ggml` written as a little-endian u32, as whisper.cpp does
+const GGML_MAGIC: &[u8; 4] = b"lmgg";
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+/// What an import would register, shown to the user before importing.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelImportPreview {
+    pub suggested_name: String,
+    pub engine_type: EngineType,
+    pub is_directory: bool,
+    pub size_mb: u64,
+    /// Best guess from the engine and file name; empty when unknown
+    pub supported_languages: Vec<String>,
+}
+
+/// Work out which engine can load the model at `path`.
+pub fn detect_engine(path: &Path) -> Result<EngineType> {
+    if path.is_dir() {
+        let has = |name: &str| path.join(name).is_file();
+        let has_onnx =
+            |stem: &str| has(&format!("{}.onnx", stem)) || has(&format!("{}.int8.onnx", stem));
+
+        if has_onnx("encoder-model") && has_onnx("decoder_joint-model") && has("vocab.txt") {
+            return Ok(EngineType::Parakeet);
+        }
+        if has("encoder_model.onnx") && has("decoder_model_merged.onnx") && has("tokenizer.json") {
+            return Ok(EngineType::Moonshine);
+        }
+        if has_onnx("model") && has("tokens.txt") {
+            return Ok(EngineType::SenseVoice);
+        }
+        bail!(
+            "Unrecognised model directory {:?}: expected Parakeet, Moonshine or SenseVoice ONNX files",
+            path
+        );
+    }
+
+    let mut magic = [0u8; 4];
+    File::open(path)?
+        .read_exact(&mut magic)
+        .map_err(|_| anyhow::anyhow!("Model file {:?} is too small", path))?;
+    if &magic == GGML_MAGIC || &magic == GGUF_MAGIC {
+        Ok(EngineType::Whisper)
+    } else {
+        bail!(
+            "Unrecognised model file {:?}: not a GGML or GGUF file",
+            path
+        )
+    }
+}
+
+/// Guess the languages a model covers from its engine and name.
+pub fn infer_languages(engine_type: &EngineType, name: &str) -> Vec<String> {
+    let lower = name.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    if tokens.iter().any(|t| *t == "dv" || *t == "dhivehi") {
+        return vec!["dv".to_string()];
+    }
+    if tokens.contains(&"en") {
+        return vec!["en".to_string()];
+    }
+
+    match engine_type {
+        EngineType::Parakeet | EngineType::Moonshine => vec!["en".to_string()],
+        EngineType::SenseVoice => ["zh", "en", "yue", "ja", "ko"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect(),
+        // Multilingual Whisper; an empty list means "any language"
+        EngineType::Whisper => Vec::new(),
+    }
+}
+
+/// Turn a display name into a model id / filename stem.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches(|c| c == '-' || c == '.').to_string();
+    if slug.is_empty() {
+        "custom-model".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Total size of a file or directory tree, in whole megabytes.
+pub fn size_mb(path: &Path) -> Result<u64> {
+    fn bytes(path: &Path) -> Result<u64> {
+        if path.is_dir() {
+            let mut total = 0;
+            for entry in fs::read_dir(path)? {
+                total += bytes(&entry?.path())?;
+            }
+            Ok(total)
+        } else {
+            Ok(path.metadata()?.len())
+        }
+    }
+    Ok(bytes(path)? / (1024 * 1024))
+}
+
+/// Copy a file or directory tree to `dest`.
+pub fn copy_model(src: &Path, dest: &Path) -> Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_model(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(src, dest)?;
+    }
+    Ok(())
+}
+
+pub fn load_custom_models(models_dir: &Path) -> Result<Vec<ManifestModel>> {
+    let path = models_dir.join(CUSTOM_MODELS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_custom_models(models_dir: &Path, models: &[ManifestModel]) -> Result<()> {
+    let path = models_dir.join(CUSTOM_MODELS_FILE);
+    let tmp = models_dir.join(format!("{}.tmp", CUSTOM_MODELS_FILE));
+    fs::write(&tmp, serde_json::to_string_pretty(models)?)?;
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn touch(dir: &Path, name: &str) {
+        fs::write(dir.join(name), b"x").unwrap();
+    }
+
+    #[test]
+    fn detects_onnx_directories() {
+        let parakeet = TempDir::new().unwrap();
+        for name in [
+            "encoder-model.int8.onnx",
+            "decoder_joint-model.int8.onnx",
+            "nemo128.onnx",
+            "vocab.txt",
+        ] {
+            touch(parakeet.path(), name);
+        }
+        assert!(matches!(
+            detect_engine(parakeet.path()).unwrap(),
+            EngineType::Parakeet
+        ));
+
+        let moonshine = TempDir::new().unwrap();
+        for name in [
+            "encoder_model.onnx",
+            "decoder_model_merged.onnx",
+            "tokenizer.json",
+        ] {
+            touch(moonshine.path(), name);
+        }
+        assert!(matches!(
+            detect_engine(moonshine.path()).unwrap(),
+            EngineType::Moonshine
+        ));
+
+        let sense_voice = TempDir::new().unwrap();
+        touch(sense_voice.path(), "model.onnx");
+        touch(sense_voice.path(), "tokens.txt");
+        assert!(matches!(
+            detect_engine(sense_voice.path()).unwrap(),
+            EngineType::SenseVoice
+        ));
+
+        let unknown = TempDir::new().unwrap();
+        touch(unknown.path(), "model.onnx");
+        assert!(detect_engine(unknown.path()).is_err());
+    }
+
+    #[test]
+    fn detects_whisper_files_by_magic() {
+        let dir = TempDir::new().unwrap();
+        let ggml = dir.path().join("a.bin");
+        fs::write(&ggml, b"lmgg\x01\x02").unwrap();
+        let gguf = dir.path().join("b.gguf");
+        fs::write(&gguf, b"GGUF\x03\x00").unwrap();
+        let other = dir.path().join("c.bin");
+        fs::write(&other, b"PK\x03\x04").unwrap();
+
+        assert!(matches!(detect_engine(&ggml).unwrap(), EngineType::Whisper));
+        assert!(matches!(detect_engine(&gguf).unwrap(), EngineType::Whisper));
+        assert!(detect_engine(&other).is_err());
+    }
+
+    #[test]
+    fn infers_languages_and_ids() {
+        assert_eq!(
+            infer_languages(&EngineType::Parakeet, "parakeet-dv-finetune"),
+            vec!["dv"]
+        );
+        assert_eq!(
+            infer_languages(&EngineType::Whisper, "ggml-base.en.bin"),
+            vec!["en"]
+        );
+        assert!(infer_languages(&EngineType::Whisper, "ggml-large-v3").is_empty());
+        assert_eq!(slugify("  Dhivehi ONNX (v2) "), "dhivehi-onnx-v2");
+        assert_eq!(slugify("!!!"), "custom-model");
+    }
+}
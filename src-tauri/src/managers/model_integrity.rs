@@ -0,0 +1,221 @@
+//! SHA-256 digests used to check downloaded models.
+//!
+//! File models hash their bytes. Directory models hash a listing of every
+//! regular file in the tree (relative path, size and contents, in sorted path
+//! order), so the same archive always extracts to the same digest regardless
+//! of filesystem iteration order.
+//!
+//! Models the catalogue has no digest for get one recorded when they are
+//! installed, so later checks still catch files that change on disk.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Outcome of checking one model against its expected digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelIntegrity {
+    Ok,
+    Corrupted,
+    /// No expected digest is known, so the files were not checked.
+    Unverified,
+    /// The files could not be read; `error` says why.
+    Unreadable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelVerification {
+    pub model_id: String,
+    pub status: ModelIntegrity,
+    pub expected_sha256: Option<String>,
+    pub actual_sha256: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Digests recorded at install time, keyed by model id.
+pub const RECORDED_CHECKSUMS_FILE: &str = "model_checksums.json";
+
+pub fn load_recorded_checksums(models_dir: &Path) -> HashMap<String, String> {
+    let path = models_dir.join(RECORDED_CHECKSUMS_FILE);
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| match serde_json::from_str(&json) {
+            Ok(checksums) => Some(checksums),
+            Err(e) => {
+                log::warn!("Ignoring unreadable recorded model checksums: {}", e);
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_recorded_checksums(
+    models_dir: &Path,
+    checksums: &HashMap<String, String>,
+) -> Result<()> {
+    fs::write(
+        models_dir.join(RECORDED_CHECKSUMS_FILE),
+        serde_json::to_string_pretty(checksums)?,
+    )?;
+    Ok(())
+}
+
+/// Hex-encoded SHA-256 of a single file.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_file_into(&mut hasher, path)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hex-encoded SHA-256 of every regular file below `root`.
+pub fn sha256_dir(root: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(root, root, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for relative in files {
+        let path = root.join(&relative);
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        hasher.update(name.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(path.metadata()?.len().to_le_bytes());
+        hash_file_into(&mut hasher, &path)?;
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Digest of a model on disk, picking the file or tree form.
+pub fn sha256_model(path: &Path, is_directory: bool) -> Result<String> {
+    if is_directory {
+        sha256_dir(path)
+    } else {
+        sha256_file(path)
+    }
+}
+
+/// Compare a model on disk against an expected digest. Returns the actual
+/// digest on mismatch.
+pub fn check_model(path: &Path, is_directory: bool, expected: &str) -> Result<Option<String>> {
+    let actual = sha256_model(path, is_directory)?;
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(None)
+    } else {
+        Ok(Some(actual))
+    }
+}
+
+fn hash_file_into(hasher: &mut Sha256, path: &Path) -> Result<()> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let read = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        hasher.update(&buf[..read]);
+    }
+    Ok(())
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if file_type.is_file() {
+            out.push(path.strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn file_digest_matches_known_vector() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("model.bin");
+        fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            check_model(
+                &path,
+                false,
+                "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn dir_digest_is_stable_and_detects_changes() {
+        let first = TempDir::new().unwrap();
+        fs::create_dir(first.path().join("nested")).unwrap();
+        fs::write(first.path().join("nested/b.onnx"), b"weights").unwrap();
+        fs::write(first.path().join("a.txt"), b"tokens").unwrap();
+
+        // Same content, created in the opposite order
+        let second = TempDir::new().unwrap();
+        fs::write(second.path().join("a.txt"), b"tokens").unwrap();
+        fs::create_dir(second.path().join("nested")).unwrap();
+        fs::write(second.path().join("nested/b.onnx"), b"weights").unwrap();
+
+        let expected = sha256_dir(first.path()).unwrap();
+        assert_eq!(sha256_dir(second.path()).unwrap(), expected);
+
+        fs::write(second.path().join("nested/b.onnx"), b"weightz").unwrap();
+        let mismatch = check_model(second.path(), true, &expected).unwrap();
+        assert!(mismatch.is_some());
+        assert_ne!(mismatch.unwrap(), expected);
+    }
+
+    #[test]
+    fn recorded_checksums_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(load_recorded_checksums(dir.path()).is_empty());
+
+        let checksums = HashMap::from([("whisper-small-dv".to_string(), "ab".repeat(32))]);
+        save_recorded_checksums(dir.path(), &checksums).unwrap();
+        assert_eq!(load_recorded_checksums(dir.path()), checksums);
+
+        fs::write(dir.path().join(RECORDED_CHECKSUMS_FILE), "{ not json").unwrap();
+        assert!(load_recorded_checksums(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn dir_digest_covers_file_names() {
+        let first = TempDir::new().unwrap();
+        fs::write(first.path().join("a.txt"), b"same").unwrap();
+        let second = TempDir::new().unwrap();
+        fs::write(second.path().join("b.txt"), b"same").unwrap();
+
+        assert_ne!(
+            sha256_dir(first.path()).unwrap(),
+            sha256_dir(second.path()).unwrap()
+        );
+    }
+}
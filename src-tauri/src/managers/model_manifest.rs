@@ -0,0 +1,295 @@
+//! Versioned JSON catalogue of downloadable models.
+//!
+//! A default manifest ships with the app. Users can point the app at a newer
+//! one (an HTTP(S) URL or a local file) to pick up new models, such as a fresh
+//! Dhivehi fine-tune, without waiting for a release. The last refreshed
+//! manifest is cached next to the models and preferred over the bundled copy
+//! when its revision is at least as new.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::model::{EngineType, ModelInfo};
+
+/// Manifest layout understood by this build. Bump when fields change
+/// incompatibly.
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// File name of the cached manifest inside the models directory.
+pub const CACHED_MANIFEST_FILE: &str = "model_manifest.json";
+
+const BUNDLED_MANIFEST: &str = include_str!("../../resources/model_manifest.json");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelManifest {
+    pub schema_version: u32,
+    /// Increases with every published change to the model list.
+    pub revision: u64,
+    pub models: Vec<ManifestModel>,
+}
+
+/// Unknown fields are ignored so newer manifests still load in older builds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestModel {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub filename: String,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    pub size_mb: u64,
+    pub is_directory: bool,
+    pub engine_type: EngineType,
+    pub accuracy_score: f32,
+    pub speed_score: f32,
+    #[serde(default)]
+    pub supports_translation: bool,
+    #[serde(default)]
+    pub is_recommended: bool,
+    pub supported_languages: Vec<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl ManifestModel {
+    pub fn to_model_info(&self) -> ModelInfo {
+        ModelInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            filename: self.filename.clone(),
+            url: self.url.clone(),
+            mirrors: self.mirrors.clone(),
+            size_mb: self.size_mb,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory: self.is_directory,
+            engine_type: self.engine_type.clone(),
+            accuracy_score: self.accuracy_score,
+            speed_score: self.speed_score,
+            supports_translation: self.supports_translation,
+            is_recommended: self.is_recommended,
+            supported_languages: self.supported_languages.clone(),
+            is_custom: false,
+            sha256: self.sha256.as_ref().map(|h| h.to_ascii_lowercase()),
+            benchmark: None,
+        }
+    }
+}
+
+/// Parse and validate a manifest document.
+pub fn parse_manifest(json: &str) -> Result<ModelManifest> {
+    let manifest: ModelManifest = serde_json::from_str(json)?;
+    validate_manifest(&manifest)?;
+    Ok(manifest)
+}
+
+/// The manifest shipped with this build. Its validity is covered by tests.
+pub fn bundled_manifest() -> ModelManifest {
+    parse_manifest(BUNDLED_MANIFEST).expect("bundled model manifest is valid")
+}
+
+/// Pick the manifest to start with: the cached refresh if it is valid and at
+/// least as new as the bundled one, otherwise the bundled default.
+pub fn load_manifest(models_dir: &Path) -> ModelManifest {
+    let bundled = bundled_manifest();
+    let cached_path = models_dir.join(CACHED_MANIFEST_FILE);
+    if !cached_path.exists() {
+        return bundled;
+    }
+
+    match std::fs::read_to_string(&cached_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| parse_manifest(&json))
+    {
+        Ok(cached) if cached.revision >= bundled.revision => cached,
+        Ok(cached) => {
+            log::info!(
+                "Ignoring cached model manifest revision {} older than bundled revision {}",
+                cached.revision,
+                bundled.revision
+            );
+            bundled
+        }
+        Err(e) => {
+            log::warn!("Ignoring invalid cached model manifest: {}", e);
+            bundled
+        }
+    }
+}
+
+pub fn validate_manifest(manifest: &ModelManifest) -> Result<()> {
+    if manifest.schema_version != MANIFEST_SCHEMA_VERSION {
+        bail!(
+            "Unsupported model manifest schema version {} (expected {})",
+            manifest.schema_version,
+            MANIFEST_SCHEMA_VERSION
+        );
+    }
+    if manifest.models.is_empty() {
+        bail!("Model manifest lists no models");
+    }
+
+    let mut ids = HashSet::new();
+    let mut filenames = HashSet::new();
+    for model in &manifest.models {
+        if model.id.trim().is_empty() || model.name.trim().is_empty() {
+            bail!("Model manifest entry is missing an id or name");
+        }
+        if !ids.insert(model.id.as_str()) {
+            bail!("Duplicate model id in manifest: {}", model.id);
+        }
+        if model.filename.is_empty()
+            || model.filename.contains(['/', '\\'])
+            || model.filename.starts_with('.')
+        {
+            bail!(
+                "Model {} has an invalid filename: {:?}",
+                model.id,
+                model.filename
+            );
+        }
+        if !filenames.insert(model.filename.as_str()) {
+            bail!("Duplicate model filename in manifest: {}", model.filename);
+        }
+        for url in model.url.iter().chain(&model.mirrors) {
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                bail!("Model {} has a non-HTTP download URL: {}", model.id, url);
+            }
+        }
+        if model.url.is_none() && !model.mirrors.is_empty() {
+            bail!("Model {} lists mirrors without a primary URL", model.id);
+        }
+        for (label, score) in [
+            ("accuracy_score", model.accuracy_score),
+            ("speed_score", model.speed_score),
+        ] {
+            if !(0.0..=1.0).contains(&score) {
+                bail!("Model {} has {} {} outside 0..1", model.id, label, score);
+            }
+        }
+        if let Some(hash) = &model.sha256 {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!("Model {} has an invalid sha256: {}", model.id, hash);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manifest_json(revision: u64, models: &str) -> String {
+        format!(
+            r#"{{ "schema_version": 1, "revision": {}, "models": [{}] }}"#,
+            revision, models
+        )
+    }
+
+    fn model_json(id: &str, filename: &str, extra: &str) -> String {
+        format!(
+            r#"{{
+                "id": "{}", "name": "Test", "description": "", "filename": "{}",
+                "url": "https://example.com/{}", "size_mb": 10, "is_directory": false,
+                "engine_type": "Whisper", "accuracy_score": 0.5, "speed_score": 0.5,
+                "supported_languages": ["dv"]{}
+            }}"#,
+            id, filename, filename, extra
+        )
+    }
+
+    #[test]
+    fn bundled_manifest_is_valid() {
+        let manifest = bundled_manifest();
+        assert!(manifest.models.iter().any(|m| m.id == "whisper-small-dv"));
+        assert!(manifest
+            .models
+            .iter()
+            .any(|m| m.is_directory && matches!(m.engine_type, EngineType::Parakeet)));
+    }
+
+    #[test]
+    fn bundled_models_have_checksums() {
+        let missing: Vec<_> = bundled_manifest()
+            .models
+            .into_iter()
+            .filter(|m| m.url.is_some() && m.sha256.is_none())
+            .map(|m| m.id)
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "run `bun run update:model-checksums` to fill in: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn parses_hashes_and_defaults() {
+        let hash = "AB".repeat(32);
+        let json = manifest_json(
+            3,
+            &model_json("dv-ft", "dv-ft.bin", &format!(r#", "sha256": "{}""#, hash)),
+        );
+        let manifest = parse_manifest(&json).unwrap();
+        let info = manifest.models[0].to_model_info();
+
+        assert_eq!(manifest.revision, 3);
+        assert_eq!(info.sha256, Some("ab".repeat(32)));
+        assert!(!info.is_recommended && !info.is_custom && !info.is_downloaded);
+    }
+
+    #[test]
+    fn rejects_invalid_manifests() {
+        let cases = [
+            r#"{ "schema_version": 2, "revision": 1, "models": [] }"#.to_string(),
+            manifest_json(1, ""),
+            manifest_json(
+                1,
+                &format!(
+                    "{},{}",
+                    model_json("a", "a.bin", ""),
+                    model_json("a", "b.bin", "")
+                ),
+            ),
+            manifest_json(1, &model_json("a", "../a.bin", "")),
+            manifest_json(1, &model_json("a", "a.bin", r#", "sha256": "abc""#)),
+            manifest_json(
+                1,
+                &model_json("a", "a.bin", r#", "mirrors": ["ftp://example.com/a.bin"]"#),
+            ),
+            manifest_json(1, &model_json("a", "a.bin", "").replace("0.5", "1.5")),
+        ];
+
+        for json in cases {
+            assert!(parse_manifest(&json).is_err(), "accepted: {}", json);
+        }
+    }
+
+    #[test]
+    fn ignores_fields_from_newer_manifests() {
+        let json = manifest_json(1, &model_json("a", "a.bin", r#", "license": "MIT""#));
+        assert_eq!(parse_manifest(&json).unwrap().models[0].id, "a");
+    }
+
+    #[test]
+    fn cached_manifest_wins_only_when_newer() {
+        let dir = TempDir::new().unwrap();
+        let bundled = bundled_manifest();
+        let path = dir.path().join(CACHED_MANIFEST_FILE);
+
+        let newer = manifest_json(bundled.revision + 1, &model_json("new", "new.bin", ""));
+        std::fs::write(&path, newer).unwrap();
+        assert_eq!(load_manifest(dir.path()).models[0].id, "new");
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load_manifest(dir.path()).revision, bundled.revision);
+    }
+}
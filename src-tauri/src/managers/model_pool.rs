@@ -0,0 +1,189 @@
+//! Transcription engines kept in memory at the same time.
+//!
+//! Loading a model from disk takes seconds, so engines that were used recently
+//! stay loaded while their combined size fits the memory budget. When a new
+//! engine needs room the least recently used ones are evicted first. The most
+//! recently used engine is always kept, so a budget of 0 keeps exactly one
+//! model loaded, which is how the app behaved before the pool existed.
+
+struct PoolEntry<E> {
+    model_id: String,
+    engine: E,
+    size_mb: u64,
+    last_used_ms: u64,
+    /// Monotonic use counter; breaks ties between uses in the same millisecond
+    last_used_tick: u64,
+}
+
+pub struct ModelPool<E> {
+    entries: Vec<PoolEntry<E>>,
+    budget_mb: u64,
+    tick: u64,
+}
+
+impl<E> ModelPool<E> {
+    pub fn new(budget_mb: u64) -> Self {
+        Self {
+            entries: Vec::new(),
+            budget_mb,
+            tick: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, model_id: &str) -> bool {
+        self.entries.iter().any(|e| e.model_id == model_id)
+    }
+
+    /// Loaded model ids, most recently used first.
+    pub fn model_ids(&self) -> Vec<String> {
+        let mut entries: Vec<&PoolEntry<E>> = self.entries.iter().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_used_tick));
+        entries.into_iter().map(|e| e.model_id.clone()).collect()
+    }
+
+    pub fn used_mb(&self) -> u64 {
+        self.entries.iter().map(|e| e.size_mb).sum()
+    }
+
+    /// Borrow an engine and mark it as used.
+    pub fn get_mut(&mut self, model_id: &str, now_ms: u64) -> Option<&mut E> {
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.entries.iter_mut().find(|e| e.model_id == model_id)?;
+        entry.last_used_ms = now_ms;
+        entry.last_used_tick = tick;
+        Some(&mut entry.engine)
+    }
+
+    /// Evict least recently used engines until `size_mb` more fits the budget.
+    /// Call before loading a model so old and new engines are not held at once.
+    pub fn reserve(&mut self, size_mb: u64) -> Vec<(String, E)> {
+        let mut evicted = Vec::new();
+        while !self.entries.is_empty() && self.used_mb() + size_mb > self.budget_mb {
+            evicted.push(self.evict_lru());
+        }
+        evicted
+    }
+
+    /// Add an engine, replacing one with the same id, and evict others that no
+    /// longer fit. Returns the engines that were removed.
+    pub fn insert(
+        &mut self,
+        model_id: &str,
+        engine: E,
+        size_mb: u64,
+        now_ms: u64,
+    ) -> Vec<(String, E)> {
+        let mut evicted: Vec<(String, E)> = self.remove(model_id).into_iter().collect();
+        self.tick += 1;
+        self.entries.push(PoolEntry {
+            model_id: model_id.to_string(),
+            engine,
+            size_mb,
+            last_used_ms: now_ms,
+            last_used_tick: self.tick,
+        });
+        evicted.extend(self.enforce_budget());
+        evicted
+    }
+
+    /// Change the budget, evicting engines that no longer fit.
+    pub fn set_budget(&mut self, budget_mb: u64) -> Vec<(String, E)> {
+        self.budget_mb = budget_mb;
+        self.enforce_budget()
+    }
+
+    /// Remove engines unused for longer than `max_idle_ms`.
+    pub fn take_idle(&mut self, now_ms: u64, max_idle_ms: u64) -> Vec<(String, E)> {
+        let (idle, kept): (Vec<_>, Vec<_>) = self
+            .entries
+            .drain(..)
+            .partition(|e| now_ms.saturating_sub(e.last_used_ms) > max_idle_ms);
+        self.entries = kept;
+        idle.into_iter().map(|e| (e.model_id, e.engine)).collect()
+    }
+
+    pub fn remove(&mut self, model_id: &str) -> Option<(String, E)> {
+        let index = self.entries.iter().position(|e| e.model_id == model_id)?;
+        let entry = self.entries.remove(index);
+        Some((entry.model_id, entry.engine))
+    }
+
+    pub fn drain(&mut self) -> Vec<(String, E)> {
+        self.entries
+            .drain(..)
+            .map(|e| (e.model_id, e.engine))
+            .collect()
+    }
+
+    fn enforce_budget(&mut self) -> Vec<(String, E)> {
+        let mut evicted = Vec::new();
+        while self.entries.len() > 1 && self.used_mb() > self.budget_mb {
+            evicted.push(self.evict_lru());
+        }
+        evicted
+    }
+
+    fn evict_lru(&mut self) -> (String, E) {
+        let index = self
+            .entries
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, e)| e.last_used_tick)
+            .map(|(i, _)| i)
+            .expect("evict_lru called on an empty pool");
+        let entry = self.entries.remove(index);
+        (entry.model_id, entry.engine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(evicted: Vec<(String, ())>) -> Vec<String> {
+        evicted.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn zero_budget_keeps_only_the_latest_model() {
+        let mut pool = ModelPool::new(0);
+        assert!(pool.insert("dv", (), 500, 0).is_empty());
+        assert_eq!(ids(pool.insert("en", (), 500, 1)), vec!["dv"]);
+        assert_eq!(pool.model_ids(), vec!["en"]);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_first() {
+        let mut pool = ModelPool::new(1000);
+        pool.insert("a", (), 400, 0);
+        pool.insert("b", (), 400, 0);
+        // Same millisecond, but "a" is used after "b"
+        assert!(pool.get_mut("a", 0).is_some());
+
+        assert_eq!(ids(pool.reserve(400)), vec!["b"]);
+        pool.insert("c", (), 400, 1);
+        assert_eq!(pool.model_ids(), vec!["c", "a"]);
+        assert_eq!(pool.used_mb(), 800);
+
+        assert_eq!(ids(pool.set_budget(500)), vec!["a"]);
+        assert_eq!(pool.model_ids(), vec!["c"]);
+    }
+
+    #[test]
+    fn idle_engines_are_taken_individually() {
+        let mut pool = ModelPool::new(u64::MAX);
+        pool.insert("old", (), 1, 1_000);
+        pool.insert("recent", (), 1, 9_000);
+        pool.get_mut("old", 2_000);
+
+        assert_eq!(ids(pool.take_idle(10_000, 5_000)), vec!["old"]);
+        assert!(pool.contains("recent") && !pool.contains("old"));
+        assert_eq!(ids(pool.drain()), vec!["recent"]);
+        assert!(pool.is_empty());
+    }
+}
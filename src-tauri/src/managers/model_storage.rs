@@ -0,0 +1,379 @@
+//! Disk usage of the models directory.
+//!
+//! Besides installed models the directory collects interrupted downloads,
+//! leftover extraction directories and models that dropped out of the
+//! catalogue. Scanning classifies every top-level entry so the UI can show
+//! where the space goes and reclaim what is safe to remove. Only entries whose
+//! exact name the app itself creates are ever reclaimable; anything else
+//! may be the user's own file, since the directory can be relocated.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::{model_benchmark, model_import, model_integrity, model_manifest};
+
+/// Downloads untouched for this long are treated as abandoned.
+pub const STALE_PARTIAL_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskEntryKind {
+    Model,
+    /// Installed model that is neither in the catalogue nor imported
+    UncataloguedModel,
+    /// Download that can still be resumed
+    Partial,
+    StalePartial,
+    /// Extraction in progress
+    Extracting,
+    /// App bookkeeping such as the cached manifest
+    Metadata,
+    /// Leftover of an interrupted extraction or temporary file
+    Orphan,
+    /// Not created by the app; never removed
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DiskEntry {
+    pub name: String,
+    pub size_bytes: u64,
+    pub is_directory: bool,
+    pub kind: DiskEntryKind,
+    pub model_id: Option<String>,
+    /// Whether cleanup removes this entry
+    pub reclaimable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelsDiskReport {
+    pub models_dir: String,
+    pub total_bytes: u64,
+    pub reclaimable_bytes: u64,
+    /// Largest first
+    pub entries: Vec<DiskEntry>,
+}
+
+/// What the model manager knows about a model whose files may be on disk.
+#[derive(Debug, Clone)]
+pub struct ModelFileRef {
+    pub model_id: String,
+    pub filename: String,
+    /// Listed in the catalogue or imported by the user
+    pub listed: bool,
+    /// Being downloaded or extracted right now
+    pub busy: bool,
+    /// Selected or loaded, so never offered for cleanup
+    pub protected: bool,
+}
+
+const METADATA_FILES: &[&str] = &[
+    model_manifest::CACHED_MANIFEST_FILE,
+    model_import::CUSTOM_MODELS_FILE,
+    model_benchmark::BENCHMARKS_FILE,
+    model_integrity::RECORDED_CHECKSUMS_FILE,
+];
+
+/// Total size of a file or directory tree in bytes.
+pub fn entry_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| entry_size(&e.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Whether `name` is a directory `tempfile::tempdir_in` made: `.tmp` followed
+/// by six random alphanumerics.
+fn is_tempfile_dir(name: &str) -> bool {
+    name.strip_prefix(".tmp")
+        .is_some_and(|rest| rest.len() == 6 && rest.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+fn classify<'a>(
+    name: &str,
+    is_dir: bool,
+    modified: Option<SystemTime>,
+    models: &'a [ModelFileRef],
+    now: SystemTime,
+) -> (DiskEntryKind, Option<&'a ModelFileRef>) {
+    if METADATA_FILES.contains(&name) {
+        return (DiskEntryKind::Metadata, None);
+    }
+    // Half-written metadata and directories for probing imported archives
+    let metadata_tmp = name
+        .strip_suffix(".tmp")
+        .is_some_and(|file| METADATA_FILES.contains(&file));
+    if metadata_tmp || (is_dir && is_tempfile_dir(name)) {
+        return (DiskEntryKind::Orphan, None);
+    }
+    if let Some(model) = models.iter().find(|m| m.filename == name) {
+        let kind = if model.listed {
+            DiskEntryKind::Model
+        } else {
+            DiskEntryKind::UncataloguedModel
+        };
+        return (kind, Some(model));
+    }
+
+    let partial_of = name
+        .strip_suffix(".partial.parts")
+        .or_else(|| name.strip_suffix(".partial"));
+    if let Some(model) = partial_of.and_then(|f| models.iter().find(|m| m.filename == f)) {
+        let age = modified
+            .and_then(|t| now.duration_since(t).ok())
+            .unwrap_or_default();
+        let kind = if !model.busy && age > STALE_PARTIAL_AGE {
+            DiskEntryKind::StalePartial
+        } else {
+            DiskEntryKind::Partial
+        };
+        return (kind, Some(model));
+    }
+
+    let extracting_of = name.strip_suffix(".extracting");
+    if let Some(model) = extracting_of.and_then(|f| models.iter().find(|m| m.filename == f)) {
+        let kind = if model.busy {
+            DiskEntryKind::Extracting
+        } else {
+            DiskEntryKind::Orphan
+        };
+        return (kind, Some(model));
+    }
+
+    // Anything else, even if it looks like ours, may be the user's own file
+    (DiskEntryKind::Unknown, None)
+}
+
+/// Whether an entry is one the app manages, and so may be moved along with
+/// the models directory.
+pub fn is_managed_entry(path: &Path, models: &[ModelFileRef]) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    let is_dir = fs::symlink_metadata(path).is_ok_and(|m| m.is_dir());
+    classify(
+        &name.to_string_lossy(),
+        is_dir,
+        None,
+        models,
+        SystemTime::now(),
+    )
+    .0 != DiskEntryKind::Unknown
+}
+
+/// List every top-level entry of `models_dir` with its size and kind.
+pub fn scan_models_dir(
+    models_dir: &Path,
+    models: &[ModelFileRef],
+    now: SystemTime,
+) -> Result<ModelsDiskReport> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(models_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+        let metadata = fs::symlink_metadata(&path)?;
+
+        let (kind, model) = classify(
+            &name,
+            metadata.is_dir(),
+            metadata.modified().ok(),
+            models,
+            now,
+        );
+        let reclaimable = match kind {
+            DiskEntryKind::StalePartial | DiskEntryKind::Orphan => true,
+            DiskEntryKind::UncataloguedModel => !model.is_some_and(|m| m.protected),
+            _ => false,
+        };
+        entries.push(DiskEntry {
+            size_bytes: entry_size(&path),
+            is_directory: metadata.is_dir(),
+            kind,
+            model_id: model.map(|m| m.model_id.clone()),
+            reclaimable,
+            name,
+        });
+    }
+
+    entries.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.name.cmp(&b.name)));
+    Ok(ModelsDiskReport {
+        models_dir: models_dir.to_string_lossy().into_owned(),
+        total_bytes: entries.iter().map(|e| e.size_bytes).sum(),
+        reclaimable_bytes: entries
+            .iter()
+            .filter(|e| e.reclaimable)
+            .map(|e| e.size_bytes)
+            .sum(),
+        entries,
+    })
+}
+
+/// Move a file or directory, copying when it has to cross file systems.
+pub fn move_entry(src: &Path, dest: &Path) -> Result<()> {
+    if fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = model_import::copy_model(src, dest) {
+        if dest.is_dir() {
+            let _ = fs::remove_dir_all(dest);
+        } else {
+            let _ = fs::remove_file(dest);
+        }
+        return Err(e);
+    }
+    if src.is_dir() {
+        fs::remove_dir_all(src)?;
+    } else {
+        fs::remove_file(src)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn model(id: &str, filename: &str, listed: bool) -> ModelFileRef {
+        ModelFileRef {
+            model_id: id.to_string(),
+            filename: filename.to_string(),
+            listed,
+            busy: false,
+            protected: false,
+        }
+    }
+
+    fn kind_of(report: &ModelsDiskReport, name: &str) -> DiskEntryKind {
+        report.entries.iter().find(|e| e.name == name).unwrap().kind
+    }
+
+    #[test]
+    fn classifies_models_partials_and_orphans() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path();
+        fs::write(d.join("small.bin"), vec![0u8; 300]).unwrap();
+        fs::write(d.join("old.bin"), vec![0u8; 200]).unwrap();
+        fs::write(d.join("small.bin.partial"), vec![0u8; 50]).unwrap();
+        fs::write(d.join("gone.bin.partial"), vec![0u8; 40]).unwrap();
+        fs::create_dir(d.join("parakeet.extracting")).unwrap();
+        fs::write(d.join("parakeet.extracting").join("x.onnx"), vec![0u8; 30]).unwrap();
+        fs::write(d.join(model_manifest::CACHED_MANIFEST_FILE), b"{}").unwrap();
+        fs::write(d.join("stray.txt"), b"x").unwrap();
+        fs::create_dir(d.join(".tmpAb12Cd")).unwrap();
+        fs::write(d.join(".tmpAb12Cd").join("probe.onnx"), vec![0u8; 7]).unwrap();
+
+        let models = vec![
+            model("small", "small.bin", true),
+            model("old", "old.bin", false),
+            model("parakeet", "parakeet", true),
+        ];
+        let report = scan_models_dir(d, &models, SystemTime::now()).unwrap();
+
+        assert_eq!(kind_of(&report, "small.bin"), DiskEntryKind::Model);
+        assert_eq!(
+            kind_of(&report, "old.bin"),
+            DiskEntryKind::UncataloguedModel
+        );
+        assert_eq!(
+            kind_of(&report, "small.bin.partial"),
+            DiskEntryKind::Partial
+        );
+        assert_eq!(kind_of(&report, "gone.bin.partial"), DiskEntryKind::Unknown);
+        assert_eq!(
+            kind_of(&report, "parakeet.extracting"),
+            DiskEntryKind::Orphan
+        );
+        assert_eq!(
+            kind_of(&report, model_manifest::CACHED_MANIFEST_FILE),
+            DiskEntryKind::Metadata
+        );
+        assert_eq!(kind_of(&report, ".tmpAb12Cd"), DiskEntryKind::Orphan);
+        assert_eq!(kind_of(&report, "stray.txt"), DiskEntryKind::Unknown);
+        assert_eq!(report.entries[0].name, "small.bin");
+        assert_eq!(report.total_bytes, 300 + 200 + 50 + 40 + 30 + 2 + 1 + 7);
+        assert_eq!(report.reclaimable_bytes, 200 + 30 + 7);
+    }
+
+    #[test]
+    fn old_partials_become_stale_unless_downloading() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.bin.partial"), b"x").unwrap();
+        let later = SystemTime::now() + STALE_PARTIAL_AGE + Duration::from_secs(60);
+
+        let mut models = vec![model("a", "a.bin", true)];
+        let report = scan_models_dir(dir.path(), &models, later).unwrap();
+        assert_eq!(
+            kind_of(&report, "a.bin.partial"),
+            DiskEntryKind::StalePartial
+        );
+
+        models[0].busy = true;
+        let report = scan_models_dir(dir.path(), &models, later).unwrap();
+        assert_eq!(kind_of(&report, "a.bin.partial"), DiskEntryKind::Partial);
+        assert_eq!(report.reclaimable_bytes, 0);
+    }
+
+    #[test]
+    fn user_files_are_never_reclaimable() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("Photos")).unwrap();
+        fs::write(dir.path().join("Photos").join("a.jpg"), vec![0u8; 10]).unwrap();
+        fs::write(dir.path().join("notes.txt"), b"todo").unwrap();
+        fs::write(dir.path().join("draft.tmp"), b"x").unwrap();
+        fs::write(dir.path().join(".tmpAb12Cd"), b"x").unwrap();
+        fs::create_dir(dir.path().join(".tmp-backups")).unwrap();
+        fs::write(dir.path().join("movie.mkv.partial"), b"x").unwrap();
+
+        let models = vec![model("a", "a.bin", true)];
+        let report = scan_models_dir(dir.path(), &models, SystemTime::now()).unwrap();
+        assert!(report
+            .entries
+            .iter()
+            .all(|e| e.kind == DiskEntryKind::Unknown && !e.reclaimable));
+        assert_eq!(report.reclaimable_bytes, 0);
+
+        for name in ["notes.txt", "draft.tmp", ".tmpAb12Cd", "movie.mkv.partial"] {
+            assert!(!is_managed_entry(&dir.path().join(name), &models));
+        }
+        let managed = [
+            "a.bin".to_string(),
+            "a.bin.partial".to_string(),
+            model_manifest::CACHED_MANIFEST_FILE.to_string(),
+            format!("{}.tmp", model_import::CUSTOM_MODELS_FILE),
+        ];
+        for name in managed {
+            assert!(is_managed_entry(&dir.path().join(name), &models));
+        }
+    }
+
+    #[test]
+    fn moves_files_and_directories() {
+        let src = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        fs::create_dir(src.path().join("model")).unwrap();
+        fs::write(src.path().join("model").join("a.onnx"), b"abc").unwrap();
+
+        move_entry(&src.path().join("model"), &dest.path().join("model")).unwrap();
+        assert!(!src.path().join("model").exists());
+        assert_eq!(
+            fs::read(dest.path().join("model").join("a.onnx")).unwrap(),
+            b"abc"
+        );
+    }
+}
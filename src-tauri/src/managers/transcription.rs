@@ -1,13 +1,15 @@
 use crate::audio_toolkit::{apply_custom_words, filter_transcription_output};
-use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::managers::model_benchmark::{self, BenchmarkClip, BenchmarkProgress, ClipResult};
+use crate::managers::model_pool::ModelPool;
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout};
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use serde::Serialize;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter};
 use transcribe_rs::{
     engines::{
@@ -39,6 +41,24 @@ enum LoadedEngine {
     SenseVoice(SenseVoiceEngine),
 }
 
+impl LoadedEngine {
+    fn unload(&mut self) {
+        match self {
+            LoadedEngine::Whisper(e) => e.unload_model(),
+            LoadedEngine::Parakeet(e) => e.unload_model(),
+            LoadedEngine::Moonshine(e) => e.unload_model(),
+            LoadedEngine::SenseVoice(e) => e.unload_model(),
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
 const WHISPER_SAMPLE_RATE: usize = 16_000;
 const WHISPER_CHUNK_SECONDS: usize = 10;
 const WHISPER_CHUNK_SAMPLES: usize = WHISPER_SAMPLE_RATE * WHISPER_CHUNK_SECONDS;
@@ -195,11 +215,11 @@ fn transcribe_whisper_with_chunking(
 
 #[derive(Clone)]
 pub struct TranscriptionManager {
-    engine: Arc<Mutex<Option<LoadedEngine>>>,
+    /// Warm engines; `current_model_id` picks the one `transcribe` uses
+    engines: Arc<Mutex<ModelPool<LoadedEngine>>>,
     model_manager: Arc<ModelManager>,
     app_handle: AppHandle,
     current_model_id: Arc<Mutex<Option<String>>>,
-    last_activity: Arc<AtomicU64>,
     shutdown_signal: Arc<AtomicBool>,
     watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
     is_loading: Arc<Mutex<bool>>,
@@ -221,17 +241,12 @@ impl Drop for LoadingStateGuard {
 
 impl TranscriptionManager {
     pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
+        let budget_mb = get_settings(app_handle).model_pool_budget_mb as u64;
         let manager = Self {
-            engine: Arc::new(Mutex::new(None)),
+            engines: Arc::new(Mutex::new(ModelPool::new(budget_mb))),
             model_manager,
             app_handle: app_handle.clone(),
             current_model_id: Arc::new(Mutex::new(None)),
-            last_activity: Arc::new(AtomicU64::new(
-                SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64,
-            )),
             shutdown_signal: Arc::new(AtomicBool::new(false)),
             watcher_handle: Arc::new(Mutex::new(None)),
             is_loading: Arc::new(Mutex::new(false)),
@@ -261,35 +276,21 @@ impl TranscriptionManager {
                             continue;
                         }
 
-                        let last = manager_cloned.last_activity.load(Ordering::Relaxed);
-                        let now_ms = SystemTime::now()
-                            .duration_since(SystemTime::UNIX_EPOCH)
+                        // Each warm model times out on its own, so a model
+                        // used a minute ago stays while an older one is freed
+                        let idle = manager_cloned
+                            .engines
+                            .lock()
                             .unwrap()
-                            .as_millis() as u64;
-
-                        if now_ms.saturating_sub(last) > limit_seconds * 1000 {
-                            // idle -> unload
-                            if manager_cloned.is_model_loaded() {
-                                let unload_start = std::time::Instant::now();
-                                debug!("Starting to unload model due to inactivity");
-
-                                if let Ok(()) = manager_cloned.unload_model() {
-                                    let _ = app_handle_cloned.emit(
-                                        "model-state-changed",
-                                        ModelStateEvent {
-                                            event_type: "unloaded".to_string(),
-                                            model_id: None,
-                                            model_name: None,
-                                            error: None,
-                                        },
-                                    );
-                                    let unload_duration = unload_start.elapsed();
-                                    debug!(
-                                        "Model unloaded due to inactivity (took {}ms)",
-                                        unload_duration.as_millis()
-                                    );
-                                }
-                            }
+                            .take_idle(now_ms(), limit_seconds * 1000);
+                        if !idle.is_empty() {
+                            let unload_start = std::time::Instant::now();
+                            debug!("Unloading {} model(s) due to inactivity", idle.len());
+                            manager_cloned.release_engines(idle);
+                            debug!(
+                                "Models unloaded due to inactivity (took {}ms)",
+                                unload_start.elapsed().as_millis()
+                            );
                         }
                     }
                 }
@@ -302,25 +303,60 @@ impl TranscriptionManager {
     }
 
     pub fn is_model_loaded(&self) -> bool {
-        let engine = self.engine.lock().unwrap();
-        engine.is_some()
+        let engines = self.engines.lock().unwrap();
+        !engines.is_empty()
+    }
+
+    /// Ids of the models currently held in memory, most recently used first.
+    pub fn loaded_models(&self) -> Vec<String> {
+        self.engines.lock().unwrap().model_ids()
+    }
+
+    /// Change the memory budget for warm models, unloading any that no longer fit.
+    pub fn set_pool_budget(&self, budget_mb: u64) {
+        let evicted = self.engines.lock().unwrap().set_budget(budget_mb);
+        self.release_engines(evicted);
+    }
+
+    /// Unload engines removed from the pool. If the active model is among
+    /// them, clear it and tell the UI it is no longer loaded.
+    fn release_engines(&self, engines: Vec<(String, LoadedEngine)>) {
+        for (model_id, mut engine) in engines {
+            engine.unload();
+            drop(engine);
+            debug!("Unloaded model {} from the pool", model_id);
+
+            let was_current = {
+                let mut current_model = self.current_model_id.lock().unwrap();
+                let was_current = current_model.as_deref() == Some(model_id.as_str());
+                if was_current {
+                    *current_model = None;
+                }
+                was_current
+            };
+            if was_current {
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "unloaded".to_string(),
+                        model_id: Some(model_id),
+                        model_name: None,
+                        error: None,
+                    },
+                );
+            }
+        }
     }
 
     pub fn unload_model(&self) -> Result<()> {
         let unload_start = std::time::Instant::now();
-        debug!("Starting to unload model");
+        debug!("Starting to unload all models");
 
         {
-            let mut engine = self.engine.lock().unwrap();
-            if let Some(ref mut loaded_engine) = *engine {
-                match loaded_engine {
-                    LoadedEngine::Whisper(ref mut e) => e.unload_model(),
-                    LoadedEngine::Parakeet(ref mut e) => e.unload_model(),
-                    LoadedEngine::Moonshine(ref mut e) => e.unload_model(),
-                    LoadedEngine::SenseVoice(ref mut e) => e.unload_model(),
-                }
+            let mut engines = self.engines.lock().unwrap();
+            for (_, mut engine) in engines.drain() {
+                engine.unload();
             }
-            *engine = None; // Drop the engine to free memory
         }
         {
             let mut current_model = self.current_model_id.lock().unwrap();
@@ -360,7 +396,35 @@ impl TranscriptionManager {
     }
 
     fn is_requested_model_loaded(&self, model_id: &str) -> bool {
-        self.is_model_loaded() && self.get_current_model().as_deref() == Some(model_id)
+        self.get_current_model().as_deref() == Some(model_id)
+            && self.engines.lock().unwrap().contains(model_id)
+    }
+
+    /// Make a model that is already in the pool the active one, without
+    /// touching the disk. Returns false if it is not loaded.
+    fn activate_warm_model(&self, model_id: &str) -> bool {
+        if self
+            .engines
+            .lock()
+            .unwrap()
+            .get_mut(model_id, now_ms())
+            .is_none()
+        {
+            return false;
+        }
+        *self.current_model_id.lock().unwrap() = Some(model_id.to_string());
+
+        let _ = self.app_handle.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: "loading_completed".to_string(),
+                model_id: Some(model_id.to_string()),
+                model_name: self.model_manager.get_model_info(model_id).map(|m| m.name),
+                error: None,
+            },
+        );
+        debug!("Switched to warm model: {}", model_id);
+        true
     }
 
     fn load_model_inner(&self, model_id: &str) -> Result<()> {
@@ -399,6 +463,10 @@ impl TranscriptionManager {
 
         let model_path = self.model_manager.get_model_path(model_id)?;
 
+        // Free room first so the old and new engines are not both held over budget
+        let evicted = self.engines.lock().unwrap().reserve(model_info.size_mb);
+        self.release_engines(evicted);
+
         // Create appropriate engine based on model type
         let loaded_engine = match model_info.engine_type {
             EngineType::Whisper => {
@@ -483,11 +551,14 @@ impl TranscriptionManager {
             }
         };
 
-        // Update the current engine and model ID
-        {
-            let mut engine = self.engine.lock().unwrap();
-            *engine = Some(loaded_engine);
-        }
+        // Add the engine to the pool and make it the active model
+        let evicted = self.engines.lock().unwrap().insert(
+            model_id,
+            loaded_engine,
+            model_info.size_mb,
+            now_ms(),
+        );
+        self.release_engines(evicted);
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = Some(model_id.to_string());
@@ -527,6 +598,9 @@ impl TranscriptionManager {
                 );
                 return Ok(());
             }
+            if self.activate_warm_model(model_id) {
+                return Ok(());
+            }
 
             *is_loading = true;
         }
@@ -539,19 +613,20 @@ impl TranscriptionManager {
         self.load_model_inner(model_id)
     }
 
-    /// Kicks off the model loading in a background thread if it's not already loaded
-    pub fn initiate_model_load(&self) {
+    /// Kicks off loading `model_id` in a background thread if it is not
+    /// already the active model. Warm models are switched to immediately.
+    pub fn initiate_model_load(&self, model_id: &str) {
         {
             let is_loading = self.is_loading.lock().unwrap();
-            if *is_loading || self.is_model_loaded() {
+            if *is_loading || self.is_requested_model_loaded(model_id) {
                 return;
             }
         }
 
         let self_clone = self.clone();
+        let model_id = model_id.to_string();
         thread::spawn(move || {
-            let settings = get_settings(&self_clone.app_handle);
-            if let Err(e) = self_clone.load_model(&settings.selected_model) {
+            if let Err(e) = self_clone.load_model(&model_id) {
                 error!("Failed to load model: {}", e);
             }
         });
@@ -563,15 +638,22 @@ impl TranscriptionManager {
     }
 
     pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
-        // Update last activity timestamp
-        self.last_activity.store(
-            SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-            Ordering::Relaxed,
-        );
+        let model_id = self
+            .get_current_model()
+            .ok_or_else(|| anyhow::anyhow!("Model is not loaded for transcription."))?;
+        self.transcribe_with(&model_id, audio, &get_settings(&self.app_handle))
+    }
 
+    /// Transcribe with a model from the pool and the given settings, e.g. a
+    /// binding's own model and language. The model is named explicitly so a
+    /// concurrent switch of the active model (by another binding or a
+    /// benchmark) cannot change which engine runs.
+    pub fn transcribe_with(
+        &self,
+        model_id: &str,
+        audio: Vec<f32>,
+        settings: &AppSettings,
+    ) -> Result<String> {
         let st = std::time::Instant::now();
 
         debug!("Audio vector length: {}", audio.len());
@@ -590,21 +672,18 @@ impl TranscriptionManager {
                 is_loading = self.loading_condvar.wait(is_loading).unwrap();
             }
 
-            let engine_guard = self.engine.lock().unwrap();
-            if engine_guard.is_none() {
+            if !self.is_model_loaded() {
                 return Err(anyhow::anyhow!("Model is not loaded for transcription."));
             }
         }
 
-        // Get current settings for configuration
-        let settings = get_settings(&self.app_handle);
-
         // Perform transcription with the appropriate engine
         let result = {
-            let mut engine_guard = self.engine.lock().unwrap();
-            let engine = engine_guard.as_mut().ok_or_else(|| {
+            let mut engines = self.engines.lock().unwrap();
+            let engine = engines.get_mut(model_id, now_ms()).ok_or_else(|| {
                 anyhow::anyhow!(
-                    "Model failed to load after auto-load attempt. Please check your model settings."
+                    "Model {} is not loaded. Please check your model settings.",
+                    model_id
                 )
             })?;
 
@@ -711,6 +790,90 @@ impl TranscriptionManager {
 
         Ok(final_result)
     }
+
+    /// Run every downloaded model over `clips` and record WER, CER and
+    /// real-time factor for each. The previously loaded model is restored
+    /// afterwards. Models that fail to load or transcribe are skipped.
+    pub fn benchmark_models(&self, clips: &[BenchmarkClip]) -> Result<Vec<ModelInfo>> {
+        let audio = clips
+            .iter()
+            .map(|clip| {
+                model_benchmark::read_clip_samples(&clip.audio_path)
+                    .map(|samples| (clip, samples))
+                    .map_err(|e| {
+                        anyhow::anyhow!("Failed to read clip {:?}: {}", clip.audio_path, e)
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut models: Vec<ModelInfo> = self
+            .model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded)
+            .collect();
+        if models.is_empty() {
+            return Err(anyhow::anyhow!("No downloaded models to benchmark"));
+        }
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let settings = get_settings(&self.app_handle);
+        let previous_model = self.get_current_model();
+        let mut benchmarked = Vec::new();
+        for (model_index, model) in models.iter().enumerate() {
+            info!("Benchmarking model {} on {} clips", model.id, audio.len());
+            let mut results = Vec::with_capacity(audio.len());
+            let outcome = audio
+                .iter()
+                .enumerate()
+                .try_for_each(|(clip_index, (clip, samples))| {
+                    let _ = self.app_handle.emit(
+                        "model-benchmark-progress",
+                        BenchmarkProgress {
+                            model_id: model.id.clone(),
+                            model_index: model_index as u32,
+                            model_count: models.len() as u32,
+                            clip_index: clip_index as u32,
+                            clip_count: audio.len() as u32,
+                        },
+                    );
+                    // transcribe_with() may unload right after each clip; loading is
+                    // kept outside the timed section
+                    self.load_model(&model.id)?;
+                    let start = Instant::now();
+                    let hypothesis = self.transcribe_with(&model.id, samples.clone(), &settings)?;
+                    results.push(ClipResult {
+                        reference: clip.reference.clone(),
+                        hypothesis,
+                        audio_seconds: samples.len() as f64 / WHISPER_SAMPLE_RATE as f64,
+                        processing: start.elapsed(),
+                    });
+                    Ok::<_, anyhow::Error>(())
+                });
+
+            if let Err(e) = outcome {
+                warn!("Skipping benchmark of model {}: {}", model.id, e);
+                continue;
+            }
+            let benchmark = model_benchmark::aggregate(&results, chrono::Utc::now().timestamp());
+            info!(
+                "Model {}: WER {:.3}, CER {:.3}, RTF {:.3}",
+                model.id,
+                benchmark.word_error_rate,
+                benchmark.char_error_rate,
+                benchmark.real_time_factor
+            );
+            benchmarked.push(self.model_manager.record_benchmark(&model.id, benchmark)?);
+        }
+
+        match previous_model {
+            Some(model_id) => self.load_model(&model_id)?,
+            None if self.is_model_loaded() => self.unload_model()?,
+            None => {}
+        }
+
+        Ok(benchmarked)
+    }
 }
 
 impl Drop for TranscriptionManager {
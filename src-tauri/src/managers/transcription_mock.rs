@@ -2,7 +2,9 @@
 // This file is copied over transcription.rs during CI tests.
 // Existing tests don't exercise transcription, so this is safe.
 
-use crate::managers::model::ModelManager;
+use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::model_benchmark::BenchmarkClip;
+use crate::settings::AppSettings;
 use anyhow::Result;
 use serde::Serialize;
 use std::sync::Arc;
@@ -43,7 +45,13 @@ impl TranscriptionManager {
         Ok(())
     }
 
-    pub fn initiate_model_load(&self) {}
+    pub fn loaded_models(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    pub fn set_pool_budget(&self, _budget_mb: u64) {}
+
+    pub fn initiate_model_load(&self, _model_id: &str) {}
 
     pub fn get_current_model(&self) -> Option<String> {
         None
@@ -52,4 +60,17 @@ impl TranscriptionManager {
     pub fn transcribe(&self, _audio: Vec<f32>) -> Result<String> {
         Ok(String::new())
     }
+
+    pub fn transcribe_with(
+        &self,
+        _model_id: &str,
+        _audio: Vec<f32>,
+        _settings: &AppSettings,
+    ) -> Result<String> {
+        Ok(String::new())
+    }
+
+    pub fn benchmark_models(&self, _clips: &[BenchmarkClip]) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
 }
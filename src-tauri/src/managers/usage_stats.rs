@@ -0,0 +1,398 @@
+//! Usage statistics aggregated from `transcription_history`.
+//!
+//! Completed days are rolled up into `usage_daily_rollups` the first time they
+//! are seen, so the numbers survive history cleanup. The current day is always
+//! computed from the live rows.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashSet};
+
+/// Average typing speed used to estimate the time saved by dictating.
+pub const TYPING_WORDS_PER_MINUTE: f64 = 40.0;
+
+pub const CREATE_ROLLUPS_SQL: &str = "CREATE TABLE IF NOT EXISTS usage_daily_rollups (
+    day TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    transcriptions INTEGER NOT NULL,
+    words INTEGER NOT NULL,
+    audio_ms INTEGER NOT NULL,
+    transcription_ms INTEGER NOT NULL,
+    timed_audio_ms INTEGER NOT NULL,
+    post_processed INTEGER NOT NULL,
+    PRIMARY KEY (day, model_id)
+);";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageRange {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl UsageRange {
+    fn first_day(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let days = match self {
+            UsageRange::Week => 7,
+            UsageRange::Month => 30,
+            UsageRange::Year => 365,
+            UsageRange::All => return None,
+        };
+        Some(today - Duration::days(days - 1))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Type)]
+pub struct DailyUsage {
+    /// Local date, `YYYY-MM-DD`
+    pub day: String,
+    pub transcriptions: u64,
+    pub words: u64,
+    pub audio_minutes: f64,
+    pub post_processed: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Type)]
+pub struct ModelUsage {
+    /// `None` for entries recorded before model tracking existed
+    pub model_id: Option<String>,
+    pub transcriptions: u64,
+    pub audio_minutes: f64,
+    /// Transcription time divided by audio duration; lower is faster
+    pub average_rtf: Option<f64>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Type)]
+pub struct UsageStats {
+    pub range: UsageRange,
+    pub transcriptions: u64,
+    pub words: u64,
+    pub audio_minutes: f64,
+    pub post_processed: u64,
+    pub post_process_rate: f64,
+    pub estimated_minutes_saved: f64,
+    pub daily: Vec<DailyUsage>,
+    pub models: Vec<ModelUsage>,
+}
+
+/// A history row reduced to what the statistics need.
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub timestamp: i64,
+    pub model_id: Option<String>,
+    pub words: u64,
+    pub audio_ms: u64,
+    pub transcription_ms: Option<u64>,
+    pub post_processed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Rollup {
+    transcriptions: u64,
+    words: u64,
+    audio_ms: u64,
+    transcription_ms: u64,
+    /// Audio duration of the entries that have a latency, for the RTF
+    timed_audio_ms: u64,
+    post_processed: u64,
+}
+
+impl Rollup {
+    fn add_record(&mut self, record: &UsageRecord) {
+        self.transcriptions += 1;
+        self.words += record.words;
+        self.audio_ms += record.audio_ms;
+        if let Some(ms) = record.transcription_ms {
+            self.transcription_ms += ms;
+            self.timed_audio_ms += record.audio_ms;
+        }
+        if record.post_processed {
+            self.post_processed += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Rollup) {
+        self.transcriptions += other.transcriptions;
+        self.words += other.words;
+        self.audio_ms += other.audio_ms;
+        self.transcription_ms += other.transcription_ms;
+        self.timed_audio_ms += other.timed_audio_ms;
+        self.post_processed += other.post_processed;
+    }
+}
+
+/// Rollups keyed by day, then model id ("" when unknown).
+type Rollups = BTreeMap<String, BTreeMap<String, Rollup>>;
+
+pub fn count_words(text: &str) -> u64 {
+    text.split_whitespace().count() as u64
+}
+
+fn local_day(timestamp: i64) -> Option<NaiveDate> {
+    DateTime::from_timestamp(timestamp, 0).map(|utc| utc.with_timezone(&Local).date_naive())
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+fn group_records<'a>(records: impl Iterator<Item = (NaiveDate, &'a UsageRecord)>) -> Rollups {
+    let mut rollups = Rollups::new();
+    for (day, record) in records {
+        rollups
+            .entry(day_key(day))
+            .or_default()
+            .entry(record.model_id.clone().unwrap_or_default())
+            .or_default()
+            .add_record(record);
+    }
+    rollups
+}
+
+/// Cache rollups for completed days that have not been stored yet. Days that
+/// are already cached are left alone, since their rows may have been cleaned
+/// up since.
+pub fn store_rollups(conn: &Connection, records: &[UsageRecord], today: NaiveDate) -> Result<()> {
+    let cached: HashSet<String> = {
+        let mut stmt = conn.prepare("SELECT DISTINCT day FROM usage_daily_rollups")?;
+        let days = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        days.collect::<rusqlite::Result<_>>()?
+    };
+
+    let pending = group_records(
+        records
+            .iter()
+            .filter_map(|r| local_day(r.timestamp).map(|day| (day, r)))
+            .filter(|(day, _)| *day < today && !cached.contains(&day_key(*day))),
+    );
+
+    for (day, models) in pending {
+        for (model_id, rollup) in models {
+            conn.execute(
+                "INSERT OR REPLACE INTO usage_daily_rollups (day, model_id, transcriptions, words, audio_ms, transcription_ms, timed_audio_ms, post_processed) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    day,
+                    model_id,
+                    rollup.transcriptions,
+                    rollup.words,
+                    rollup.audio_ms,
+                    rollup.transcription_ms,
+                    rollup.timed_audio_ms,
+                    rollup.post_processed
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn load_rollups(
+    conn: &Connection,
+    first_day: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<Rollups> {
+    let first = first_day.map(day_key).unwrap_or_default();
+    let mut stmt = conn.prepare(
+        "SELECT day, model_id, transcriptions, words, audio_ms, transcription_ms, timed_audio_ms, post_processed
+         FROM usage_daily_rollups WHERE day >= ?1 AND day < ?2",
+    )?;
+    let rows = stmt.query_map(params![first, day_key(today)], |row| {
+        Ok((
+            row.get::<_, String>("day")?,
+            row.get::<_, String>("model_id")?,
+            Rollup {
+                transcriptions: row.get("transcriptions")?,
+                words: row.get("words")?,
+                audio_ms: row.get("audio_ms")?,
+                transcription_ms: row.get("transcription_ms")?,
+                timed_audio_ms: row.get("timed_audio_ms")?,
+                post_processed: row.get("post_processed")?,
+            },
+        ))
+    })?;
+
+    let mut rollups = Rollups::new();
+    for row in rows {
+        let (day, model_id, rollup) = row?;
+        rollups.entry(day).or_default().insert(model_id, rollup);
+    }
+    Ok(rollups)
+}
+
+/// Build the statistics for `range` from cached rollups plus today's rows.
+pub fn compute_usage_stats(
+    conn: &Connection,
+    records: &[UsageRecord],
+    range: UsageRange,
+    today: NaiveDate,
+) -> Result<UsageStats> {
+    let mut rollups = load_rollups(conn, range.first_day(today), today)?;
+    rollups.extend(group_records(
+        records
+            .iter()
+            .filter(|r| local_day(r.timestamp) == Some(today))
+            .map(|r| (today, r)),
+    ));
+
+    let mut total = Rollup::default();
+    let mut per_model: BTreeMap<String, Rollup> = BTreeMap::new();
+    let mut daily = Vec::new();
+    for (day, models) in &rollups {
+        let mut day_total = Rollup::default();
+        for (model_id, rollup) in models {
+            day_total.merge(rollup);
+            per_model.entry(model_id.clone()).or_default().merge(rollup);
+        }
+        total.merge(&day_total);
+        daily.push(DailyUsage {
+            day: day.clone(),
+            transcriptions: day_total.transcriptions,
+            words: day_total.words,
+            audio_minutes: ms_to_minutes(day_total.audio_ms),
+            post_processed: day_total.post_processed,
+        });
+    }
+
+    let models = per_model
+        .into_iter()
+        .map(|(model_id, rollup)| ModelUsage {
+            model_id: (!model_id.is_empty()).then_some(model_id),
+            transcriptions: rollup.transcriptions,
+            audio_minutes: ms_to_minutes(rollup.audio_ms),
+            average_rtf: (rollup.timed_audio_ms > 0)
+                .then(|| rollup.transcription_ms as f64 / rollup.timed_audio_ms as f64),
+        })
+        .collect();
+
+    let typing_minutes = total.words as f64 / TYPING_WORDS_PER_MINUTE;
+    let dictation_minutes = ms_to_minutes(total.audio_ms + total.transcription_ms);
+
+    Ok(UsageStats {
+        range,
+        transcriptions: total.transcriptions,
+        words: total.words,
+        audio_minutes: ms_to_minutes(total.audio_ms),
+        post_processed: total.post_processed,
+        post_process_rate: if total.transcriptions > 0 {
+            total.post_processed as f64 / total.transcriptions as f64
+        } else {
+            0.0
+        },
+        estimated_minutes_saved: (typing_minutes - dictation_minutes).max(0.0),
+        daily,
+        models,
+    })
+}
+
+fn ms_to_minutes(ms: u64) -> f64 {
+    ms as f64 / 60_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        conn.execute_batch(CREATE_ROLLUPS_SQL)
+            .expect("create rollup table");
+        conn
+    }
+
+    fn record(day: NaiveDate, model: &str, words: u64, audio_ms: u64) -> UsageRecord {
+        let noon = Local
+            .from_local_datetime(&day.and_hms_opt(12, 0, 0).unwrap())
+            .unwrap();
+        UsageRecord {
+            timestamp: noon.timestamp(),
+            model_id: Some(model.to_string()),
+            words,
+            audio_ms,
+            transcription_ms: Some(audio_ms / 4),
+            post_processed: false,
+        }
+    }
+
+    #[test]
+    fn aggregates_days_models_and_rtf() {
+        let conn = setup_conn();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let yesterday = today - Duration::days(1);
+        let mut post_processed = record(today, "small", 20, 6_000);
+        post_processed.post_processed = true;
+        let records = vec![
+            record(yesterday, "small", 100, 60_000),
+            record(yesterday, "parakeet", 50, 30_000),
+            post_processed,
+        ];
+
+        store_rollups(&conn, &records, today).unwrap();
+        let stats = compute_usage_stats(&conn, &records, UsageRange::Week, today).unwrap();
+
+        assert_eq!(stats.transcriptions, 3);
+        assert_eq!(stats.words, 170);
+        assert_eq!(stats.post_processed, 1);
+        assert_eq!(stats.daily.len(), 2);
+        assert_eq!(stats.daily[0].day, "2025-03-09");
+        assert_eq!(stats.daily[0].words, 150);
+        assert!((stats.audio_minutes - 1.6).abs() < 1e-9);
+
+        let small = stats
+            .models
+            .iter()
+            .find(|m| m.model_id.as_deref() == Some("small"))
+            .unwrap();
+        assert_eq!(small.transcriptions, 2);
+        assert!((small.average_rtf.unwrap() - 0.25).abs() < 1e-9);
+
+        // 170 words typed at 40 wpm vs 1.6 min spoken + 0.4 min transcribing
+        assert!((stats.estimated_minutes_saved - (170.0 / 40.0 - 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cached_days_survive_history_cleanup() {
+        let conn = setup_conn();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let records = vec![record(today - Duration::days(2), "small", 10, 1_000)];
+        store_rollups(&conn, &records, today).unwrap();
+
+        // The history rows are gone, but the rollup still counts
+        store_rollups(&conn, &[], today).unwrap();
+        let stats = compute_usage_stats(&conn, &[], UsageRange::All, today).unwrap();
+        assert_eq!(stats.words, 10);
+
+        // Today's rows are never cached
+        let live = vec![record(today, "small", 5, 1_000)];
+        store_rollups(&conn, &live, today).unwrap();
+        let cached: i64 = conn
+            .query_row("SELECT COUNT(*) FROM usage_daily_rollups", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(cached, 1);
+    }
+
+    #[test]
+    fn range_excludes_older_days() {
+        let conn = setup_conn();
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let records = vec![
+            record(today - Duration::days(6), "small", 1, 1_000),
+            record(today - Duration::days(7), "small", 2, 1_000),
+        ];
+        store_rollups(&conn, &records, today).unwrap();
+
+        let week = compute_usage_stats(&conn, &records, UsageRange::Week, today).unwrap();
+        assert_eq!(week.words, 1);
+        let all = compute_usage_stats(&conn, &records, UsageRange::All, today).unwrap();
+        assert_eq!(all.words, 3);
+        assert_eq!(count_words("  ދިވެހި  ބަސް \n ok "), 3);
+    }
+}
@@ -0,0 +1,275 @@
+//! Sends transcripts to the output targets that are not the desktop: files,
+//! HTTP endpoints, shell commands and control socket subscribers.
+
+use crate::settings::OutputTarget;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event carrying transcripts of bindings with the IPC target.
+pub const TRANSCRIPT_EVENT: &str = "transcript";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+/// An output command still running after this long is killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Transcript<'a> {
+    pub text: &'a str,
+    pub binding: &'a str,
+}
+
+/// Short name of a target, as recorded in history.
+pub fn target_name(target: &OutputTarget) -> &'static str {
+    match target {
+        OutputTarget::Paste => "paste",
+        OutputTarget::Clipboard => "clipboard",
+        OutputTarget::File { .. } => "file",
+        OutputTarget::Http { .. } => "http",
+        OutputTarget::Command { .. } => "command",
+        OutputTarget::Ipc => "ipc",
+    }
+}
+
+/// Check a target's settings before it is saved on a binding.
+pub fn validate(target: &OutputTarget) -> Result<(), String> {
+    match target {
+        OutputTarget::File { path } if path.trim().is_empty() => {
+            Err("The output file path cannot be empty".to_string())
+        }
+        OutputTarget::Http { url } => {
+            let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err("The URL must start with http:// or https://".to_string());
+            }
+            Ok(())
+        }
+        OutputTarget::Command { command } if command.trim().is_empty() => {
+            Err("The output command cannot be empty".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Send a transcript to a target other than the desktop. Paste and
+/// clipboard targets are handled by `utils::paste`.
+pub async fn deliver(
+    app: &AppHandle,
+    target: &OutputTarget,
+    binding_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let transcript = Transcript {
+        text,
+        binding: binding_id,
+    };
+    match target {
+        OutputTarget::Paste | OutputTarget::Clipboard => Ok(()),
+        OutputTarget::File { path } => {
+            let home = app.path().home_dir().ok();
+            let path = expand_path(path, Local::now().date_naive(), home.as_deref());
+            append_to_file(&path, text)
+        }
+        OutputTarget::Http { url } => post(url, &transcript).await,
+        OutputTarget::Command { command } => {
+            let (command, text, binding) =
+                (command.clone(), text.to_string(), binding_id.to_string());
+            tauri::async_runtime::spawn_blocking(move || {
+                run_command(&command, &text, &binding, COMMAND_TIMEOUT)
+            })
+            .await
+            .map_err(|e| format!("Output command task failed: {}", e))?
+        }
+        OutputTarget::Ipc => app
+            .emit(TRANSCRIPT_EVENT, &transcript)
+            .map_err(|e| format!("Failed to emit transcript: {}", e)),
+    }
+}
+
+/// Replace `{date}` with the given day and a leading `~/` with the home
+/// directory, so one setting can point at a daily note.
+fn expand_path(template: &str, date: NaiveDate, home: Option<&Path>) -> PathBuf {
+    let path = template.replace("{date}", &date.format("%Y-%m-%d").to_string());
+    match (path.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn append_to_file(path: &Path, text: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+async fn post(url: &str, transcript: &Transcript<'_>) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    client
+        .post(url)
+        .json(transcript)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("POST to {} failed: {}", url, e))?;
+    Ok(())
+}
+
+/// Run a command through the shell with the transcript on stdin and the
+/// binding id in `HANDY_BINDING`, killing it if it outlives `timeout`.
+fn run_command(
+    command: &str,
+    text: &str,
+    binding_id: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    #[cfg(windows)]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    };
+    #[cfg(not(windows))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+
+    let mut child = shell
+        .env("HANDY_BINDING", binding_id)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run output command: {}", e))?;
+
+    // Feed stdin and drain stderr on their own threads so a command that
+    // stops reading or writes a lot cannot stall the timeout
+    if let Some(mut stdin) = child.stdin.take() {
+        let text = text.to_string();
+        std::thread::spawn(move || {
+            // A command that ignores its input closes the pipe early; its
+            // exit status is what counts
+            let _ = stdin.write_all(text.as_bytes());
+        });
+    }
+    let stderr = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf);
+            buf
+        })
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "Output command did not finish within {:?} and was killed",
+                    timeout
+                ));
+            }
+            Ok(None) => std::thread::sleep(COMMAND_POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for output command: {}", e)),
+        }
+    };
+    if !status.success() {
+        let stderr = stderr
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        return Err(format!(
+            "Output command exited with {}: {}",
+            status,
+            String::from_utf8_lossy(&stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_date_and_home_in_file_paths() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let home = Path::new("/home/ali");
+        assert_eq!(
+            expand_path("~/notes/{date}.md", date, Some(home)),
+            PathBuf::from("/home/ali/notes/2024-03-09.md")
+        );
+        assert_eq!(
+            expand_path("/tmp/out.txt", date, Some(home)),
+            PathBuf::from("/tmp/out.txt")
+        );
+        assert_eq!(
+            expand_path("~/{date}.md", date, None),
+            PathBuf::from("~/2024-03-09.md")
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_targets() {
+        assert!(validate(&OutputTarget::File {
+            path: " ".to_string()
+        })
+        .is_err());
+        assert!(validate(&OutputTarget::Http {
+            url: "ftp://localhost/notes".to_string()
+        })
+        .is_err());
+        assert!(validate(&OutputTarget::Http {
+            url: "http://127.0.0.1:8080/notes".to_string()
+        })
+        .is_ok());
+        assert!(validate(&OutputTarget::Ipc).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_receive_the_transcript_and_report_failure() {
+        let path = std::env::temp_dir().join(format!("handy-output-{}", std::process::id()));
+        let command = format!("cat > '{}'", path.display());
+        run_command(&command, "ސަލާމް", "transcribe", COMMAND_TIMEOUT).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "ސަލާމް");
+        let _ = std::fs::remove_file(&path);
+
+        let err =
+            run_command("echo nope >&2; exit 3", "", "transcribe", COMMAND_TIMEOUT).unwrap_err();
+        assert!(err.contains("nope"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_that_hang_are_killed() {
+        let started = Instant::now();
+        let err = run_command(
+            "exec sleep 30",
+            "",
+            "transcribe",
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(err.contains("killed"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::input;
 use crate::settings;
 use crate::settings::OverlayPosition;
+use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize};
 
 #[cfg(not(target_os = "macos"))]
@@ -354,6 +355,14 @@ pub fn hide_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
+pub fn emit_recording_progress<S: Serialize + Clone>(app_handle: &AppHandle, progress: &S) {
+    let _ = app_handle.emit("recording-progress", progress);
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("recording-progress", progress);
+    }
+}
+
 pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
     // emit levels to main app
     let _ = app_handle.emit("mic-level", levels);
@@ -1,3 +1,6 @@
+use crate::audio_toolkit::{
+    dsp::DspConfig, ChannelMix, LoopbackMode, LoopbackSpec, RecordingLimits,
+};
 use log::{debug, warn};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -83,6 +86,25 @@ pub struct ShortcutBinding {
     pub description: String,
     pub default_binding: String,
     pub current_binding: String,
+    #[serde(default)]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub post_process_prompt_id: Option<String>,
+    #[serde(default)]
+    pub paste_method: Option<PasteMethod>,
+    #[serde(default)]
+    pub output: OutputTarget,
+    #[serde(default)]
+    pub trigger: Option<TriggerMode>,
+}
+
+/// Ids of bindings the user created, as opposed to the built-in ones.
+pub const CUSTOM_BINDING_PREFIX: &str = "custom_";
+
+pub fn is_custom_binding(id: &str) -> bool {
+    id.starts_with(CUSTOM_BINDING_PREFIX)
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
@@ -134,6 +156,48 @@ pub enum PasteMethod {
     CtrlShiftV,
 }
 
+/// Where a binding delivers its transcript.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputTarget {
+    /// Paste into the focused app with the paste method
+    Paste,
+    /// Only put the text on the clipboard
+    Clipboard,
+    /// Append to a file; `{date}` in the path becomes today's date
+    File { path: String },
+    /// POST `{ "text", "binding" }` as JSON to a URL
+    Http { url: String },
+    /// Run a shell command with the text on its stdin
+    Command { command: String },
+    /// Send to control socket subscribers as a `transcript` event
+    Ipc,
+}
+
+impl OutputTarget {
+    /// Whether the transcript goes to the focused app or clipboard rather
+    /// than somewhere outside the desktop.
+    pub fn is_desktop(&self) -> bool {
+        matches!(self, OutputTarget::Paste | OutputTarget::Clipboard)
+    }
+}
+
+/// How a binding's key presses and releases start and stop recording.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerMode {
+    /// Press to start, press again to stop
+    Toggle,
+    /// Record while the key is held
+    Hold,
+    /// Record while the key is held; a double tap keeps recording until the
+    /// next press
+    DoubleTapLock { window_ms: u64 },
+    /// Held longer than the threshold records while held, a shorter tap
+    /// toggles
+    HoldOrToggle { threshold_ms: u64 },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum ClipboardHandling {
@@ -159,6 +223,33 @@ pub enum RecordingRetentionPeriod {
     Months3,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingCodec {
+    Wav,
+    Flac,
+    Opus,
+}
+
+impl Default for RecordingCodec {
+    fn default() -> Self {
+        RecordingCodec::Wav
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKeySource {
+    Keyring,
+    Passphrase,
+}
+
+impl Default for HistoryKeySource {
+    fn default() -> Self {
+        HistoryKeySource::Keyring
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum KeyboardImplementation {
@@ -193,6 +284,12 @@ impl Default for PasteMethod {
     }
 }
 
+impl Default for OutputTarget {
+    fn default() -> Self {
+        OutputTarget::Paste
+    }
+}
+
 impl Default for ClipboardHandling {
     fn default() -> Self {
         ClipboardHandling::DontModify
@@ -205,6 +302,92 @@ impl Default for AutoSubmitKey {
     }
 }
 
+/// Voice activity detection tuned for a recording environment.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VadPreset {
+    QuietRoom,
+    Office,
+    Outdoors,
+    Custom,
+}
+
+impl Default for VadPreset {
+    fn default() -> Self {
+        VadPreset::Office
+    }
+}
+
+/// Silero threshold and smoothing, counted in 30 ms frames.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Type)]
+pub struct VadParams {
+    /// Speech probability above which a frame counts as voice
+    pub threshold: f32,
+    /// Frames kept from before speech starts
+    pub prefill_frames: u32,
+    /// Frames kept after speech stops
+    pub hangover_frames: u32,
+    /// Consecutive voice frames needed to start speech
+    pub onset_frames: u32,
+}
+
+impl Default for VadParams {
+    fn default() -> Self {
+        VadPreset::Office.params(None)
+    }
+}
+
+impl VadParams {
+    /// Three seconds of smoothing is already far beyond any useful setting.
+    pub const MAX_FRAMES: u32 = 100;
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err("VAD threshold must be between 0.0 and 1.0".to_string());
+        }
+        if self.onset_frames == 0 {
+            return Err("VAD onset must be at least one frame".to_string());
+        }
+        let frames = [self.prefill_frames, self.hangover_frames, self.onset_frames];
+        if frames.iter().any(|&f| f > Self::MAX_FRAMES) {
+            return Err(format!(
+                "VAD frame counts must not exceed {}",
+                Self::MAX_FRAMES
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl VadPreset {
+    /// Parameters for this preset; `Custom` uses `custom` or the office values.
+    pub fn params(self, custom: Option<VadParams>) -> VadParams {
+        match self {
+            // Soft speech is easy to separate from silence, so listen more eagerly
+            VadPreset::QuietRoom => VadParams {
+                threshold: 0.2,
+                prefill_frames: 15,
+                hangover_frames: 20,
+                onset_frames: 2,
+            },
+            VadPreset::Office => VadParams {
+                threshold: 0.3,
+                prefill_frames: 15,
+                hangover_frames: 15,
+                onset_frames: 2,
+            },
+            // Wind and traffic produce short bursts; demand longer, clearer speech
+            VadPreset::Outdoors => VadParams {
+                threshold: 0.5,
+                prefill_frames: 15,
+                hangover_frames: 10,
+                onset_frames: 4,
+            },
+            VadPreset::Custom => custom.unwrap_or_else(|| VadPreset::Office.params(None)),
+        }
+    }
+}
+
 impl ModelUnloadTimeout {
     pub fn to_minutes(self) -> Option<u64> {
         match self {
@@ -293,10 +476,46 @@ pub struct AppSettings {
     #[serde(default = "default_always_on_microphone")]
     pub always_on_microphone: bool,
     #[serde(default)]
+    pub vad_preset: VadPreset,
+    #[serde(default)]
+    pub vad_custom_params: VadParams,
+    #[serde(default = "default_hands_free_silence_ms")]
+    pub hands_free_silence_ms: u32,
+    #[serde(default = "default_hands_free_max_utterance_secs")]
+    pub hands_free_max_utterance_secs: u32,
+    #[serde(default)]
+    pub wake_word_enabled: bool,
+    #[serde(default)]
+    pub wake_word_model: Option<String>,
+    #[serde(default = "default_wake_word_threshold")]
+    pub wake_word_threshold: f32,
+    #[serde(default)]
+    pub dsp_high_pass: bool,
+    #[serde(default)]
+    pub dsp_denoise: bool,
+    #[serde(default)]
+    pub dsp_agc: bool,
+    /// Audio from just before recording starts to keep. Only takes effect
+    /// while the microphone stays open between recordings.
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    #[serde(default = "default_max_recording_secs")]
+    pub max_recording_secs: u32,
+    #[serde(default = "default_max_recording_mb")]
+    pub max_recording_mb: u32,
+    #[serde(default)]
     pub selected_microphone: Option<String>,
     #[serde(default)]
+    pub microphone_channels: HashMap<String, Vec<u16>>,
+    #[serde(default)]
     pub clamshell_microphone: Option<String>,
     #[serde(default)]
+    pub microphone_priority: Vec<String>,
+    #[serde(default)]
+    pub loopback_source: Option<String>,
+    #[serde(default = "default_loopback_mix_microphone")]
+    pub loopback_mix_microphone: bool,
+    #[serde(default)]
     pub selected_output_device: Option<String>,
     #[serde(default = "default_translate_to_english")]
     pub translate_to_english: bool,
@@ -312,6 +531,16 @@ pub struct AppSettings {
     pub custom_words: Vec<String>,
     #[serde(default)]
     pub model_unload_timeout: ModelUnloadTimeout,
+    #[serde(default)]
+    pub model_pool_budget_mb: u32,
+    #[serde(default)]
+    pub model_manifest_source: Option<String>,
+    #[serde(default)]
+    pub models_directory: Option<String>,
+    #[serde(default = "default_download_connections")]
+    pub download_connections: u8,
+    #[serde(default)]
+    pub download_speed_limit_kbps: Option<u32>,
     #[serde(default = "default_word_correction_threshold")]
     pub word_correction_threshold: f64,
     #[serde(default = "default_history_limit")]
@@ -319,6 +548,12 @@ pub struct AppSettings {
     #[serde(default = "default_recording_retention_period")]
     pub recording_retention_period: RecordingRetentionPeriod,
     #[serde(default)]
+    pub recording_codec: RecordingCodec,
+    #[serde(default)]
+    pub history_encryption_enabled: bool,
+    #[serde(default)]
+    pub history_encryption_key_source: HistoryKeySource,
+    #[serde(default)]
     pub paste_method: PasteMethod,
     #[serde(default)]
     pub clipboard_handling: ClipboardHandling,
@@ -342,6 +577,8 @@ pub struct AppSettings {
     pub post_process_selected_prompt_id: Option<String>,
     #[serde(default)]
     pub mute_while_recording: bool,
+    #[serde(default = "default_duck_volume_percent")]
+    pub duck_volume_percent: u8,
     #[serde(default)]
     pub append_trailing_space: bool,
     #[serde(default = "default_app_language")]
@@ -366,6 +603,34 @@ fn default_always_on_microphone() -> bool {
     false
 }
 
+fn default_hands_free_silence_ms() -> u32 {
+    800
+}
+
+fn default_hands_free_max_utterance_secs() -> u32 {
+    30
+}
+
+fn default_wake_word_threshold() -> f32 {
+    0.5
+}
+
+fn default_pre_roll_ms() -> u32 {
+    300
+}
+
+fn default_max_recording_secs() -> u32 {
+    600
+}
+
+fn default_max_recording_mb() -> u32 {
+    100
+}
+
+fn default_loopback_mix_microphone() -> bool {
+    true
+}
+
 fn default_translate_to_english() -> bool {
     false
 }
@@ -405,6 +670,10 @@ fn default_word_correction_threshold() -> f64 {
     0.18
 }
 
+fn default_duck_volume_percent() -> u8 {
+    20
+}
+
 fn default_paste_delay_ms() -> u64 {
     60
 }
@@ -417,6 +686,10 @@ fn default_history_limit() -> usize {
     5
 }
 
+fn default_download_connections() -> u8 {
+    1
+}
+
 fn default_recording_retention_period() -> RecordingRetentionPeriod {
     RecordingRetentionPeriod::PreserveLimit
 }
@@ -609,6 +882,12 @@ pub fn get_default_settings() -> AppSettings {
             description: "Converts your speech into text.".to_string(),
             default_binding: default_shortcut.to_string(),
             current_binding: default_shortcut.to_string(),
+            model_id: None,
+            language: None,
+            post_process_prompt_id: None,
+            paste_method: None,
+            output: OutputTarget::Paste,
+            trigger: None,
         },
     );
     #[cfg(target_os = "windows")]
@@ -629,6 +908,34 @@ pub fn get_default_settings() -> AppSettings {
                 .to_string(),
             default_binding: default_post_process_shortcut.to_string(),
             current_binding: default_post_process_shortcut.to_string(),
+            model_id: None,
+            language: None,
+            post_process_prompt_id: None,
+            paste_method: None,
+            output: OutputTarget::Paste,
+            trigger: None,
+        },
+    );
+    #[cfg(target_os = "macos")]
+    let default_hands_free_shortcut = "ctrl+option+space";
+    #[cfg(not(target_os = "macos"))]
+    let default_hands_free_shortcut = "ctrl+alt+space";
+
+    bindings.insert(
+        "hands_free".to_string(),
+        ShortcutBinding {
+            id: "hands_free".to_string(),
+            name: "Hands-Free Dictation".to_string(),
+            description: "Listens continuously and transcribes each pause-separated utterance."
+                .to_string(),
+            default_binding: default_hands_free_shortcut.to_string(),
+            current_binding: default_hands_free_shortcut.to_string(),
+            model_id: None,
+            language: None,
+            post_process_prompt_id: None,
+            paste_method: None,
+            output: OutputTarget::Paste,
+            trigger: None,
         },
     );
     bindings.insert(
@@ -639,6 +946,12 @@ pub fn get_default_settings() -> AppSettings {
             description: "Cancels the current recording.".to_string(),
             default_binding: "escape".to_string(),
             current_binding: "escape".to_string(),
+            model_id: None,
+            language: None,
+            post_process_prompt_id: None,
+            paste_method: None,
+            output: OutputTarget::Paste,
+            trigger: None,
         },
     );
 
@@ -653,8 +966,25 @@ pub fn get_default_settings() -> AppSettings {
         update_checks_enabled: default_update_checks_enabled(),
         selected_model: "".to_string(),
         always_on_microphone: false,
+        vad_preset: VadPreset::default(),
+        vad_custom_params: VadParams::default(),
+        hands_free_silence_ms: default_hands_free_silence_ms(),
+        hands_free_max_utterance_secs: default_hands_free_max_utterance_secs(),
+        wake_word_enabled: false,
+        wake_word_model: None,
+        wake_word_threshold: default_wake_word_threshold(),
+        dsp_high_pass: false,
+        dsp_denoise: false,
+        dsp_agc: false,
+        pre_roll_ms: default_pre_roll_ms(),
+        max_recording_secs: default_max_recording_secs(),
+        max_recording_mb: default_max_recording_mb(),
         selected_microphone: None,
+        microphone_channels: HashMap::new(),
         clamshell_microphone: None,
+        microphone_priority: Vec::new(),
+        loopback_source: None,
+        loopback_mix_microphone: default_loopback_mix_microphone(),
         selected_output_device: None,
         translate_to_english: false,
         selected_language: default_selected_language(),
@@ -663,9 +993,17 @@ pub fn get_default_settings() -> AppSettings {
         log_level: default_log_level(),
         custom_words: Vec::new(),
         model_unload_timeout: ModelUnloadTimeout::Never,
+        model_pool_budget_mb: 0,
+        model_manifest_source: None,
+        models_directory: None,
+        download_connections: default_download_connections(),
+        download_speed_limit_kbps: None,
         word_correction_threshold: default_word_correction_threshold(),
         history_limit: default_history_limit(),
         recording_retention_period: default_recording_retention_period(),
+        recording_codec: RecordingCodec::default(),
+        history_encryption_enabled: false,
+        history_encryption_key_source: HistoryKeySource::default(),
         paste_method: PasteMethod::default(),
         clipboard_handling: ClipboardHandling::default(),
         auto_submit: default_auto_submit(),
@@ -678,6 +1016,7 @@ pub fn get_default_settings() -> AppSettings {
         post_process_prompts: default_post_process_prompts(),
         post_process_selected_prompt_id: None,
         mute_while_recording: false,
+        duck_volume_percent: default_duck_volume_percent(),
         append_trailing_space: false,
         app_language: default_app_language(),
         experimental_enabled: false,
@@ -689,6 +1028,107 @@ pub fn get_default_settings() -> AppSettings {
 }
 
 impl AppSettings {
+    /// The wake word can only be heard while the microphone stream is open.
+    /// Without a model there is nothing to listen for.
+    pub fn keeps_microphone_open(&self) -> bool {
+        self.always_on_microphone || (self.wake_word_enabled && self.wake_word_model.is_some())
+    }
+
+    pub fn vad_params(&self) -> VadParams {
+        self.vad_preset.params(Some(self.vad_custom_params))
+    }
+
+    /// Channel selection stored for a device, every channel when none is.
+    pub fn channel_mix(&self, device: &str) -> ChannelMix {
+        self.microphone_channels
+            .get(device)
+            .map(|channels| ChannelMix::from_channels(channels))
+            .unwrap_or_default()
+    }
+
+    /// Zero means no limit for either setting.
+    pub fn recording_limits(&self) -> RecordingLimits {
+        let secs = self.max_recording_secs as u64;
+        let bytes = self.max_recording_mb as usize * 1024 * 1024;
+        RecordingLimits {
+            max_duration: (secs > 0).then_some(std::time::Duration::from_secs(secs)),
+            max_samples: (bytes > 0).then_some(bytes / std::mem::size_of::<f32>()),
+        }
+    }
+
+    /// System audio to capture, mixed with the microphone or instead of it.
+    pub fn loopback_spec(&self) -> Option<LoopbackSpec> {
+        let mode = if self.loopback_mix_microphone {
+            LoopbackMode::Mix
+        } else {
+            LoopbackMode::Replace
+        };
+        self.loopback_source
+            .clone()
+            .map(|source| LoopbackSpec { source, mode })
+    }
+
+    /// These settings with a binding's own model, language, prompt and
+    /// output applied on top, for everything that runs after it stops.
+    pub fn for_binding(&self, binding_id: &str) -> AppSettings {
+        let mut settings = self.clone();
+        let Some(binding) = self.bindings.get(binding_id) else {
+            return settings;
+        };
+        if let Some(model_id) = &binding.model_id {
+            settings.selected_model = model_id.clone();
+        }
+        if let Some(language) = &binding.language {
+            settings.selected_language = language.clone();
+        }
+        if let Some(prompt_id) = &binding.post_process_prompt_id {
+            settings.post_process_selected_prompt_id = Some(prompt_id.clone());
+        }
+        if let Some(paste_method) = binding.paste_method {
+            settings.paste_method = paste_method;
+        }
+        if binding.output == OutputTarget::Clipboard {
+            settings.paste_method = PasteMethod::None;
+            settings.clipboard_handling = ClipboardHandling::CopyToClipboard;
+            settings.auto_submit = false;
+        }
+        settings
+    }
+
+    /// Whether a binding runs its transcript through the LLM: the built-in
+    /// post-process binding, or any binding that names a prompt.
+    pub fn binding_post_processes(&self, binding_id: &str) -> bool {
+        binding_id == "transcribe_with_post_process"
+            || self
+                .bindings
+                .get(binding_id)
+                .is_some_and(|b| b.post_process_prompt_id.is_some())
+    }
+
+    pub fn binding_output(&self, binding_id: &str) -> OutputTarget {
+        self.bindings
+            .get(binding_id)
+            .map(|b| b.output.clone())
+            .unwrap_or_default()
+    }
+
+    /// A binding's trigger mode, or the push-to-talk setting if it has none.
+    pub fn binding_trigger(&self, binding_id: &str) -> TriggerMode {
+        match self.bindings.get(binding_id).and_then(|b| b.trigger) {
+            Some(trigger) => trigger,
+            None if self.push_to_talk => TriggerMode::Hold,
+            None => TriggerMode::Toggle,
+        }
+    }
+
+    pub fn dsp_config(&self) -> DspConfig {
+        DspConfig {
+            high_pass: self.dsp_high_pass,
+            denoise: self.dsp_denoise,
+            agc: self.dsp_agc,
+        }
+    }
+
     pub fn active_post_process_provider(&self) -> Option<&PostProcessProvider> {
         self.post_process_providers
             .iter()
@@ -835,4 +1275,65 @@ mod tests {
         assert!(!settings.auto_submit);
         assert_eq!(settings.auto_submit_key, AutoSubmitKey::Enter);
     }
+
+    #[test]
+    fn default_vad_params_match_previous_hard_coded_values() {
+        let params = get_default_settings().vad_params();
+        assert_eq!(params.threshold, 0.3);
+        assert_eq!(params.prefill_frames, 15);
+        assert_eq!(params.hangover_frames, 15);
+        assert_eq!(params.onset_frames, 2);
+        for preset in [VadPreset::QuietRoom, VadPreset::Office, VadPreset::Outdoors] {
+            assert!(preset.params(None).validate().is_ok());
+        }
+        let mut custom = params;
+        custom.threshold = 1.5;
+        assert!(custom.validate().is_err());
+    }
+
+    #[test]
+    fn recording_limits_treat_zero_as_unlimited() {
+        let mut settings = get_default_settings();
+        settings.max_recording_secs = 60;
+        settings.max_recording_mb = 1;
+        let limits = settings.recording_limits();
+        assert_eq!(limits.max_duration.map(|d| d.as_secs()), Some(60));
+        assert_eq!(limits.max_samples, Some(262_144));
+
+        settings.max_recording_secs = 0;
+        settings.max_recording_mb = 0;
+        let limits = settings.recording_limits();
+        assert!(limits.max_duration.is_none() && limits.max_samples.is_none());
+    }
+
+    #[test]
+    fn binding_overrides_apply_on_top_of_global_settings() {
+        let mut settings = get_default_settings();
+        settings.selected_model = "small".to_string();
+        let mut binding = settings.bindings["transcribe"].clone();
+        binding.id = "custom_dv".to_string();
+        binding.language = Some("dv".to_string());
+        binding.post_process_prompt_id = Some("dv_cleanup".to_string());
+        binding.output = OutputTarget::Clipboard;
+        settings.bindings.insert(binding.id.clone(), binding);
+
+        let effective = settings.for_binding("custom_dv");
+        assert_eq!(effective.selected_model, "small");
+        assert_eq!(effective.selected_language, "dv");
+        assert_eq!(
+            effective.post_process_selected_prompt_id.as_deref(),
+            Some("dv_cleanup")
+        );
+        assert_eq!(effective.paste_method, PasteMethod::None);
+        assert_eq!(
+            effective.clipboard_handling,
+            ClipboardHandling::CopyToClipboard
+        );
+        assert!(settings.binding_post_processes("custom_dv"));
+        assert!(!settings.binding_post_processes("transcribe"));
+
+        // Built-in bindings without overrides follow the global settings
+        let effective = settings.for_binding("transcribe");
+        assert_eq!(effective.paste_method, settings.paste_method);
+    }
 }
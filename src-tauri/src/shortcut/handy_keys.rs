@@ -452,6 +452,18 @@ pub fn init_shortcuts(app: &AppHandle) -> Result<(), String> {
         }
     }
 
+    // Bindings the user created
+    for binding in user_settings.bindings.values() {
+        if settings::is_custom_binding(&binding.id) {
+            if let Err(e) = state.register(binding) {
+                error!(
+                    "Failed to register handy-keys shortcut {} during init: {}",
+                    binding.id, e
+                );
+            }
+        }
+    }
+
     app.manage(state);
     info!("handy-keys shortcuts initialized");
     Ok(())
@@ -12,6 +12,7 @@
 mod handler;
 pub mod handy_keys;
 mod tauri_impl;
+pub mod trigger;
 
 use log::{error, info, warn};
 use serde::Serialize;
@@ -19,12 +20,18 @@ use specta::Type;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_autostart::ManagerExt;
 
+use crate::managers::model::ModelManager;
 use crate::settings::{
     self, get_settings, AutoSubmitKey, ClipboardHandling, KeyboardImplementation, LLMPrompt,
-    OverlayPosition, PasteMethod, ShortcutBinding, SoundTheme, TypingTool,
-    APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
+    OutputTarget, OverlayPosition, PasteMethod, ShortcutBinding, SoundTheme, TriggerMode,
+    TypingTool, APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
 };
+use crate::transcription_coordinator::is_transcribe_binding;
 use crate::tray;
+use std::sync::Arc;
+
+/// Allowed double-tap windows and hold thresholds.
+const TRIGGER_MS_RANGE: std::ops::RangeInclusive<u64> = 100..=2000;
 
 // Note: Commands are accessed via shortcut::handy_keys:: in lib.rs
 
@@ -233,6 +240,180 @@ pub fn resume_binding(app: AppHandle, id: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Choose the model a shortcut transcribes with. `None` follows the model
+/// selected in settings.
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_model(
+    app: AppHandle,
+    id: String,
+    model_id: Option<String>,
+) -> Result<ShortcutBinding, String> {
+    if let Some(model_id) = &model_id {
+        let model_manager = app.state::<Arc<ModelManager>>();
+        match model_manager.get_model_info(model_id) {
+            Some(model) if model.is_downloaded => {}
+            Some(_) => return Err(format!("Model '{}' is not downloaded", model_id)),
+            None => return Err(format!("Model '{}' not found", model_id)),
+        }
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let binding = settings
+        .bindings
+        .get_mut(&id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))?;
+    binding.model_id = model_id;
+    let updated = binding.clone();
+    settings::write_settings(&app, settings);
+    Ok(updated)
+}
+
+/// Create a shortcut that transcribes with its own model, language, prompt
+/// and output. It follows the global settings until those are set.
+#[tauri::command]
+#[specta::specta]
+pub fn add_custom_binding(
+    app: AppHandle,
+    name: String,
+    binding: String,
+) -> Result<ShortcutBinding, String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    if binding.trim().is_empty() {
+        return Err("Binding cannot be empty".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    validate_shortcut_for_implementation(&binding, settings.keyboard_implementation)?;
+
+    let id = (1..)
+        .map(|n| format!("{}{}", settings::CUSTOM_BINDING_PREFIX, n))
+        .find(|id| !settings.bindings.contains_key(id))
+        .expect("unbounded id range");
+    let new_binding = ShortcutBinding {
+        id: id.clone(),
+        name: name.trim().to_string(),
+        description: String::new(),
+        default_binding: binding.clone(),
+        current_binding: binding,
+        model_id: None,
+        language: None,
+        post_process_prompt_id: None,
+        paste_method: None,
+        output: OutputTarget::Paste,
+        trigger: None,
+    };
+
+    register_shortcut(&app, new_binding.clone())
+        .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+    settings.bindings.insert(id, new_binding.clone());
+    settings::write_settings(&app, settings);
+    Ok(new_binding)
+}
+
+/// Remove a binding the user created. Built-in bindings cannot be removed.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_custom_binding(app: AppHandle, id: String) -> Result<(), String> {
+    if !settings::is_custom_binding(&id) {
+        return Err(format!(
+            "Binding '{}' is built in and cannot be removed",
+            id
+        ));
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let binding = settings
+        .bindings
+        .remove(&id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))?;
+    if let Err(e) = unregister_shortcut(&app, binding) {
+        error!("remove_custom_binding error for id '{}': {}", id, e);
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Choose what a transcribe shortcut does once it stops. `None` follows the
+/// global setting for the language, prompt and paste method.
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_pipeline(
+    app: AppHandle,
+    id: String,
+    language: Option<String>,
+    post_process_prompt_id: Option<String>,
+    paste_method: Option<PasteMethod>,
+    output: OutputTarget,
+) -> Result<ShortcutBinding, String> {
+    if !is_transcribe_binding(&id) {
+        return Err(format!("Binding '{}' does not transcribe", id));
+    }
+    crate::output::validate(&output)?;
+
+    let mut settings = settings::get_settings(&app);
+    if let Some(prompt_id) = &post_process_prompt_id {
+        if !settings
+            .post_process_prompts
+            .iter()
+            .any(|p| &p.id == prompt_id)
+        {
+            return Err(format!("Prompt with id '{}' not found", prompt_id));
+        }
+    }
+
+    let binding = settings
+        .bindings
+        .get_mut(&id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))?;
+    binding.language = language;
+    binding.post_process_prompt_id = post_process_prompt_id;
+    binding.paste_method = paste_method;
+    binding.output = output;
+    let updated = binding.clone();
+    settings::write_settings(&app, settings);
+    Ok(updated)
+}
+
+/// Choose how a transcribe shortcut starts and stops recording. `None`
+/// follows the push-to-talk setting.
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_trigger(
+    app: AppHandle,
+    id: String,
+    trigger: Option<TriggerMode>,
+) -> Result<ShortcutBinding, String> {
+    if !is_transcribe_binding(&id) {
+        return Err(format!("Binding '{}' does not transcribe", id));
+    }
+    match trigger {
+        Some(TriggerMode::DoubleTapLock { window_ms: ms })
+        | Some(TriggerMode::HoldOrToggle { threshold_ms: ms })
+            if !TRIGGER_MS_RANGE.contains(&ms) =>
+        {
+            return Err(format!(
+                "Timing must be between {} and {} ms",
+                TRIGGER_MS_RANGE.start(),
+                TRIGGER_MS_RANGE.end()
+            ));
+        }
+        _ => {}
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let binding = settings
+        .bindings
+        .get_mut(&id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))?;
+    binding.trigger = trigger;
+    let updated = binding.clone();
+    settings::write_settings(&app, settings);
+    Ok(updated)
+}
+
 // ============================================================================
 // Keyboard Implementation Switching
 // ============================================================================
@@ -385,8 +566,18 @@ fn register_all_shortcuts_for_implementation(
     let mut reset_bindings = Vec::new();
     let default_bindings = settings::get_default_settings().bindings;
     let mut current_settings = settings::get_settings(app);
+    // Bindings the user created fall back to the shortcut they were made with
+    let custom_bindings: Vec<(String, ShortcutBinding)> = current_settings
+        .bindings
+        .iter()
+        .filter(|(id, _)| settings::is_custom_binding(id))
+        .map(|(id, b)| (id.clone(), b.clone()))
+        .collect();
 
-    for (id, default_binding) in &default_bindings {
+    for (id, default_binding) in default_bindings
+        .iter()
+        .chain(custom_bindings.iter().map(|(id, b)| (id, b)))
+    {
         // Skip cancel shortcut as it's dynamically registered
         if id == "cancel" {
             continue;
@@ -413,7 +604,7 @@ fn register_all_shortcuts_for_implementation(
             );
 
             // Reset to default
-            binding.current_binding = default_binding.current_binding.clone();
+            binding.current_binding = default_binding.default_binding.clone();
             current_settings
                 .bindings
                 .insert(id.clone(), binding.clone());
@@ -1018,6 +1209,20 @@ pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+/// Volume other audio is lowered to while recording, in percent of its level.
+/// Zero silences it.
+#[tauri::command]
+#[specta::specta]
+pub fn change_duck_volume_setting(app: AppHandle, percent: u8) -> Result<(), String> {
+    if percent > 100 {
+        return Err("Duck volume must be between 0 and 100%".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.duck_volume_percent = percent;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -35,6 +35,16 @@ pub fn init_shortcuts(app: &AppHandle) {
             error!("Failed to register shortcut {} during init: {}", id, e);
         }
     }
+
+    // Bindings the user created
+    for binding in user_settings.bindings.into_values() {
+        if settings::is_custom_binding(&binding.id) {
+            let id = binding.id.clone();
+            if let Err(e) = register_shortcut(app, binding) {
+                error!("Failed to register shortcut {} during init: {}", id, e);
+            }
+        }
+    }
 }
 
 /// Validate a shortcut string for the Tauri global-shortcut implementation.
@@ -0,0 +1,274 @@
+//! Turns a binding's key presses and releases into recording starts and
+//! stops according to its trigger mode.
+//!
+//! The machine is driven by the transcription coordinator, which tells it
+//! whether the binding is still recording so that recordings ended some
+//! other way (cancel, the length limit, the control socket) do not leave
+//! it out of step.
+
+use crate::settings::TriggerMode;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerAction {
+    Start,
+    Stop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Idle,
+    /// The key that started the recording is still down
+    Held {
+        since: Instant,
+    },
+    /// Recording continues until the next press
+    Latched,
+    /// Released after a short tap; another press before `until` locks
+    /// recording on
+    AwaitingSecondTap {
+        until: Instant,
+    },
+    /// The second tap of a double tap is down
+    Locking,
+}
+
+#[derive(Clone, Debug)]
+pub struct Trigger {
+    mode: TriggerMode,
+    phase: Phase,
+}
+
+impl Trigger {
+    pub fn new(mode: TriggerMode) -> Self {
+        Self {
+            mode,
+            phase: Phase::Idle,
+        }
+    }
+
+    pub fn mode(&self) -> TriggerMode {
+        self.mode
+    }
+
+    /// `recording` is whether this binding's recording is still running.
+    pub fn press(&mut self, now: Instant, recording: bool) -> Option<TriggerAction> {
+        if !recording && !matches!(self.phase, Phase::Held { .. } | Phase::Locking) {
+            self.phase = Phase::Idle;
+        }
+        // Started some other way (control socket, signal, wake word,
+        // hands-free): the first press stops it, like a toggle
+        if recording && self.phase == Phase::Idle {
+            self.phase = Phase::Latched;
+        }
+
+        match self.phase {
+            Phase::Idle => {
+                self.phase = match self.mode {
+                    TriggerMode::Toggle => Phase::Latched,
+                    _ => Phase::Held { since: now },
+                };
+                Some(TriggerAction::Start)
+            }
+            // Key repeat
+            Phase::Held { .. } | Phase::Locking => None,
+            Phase::Latched => {
+                self.phase = Phase::Idle;
+                Some(TriggerAction::Stop)
+            }
+            Phase::AwaitingSecondTap { until } if now < until => {
+                self.phase = Phase::Locking;
+                None
+            }
+            Phase::AwaitingSecondTap { .. } => {
+                self.phase = Phase::Idle;
+                Some(TriggerAction::Stop)
+            }
+        }
+    }
+
+    pub fn release(&mut self, now: Instant) -> Option<TriggerAction> {
+        match self.phase {
+            Phase::Held { since } => {
+                let held = now.saturating_duration_since(since);
+                match self.mode {
+                    TriggerMode::Toggle => None,
+                    TriggerMode::HoldOrToggle { threshold_ms }
+                        if held < Duration::from_millis(threshold_ms) =>
+                    {
+                        self.phase = Phase::Latched;
+                        None
+                    }
+                    TriggerMode::DoubleTapLock { window_ms }
+                        if held < Duration::from_millis(window_ms) =>
+                    {
+                        self.phase = Phase::AwaitingSecondTap {
+                            until: now + Duration::from_millis(window_ms),
+                        };
+                        None
+                    }
+                    _ => {
+                        self.phase = Phase::Idle;
+                        Some(TriggerAction::Stop)
+                    }
+                }
+            }
+            Phase::Locking => {
+                self.phase = Phase::Latched;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// When `expire` next needs to be called.
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.phase {
+            Phase::AwaitingSecondTap { until } => Some(until),
+            _ => None,
+        }
+    }
+
+    /// A single short tap of a double-tap binding ends once the window for
+    /// the second tap has passed.
+    pub fn expire(&mut self, now: Instant) -> Option<TriggerAction> {
+        match self.phase {
+            Phase::AwaitingSecondTap { until } if now >= until => {
+                self.phase = Phase::Idle;
+                Some(TriggerAction::Stop)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TriggerAction::{Start, Stop};
+
+    enum Step {
+        Press(u64),
+        Release(u64),
+        Expire(u64),
+    }
+    use Step::{Expire, Press, Release};
+
+    /// Play a timeline of key events (in ms) and collect what each produced.
+    /// The binding counts as recording between a Start and the next Stop.
+    fn run(mode: TriggerMode, steps: &[Step]) -> Vec<Option<TriggerAction>> {
+        let origin = Instant::now();
+        let at = |ms: u64| origin + Duration::from_millis(ms);
+        let mut trigger = Trigger::new(mode);
+        let mut recording = false;
+        steps
+            .iter()
+            .map(|step| {
+                let action = match *step {
+                    Press(ms) => trigger.press(at(ms), recording),
+                    Release(ms) => trigger.release(at(ms)),
+                    Expire(ms) => trigger.expire(at(ms)),
+                };
+                match action {
+                    Some(Start) => recording = true,
+                    Some(Stop) => recording = false,
+                    None => {}
+                }
+                action
+            })
+            .collect()
+    }
+
+    #[test]
+    fn toggle_and_hold() {
+        let steps = [Press(0), Release(80), Press(2000), Release(2080)];
+        assert_eq!(
+            run(TriggerMode::Toggle, &steps),
+            [Some(Start), None, Some(Stop), None]
+        );
+        assert_eq!(
+            run(TriggerMode::Hold, &[Press(0), Press(40), Release(1500)]),
+            [Some(Start), None, Some(Stop)]
+        );
+    }
+
+    #[test]
+    fn hold_or_toggle_depends_on_how_long_the_key_is_held() {
+        let mode = TriggerMode::HoldOrToggle { threshold_ms: 300 };
+        assert_eq!(
+            run(mode, &[Press(0), Release(1200)]),
+            [Some(Start), Some(Stop)]
+        );
+        assert_eq!(
+            run(mode, &[Press(0), Release(150), Press(3000), Release(3100)]),
+            [Some(Start), None, Some(Stop), None]
+        );
+    }
+
+    #[test]
+    fn double_tap_locks_and_single_tap_expires() {
+        let mode = TriggerMode::DoubleTapLock { window_ms: 250 };
+        // Held past the window: push-to-talk
+        assert_eq!(
+            run(mode, &[Press(0), Release(900)]),
+            [Some(Start), Some(Stop)]
+        );
+        // Double tap: stays on through the deadline until the next press
+        assert_eq!(
+            run(
+                mode,
+                &[
+                    Press(0),
+                    Release(100),
+                    Press(200),
+                    Release(280),
+                    Expire(600),
+                    Press(5000)
+                ]
+            ),
+            [Some(Start), None, None, None, None, Some(Stop)]
+        );
+        // A lone tap ends when the window for the second one closes
+        assert_eq!(
+            run(mode, &[Press(0), Release(100), Expire(200), Expire(350)]),
+            [Some(Start), None, None, Some(Stop)]
+        );
+    }
+
+    #[test]
+    fn resyncs_after_the_recording_ends_elsewhere() {
+        let origin = Instant::now();
+        let mut trigger = Trigger::new(TriggerMode::Toggle);
+        assert_eq!(trigger.press(origin, false), Some(Start));
+        // Stopped by the recording limit; the next press starts again
+        let later = origin + Duration::from_secs(60);
+        assert_eq!(trigger.press(later, false), Some(Start));
+        assert_eq!(trigger.press(later, true), Some(Stop));
+    }
+
+    #[test]
+    fn first_press_stops_a_recording_started_elsewhere() {
+        let origin = Instant::now();
+        for mode in [
+            TriggerMode::Toggle,
+            TriggerMode::Hold,
+            TriggerMode::HoldOrToggle { threshold_ms: 300 },
+            TriggerMode::DoubleTapLock { window_ms: 250 },
+        ] {
+            let mut trigger = Trigger::new(mode);
+            assert_eq!(trigger.press(origin, true), Some(Stop), "{:?}", mode);
+            assert_eq!(
+                trigger.release(origin + Duration::from_millis(80)),
+                None,
+                "{:?}",
+                mode
+            );
+            assert_eq!(
+                trigger.press(origin + Duration::from_secs(2), false),
+                Some(Start),
+                "{:?}",
+                mode
+            );
+        }
+    }
+}
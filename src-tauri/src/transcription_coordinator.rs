@@ -1,14 +1,34 @@
-use crate::actions::ACTION_MAP;
-use crate::managers::audio::AudioRecordingManager;
-use log::{debug, error, warn};
-use std::sync::mpsc::{self, Sender};
-use std::sync::Arc;
+use crate::actions;
+use crate::audio_feedback;
+use crate::audio_toolkit::vad::UtteranceEnd;
+use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, is_custom_binding, TriggerMode};
+use crate::shortcut::trigger::{Trigger, TriggerAction};
+use crate::utils;
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 const DEBOUNCE: Duration = Duration::from_millis(30);
 
+/// Binding used for hands-free segments.
+const HANDS_FREE_BINDING: &str = "transcribe";
+/// Stands in for the hotkey string of segments started by hands-free mode.
+const HANDS_FREE_SOURCE: &str = "hands-free";
+const WAKE_WORD_SOURCE: &str = "wake-word";
+/// Stands in for the hotkey string when a recording is stopped at its limit.
+const LIMIT_SOURCE: &str = "recording-limit";
+/// Stands in for the hotkey string when a lone tap of a double-tap binding
+/// runs out of time for the second tap.
+const TAP_TIMEOUT_SOURCE: &str = "tap-timeout";
+
 /// Commands processed sequentially by the coordinator thread.
 enum Command {
     Input {
@@ -17,10 +37,31 @@ enum Command {
         is_pressed: bool,
         push_to_talk: bool,
     },
+    /// A shortcut key event, interpreted by the binding's trigger mode
+    Key {
+        binding_id: String,
+        hotkey_string: String,
+        is_pressed: bool,
+        mode: TriggerMode,
+    },
+    /// A trigger deadline passed
+    TriggerTimeout,
     Cancel {
         recording_was_active: bool,
     },
     ProcessingFinished,
+    SetHandsFree {
+        armed: bool,
+    },
+    UtteranceEnded(UtteranceEnd),
+    WakeWord,
+    LimitReached,
+    /// Audio read from a file, transcribed with a binding's settings
+    TranscribeFile {
+        binding_id: String,
+        samples: Vec<f32>,
+        reply: Sender<Result<String, String>>,
+    },
 }
 
 /// Pipeline lifecycle, owned exclusively by the coordinator thread.
@@ -30,27 +71,78 @@ enum Stage {
     Processing,
 }
 
+/// What the pipeline is doing, as seen from outside the coordinator thread.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PipelineState {
+    /// `idle`, `recording` or `processing`
+    pub stage: &'static str,
+    /// Binding being recorded
+    pub binding: Option<String>,
+    pub hands_free: bool,
+}
+
+impl PipelineState {
+    fn of(stage: &Stage, hands_free: bool) -> Self {
+        let (stage, binding) = match stage {
+            Stage::Idle => ("idle", None),
+            Stage::Recording(id) => ("recording", Some(id.clone())),
+            Stage::Processing => ("processing", None),
+        };
+        Self {
+            stage,
+            binding,
+            hands_free,
+        }
+    }
+}
+
 /// Serialises all transcription lifecycle events through a single thread
 /// to eliminate race conditions between keyboard shortcuts, signals, and
 /// the async transcribe-paste pipeline.
 pub struct TranscriptionCoordinator {
     tx: Sender<Command>,
+    hands_free: Arc<AtomicBool>,
+    state: Arc<Mutex<PipelineState>>,
 }
 
 pub fn is_transcribe_binding(id: &str) -> bool {
-    id == "transcribe" || id == "transcribe_with_post_process"
+    id == "transcribe" || id == "transcribe_with_post_process" || is_custom_binding(id)
 }
 
 impl TranscriptionCoordinator {
     pub fn new(app: AppHandle) -> Self {
         let (tx, rx) = mpsc::channel();
+        let hands_free = Arc::new(AtomicBool::new(false));
+        let armed = hands_free.clone();
+        let state = Arc::new(Mutex::new(PipelineState::of(&Stage::Idle, false)));
+        let published = state.clone();
 
         thread::spawn(move || {
             let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                 let mut stage = Stage::Idle;
                 let mut last_press: Option<Instant> = None;
+                // The current recording ends on silence rather than a key press
+                let mut wake_segment = false;
+                let mut triggers: HashMap<String, Trigger> = HashMap::new();
+
+                loop {
+                    let deadline = triggers.values().filter_map(Trigger::deadline).min();
+                    let cmd = match deadline {
+                        Some(deadline) => {
+                            match rx
+                                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                            {
+                                Ok(cmd) => cmd,
+                                Err(RecvTimeoutError::Timeout) => Command::TriggerTimeout,
+                                Err(RecvTimeoutError::Disconnected) => break,
+                            }
+                        }
+                        None => match rx.recv() {
+                            Ok(cmd) => cmd,
+                            Err(_) => break,
+                        },
+                    };
 
-                while let Ok(cmd) = rx.recv() {
                     match cmd {
                         Command::Input {
                             binding_id,
@@ -60,13 +152,9 @@ impl TranscriptionCoordinator {
                         } => {
                             // Debounce rapid-fire press events (key repeat / double-tap).
                             // Releases always pass through for push-to-talk.
-                            if is_pressed {
-                                let now = Instant::now();
-                                if last_press.map_or(false, |t| now.duration_since(t) < DEBOUNCE) {
-                                    debug!("Debounced press for '{binding_id}'");
-                                    continue;
-                                }
-                                last_press = Some(now);
+                            if is_pressed && debounced(&mut last_press) {
+                                debug!("Debounced press for '{binding_id}'");
+                                continue;
                             }
 
                             if push_to_talk {
@@ -91,6 +179,45 @@ impl TranscriptionCoordinator {
                                 }
                             }
                         }
+                        Command::Key {
+                            binding_id,
+                            hotkey_string,
+                            is_pressed,
+                            mode,
+                        } => {
+                            if is_pressed && debounced(&mut last_press) {
+                                debug!("Debounced press for '{binding_id}'");
+                                continue;
+                            }
+                            let trigger = triggers
+                                .entry(binding_id.clone())
+                                .or_insert_with(|| Trigger::new(mode));
+                            if trigger.mode() != mode {
+                                *trigger = Trigger::new(mode);
+                            }
+                            let now = Instant::now();
+                            let action = if is_pressed {
+                                let recording =
+                                    matches!(&stage, Stage::Recording(id) if id == &binding_id);
+                                trigger.press(now, recording)
+                            } else {
+                                trigger.release(now)
+                            };
+                            apply_trigger(&app, &mut stage, action, &binding_id, &hotkey_string);
+                        }
+                        Command::TriggerTimeout => {
+                            let now = Instant::now();
+                            for (binding_id, trigger) in triggers.iter_mut() {
+                                let action = trigger.expire(now);
+                                apply_trigger(
+                                    &app,
+                                    &mut stage,
+                                    action,
+                                    binding_id,
+                                    TAP_TIMEOUT_SOURCE,
+                                );
+                            }
+                        }
                         Command::Cancel {
                             recording_was_active,
                         } => {
@@ -100,10 +227,99 @@ impl TranscriptionCoordinator {
                             {
                                 stage = Stage::Idle;
                             }
+                            wake_segment = false;
+                            // Cancelling also ends hands-free listening
+                            if armed.swap(false, Ordering::SeqCst) {
+                                hands_free_changed(&app, false);
+                            }
                         }
                         Command::ProcessingFinished => {
                             stage = Stage::Idle;
+                            wake_segment = false;
+                            if armed.load(Ordering::SeqCst) {
+                                start(&app, &mut stage, HANDS_FREE_BINDING, HANDS_FREE_SOURCE);
+                            }
+                        }
+                        Command::SetHandsFree { armed: arm } => {
+                            if armed.swap(arm, Ordering::SeqCst) == arm {
+                                continue;
+                            }
+                            hands_free_changed(&app, arm);
+                            match &stage {
+                                Stage::Idle if arm => {
+                                    start(&app, &mut stage, HANDS_FREE_BINDING, HANDS_FREE_SOURCE);
+                                }
+                                Stage::Recording(id) if !arm => {
+                                    let heard_speech = app
+                                        .try_state::<Arc<AudioRecordingManager>>()
+                                        .map_or(false, |a| a.utterance_started());
+                                    if heard_speech {
+                                        let id = id.clone();
+                                        stop(&app, &mut stage, &id, HANDS_FREE_SOURCE);
+                                    } else {
+                                        utils::cancel_current_operation(&app);
+                                        stage = Stage::Idle;
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        Command::UtteranceEnded(end) => {
+                            if !armed.load(Ordering::SeqCst) && !wake_segment {
+                                continue;
+                            }
+                            if let Stage::Recording(id) = &stage {
+                                debug!("Utterance ended ({end:?})");
+                                let id = id.clone();
+                                let source = if wake_segment {
+                                    WAKE_WORD_SOURCE
+                                } else {
+                                    HANDS_FREE_SOURCE
+                                };
+                                stop(&app, &mut stage, &id, source);
+                            }
+                        }
+                        Command::LimitReached => {
+                            // A stuck push-to-talk key lands here; its release
+                            // is ignored once processing has started
+                            if let Stage::Recording(id) = &stage {
+                                warn!("Recording limit reached, stopping '{id}'");
+                                audio_feedback::play_warning_sound(&app);
+                                let id = id.clone();
+                                stop(&app, &mut stage, &id, LIMIT_SOURCE);
+                            }
+                        }
+                        Command::WakeWord => {
+                            if matches!(stage, Stage::Idle) {
+                                info!("Wake word detected");
+                                start(&app, &mut stage, HANDS_FREE_BINDING, WAKE_WORD_SOURCE);
+                                wake_segment = matches!(stage, Stage::Recording(_));
+                            }
                         }
+                        Command::TranscribeFile {
+                            binding_id,
+                            samples,
+                            reply,
+                        } => {
+                            if !matches!(stage, Stage::Idle) {
+                                let _ =
+                                    reply
+                                        .send(Err("A recording or transcription is in progress"
+                                            .to_string()));
+                                continue;
+                            }
+                            stage = Stage::Processing;
+                            transcribe_file(&app, binding_id, samples, reply);
+                        }
+                    }
+
+                    // Branches that `continue` leave the stage as it was
+                    let current = PipelineState::of(&stage, armed.load(Ordering::SeqCst));
+                    let mut last = published.lock().unwrap();
+                    if *last != current {
+                        *last = current.clone();
+                        drop(last);
+                        let _ = app.emit("pipeline-state-changed", current);
                     }
                 }
                 debug!("Transcription coordinator exited");
@@ -113,7 +329,15 @@ impl TranscriptionCoordinator {
             }
         });
 
-        Self { tx }
+        Self {
+            tx,
+            hands_free,
+            state,
+        }
+    }
+
+    pub fn state(&self) -> PipelineState {
+        self.state.lock().unwrap().clone()
     }
 
     /// Send a keyboard/signal input event for a transcribe binding.
@@ -139,6 +363,29 @@ impl TranscriptionCoordinator {
         }
     }
 
+    /// Send a shortcut key event for a transcribe binding, to be read
+    /// according to the binding's trigger mode.
+    pub fn send_key(
+        &self,
+        binding_id: &str,
+        hotkey_string: &str,
+        is_pressed: bool,
+        mode: TriggerMode,
+    ) {
+        if self
+            .tx
+            .send(Command::Key {
+                binding_id: binding_id.to_string(),
+                hotkey_string: hotkey_string.to_string(),
+                is_pressed,
+                mode,
+            })
+            .is_err()
+        {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
     pub fn notify_cancel(&self, recording_was_active: bool) {
         if self
             .tx
@@ -151,6 +398,58 @@ impl TranscriptionCoordinator {
         }
     }
 
+    pub fn is_hands_free_armed(&self) -> bool {
+        self.hands_free.load(Ordering::SeqCst)
+    }
+
+    /// Arm or disarm hands-free listening.
+    pub fn set_hands_free(&self, armed: bool) {
+        if self.tx.send(Command::SetHandsFree { armed }).is_err() {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
+    pub fn toggle_hands_free(&self) {
+        self.set_hands_free(!self.is_hands_free_armed());
+    }
+
+    /// Called from the audio thread when the wake phrase is heard.
+    pub fn notify_wake_word(&self) {
+        if self.tx.send(Command::WakeWord).is_err() {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
+    /// Called from the audio thread when the VAD decides an utterance is over.
+    pub fn notify_utterance_end(&self, end: UtteranceEnd) {
+        if self.tx.send(Command::UtteranceEnded(end)).is_err() {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
+    /// Called from the audio thread when a recording hits its length or
+    /// memory limit.
+    pub fn notify_limit_reached(&self) {
+        if self.tx.send(Command::LimitReached).is_err() {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
+    /// Transcribe audio read from a file as if `binding_id` had recorded it,
+    /// once nothing else is recording or transcribing. Blocks until done.
+    pub fn transcribe_file(&self, binding_id: &str, samples: Vec<f32>) -> Result<String, String> {
+        let (reply, rx) = mpsc::channel();
+        self.tx
+            .send(Command::TranscribeFile {
+                binding_id: binding_id.to_string(),
+                samples,
+                reply,
+            })
+            .map_err(|_| "Transcription coordinator channel closed".to_string())?;
+        rx.recv()
+            .map_err(|_| "Transcription of the file was abandoned".to_string())?
+    }
+
     pub fn notify_processing_finished(&self) {
         if self.tx.send(Command::ProcessingFinished).is_err() {
             warn!("Transcription coordinator channel closed");
@@ -158,9 +457,68 @@ impl TranscriptionCoordinator {
     }
 }
 
+/// Keep the microphone open while hands-free is armed, the wake word is on
+/// or the user asked for it; otherwise open it only while recording.
+pub fn sync_microphone_mode(app: &AppHandle) -> Result<(), anyhow::Error> {
+    let hands_free = app
+        .try_state::<TranscriptionCoordinator>()
+        .map_or(false, |c| c.is_hands_free_armed());
+    let mode = if hands_free || get_settings(app).keeps_microphone_open() {
+        MicrophoneMode::AlwaysOn
+    } else {
+        MicrophoneMode::OnDemand
+    };
+    match app.try_state::<Arc<AudioRecordingManager>>() {
+        Some(rm) => rm.update_mode(mode),
+        None => Ok(()),
+    }
+}
+
+fn hands_free_changed(app: &AppHandle, armed: bool) {
+    info!(
+        "Hands-free dictation {}",
+        if armed { "armed" } else { "disarmed" }
+    );
+    // Listening continuously needs the stream open so speech onsets are not
+    // lost to stream start-up
+    if let Err(e) = sync_microphone_mode(app) {
+        error!("Failed to switch microphone mode for hands-free: {e}");
+    }
+    let _ = app.emit("hands-free-changed", armed);
+}
+
+/// Whether a press came too soon after the previous one to be deliberate.
+fn debounced(last_press: &mut Option<Instant>) -> bool {
+    let now = Instant::now();
+    if last_press.map_or(false, |t| now.duration_since(t) < DEBOUNCE) {
+        return true;
+    }
+    *last_press = Some(now);
+    false
+}
+
+fn apply_trigger(
+    app: &AppHandle,
+    stage: &mut Stage,
+    action: Option<TriggerAction>,
+    binding_id: &str,
+    hotkey_string: &str,
+) {
+    match action {
+        Some(TriggerAction::Start) if matches!(stage, Stage::Idle) => {
+            start(app, stage, binding_id, hotkey_string);
+        }
+        Some(TriggerAction::Stop) if matches!(&*stage, Stage::Recording(id) if id == binding_id) => {
+            stop(app, stage, binding_id, hotkey_string);
+        }
+        Some(action) => debug!("Ignoring {action:?} for '{binding_id}': pipeline busy"),
+        None => {}
+    }
+}
+
 fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &str) {
-    let Some(action) = ACTION_MAP.get(binding_id) else {
-        warn!("No action in ACTION_MAP for '{binding_id}'");
+    let Some(action) = actions::action_for(binding_id) else {
+        warn!("No action for '{binding_id}'");
         return;
     };
     action.start(app, binding_id, hotkey_string);
@@ -175,10 +533,35 @@ fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &s
 }
 
 fn stop(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &str) {
-    let Some(action) = ACTION_MAP.get(binding_id) else {
-        warn!("No action in ACTION_MAP for '{binding_id}'");
+    let Some(action) = actions::action_for(binding_id) else {
+        warn!("No action for '{binding_id}'");
         return;
     };
     action.stop(app, binding_id, hotkey_string);
     *stage = Stage::Processing;
 }
+
+/// Transcribe off the coordinator thread. The pipeline stays in `Processing`
+/// until it is done so a key press cannot start a recording alongside it.
+fn transcribe_file(
+    app: &AppHandle,
+    binding_id: String,
+    samples: Vec<f32>,
+    reply: Sender<Result<String, String>>,
+) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let settings = get_settings(&app).for_binding(&binding_id);
+            let tm = app.state::<Arc<TranscriptionManager>>();
+            tm.load_model(&settings.selected_model)
+                .and_then(|_| tm.transcribe_with(&settings.selected_model, samples, &settings))
+                .map_err(|e| e.to_string())
+        }))
+        .unwrap_or_else(|_| Err("Transcription panicked".to_string()));
+        let _ = reply.send(result);
+        if let Some(coordinator) = app.try_state::<TranscriptionCoordinator>() {
+            coordinator.notify_processing_finished();
+        }
+    });
+}
@@ -233,6 +233,8 @@ mod tests {
             transcription_text: transcription.to_string(),
             post_processed_text: post_processed.map(|text| text.to_string()),
             post_process_prompt: None,
+            output_target: None,
+            output_error: None,
         }
     }
 
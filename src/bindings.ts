@@ -271,6 +271,65 @@ async resumeBinding(id: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Choose the model a shortcut transcribes with. `None` follows the model
+ * selected in settings.
+ */
+async changeBindingModel(id: string, modelId: string | null) : Promise<Result<ShortcutBinding, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_model", { id, modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a shortcut that transcribes with its own model, language, prompt
+ * and output. It follows the global settings until those are set.
+ */
+async addCustomBinding(name: string, binding: string) : Promise<Result<ShortcutBinding, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_custom_binding", { name, binding }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a binding the user created. Built-in bindings cannot be removed.
+ */
+async removeCustomBinding(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_custom_binding", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Choose what a transcribe shortcut does once it stops. `None` follows the
+ * global setting for the language, prompt and paste method.
+ */
+async changeBindingPipeline(id: string, language: string | null, postProcessPromptId: string | null, pasteMethod: PasteMethod | null, output: OutputTarget) : Promise<Result<ShortcutBinding, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_pipeline", { id, language, postProcessPromptId, pasteMethod, output }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Choose how a transcribe shortcut starts and stops recording. `None`
+ * follows the push-to-talk setting.
+ */
+async changeBindingTrigger(id: string, trigger: TriggerMode | null) : Promise<Result<ShortcutBinding, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_trigger", { id, trigger }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeMuteWhileRecordingSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_mute_while_recording_setting", { enabled }) };
@@ -279,6 +338,18 @@ async changeMuteWhileRecordingSetting(enabled: boolean) : Promise<Result<null, s
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Volume other audio is lowered to while recording, in percent of its level.
+ * Zero silences it.
+ */
+async changeDuckVolumeSetting(percent: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_duck_volume_setting", { percent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_append_trailing_space_setting", { enabled }) };
@@ -503,6 +574,110 @@ async cancelDownload(modelId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async verifyModels() : Promise<Result<ModelVerification[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_models") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async repairModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("repair_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setModelManifestSource(source: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_model_manifest_source", { source }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async refreshModelManifest() : Promise<Result<ModelInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("refresh_model_manifest") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async inspectModelImport(path: string) : Promise<Result<ModelImportPreview, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("inspect_model_import", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importModel(path: string, name: string | null, languages: string[] | null) : Promise<Result<ModelInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_model", { path, name, languages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDownloadConnectionsSetting(connections: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_download_connections_setting", { connections }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDownloadSpeedLimitSetting(limitKbps: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_download_speed_limit_setting", { limitKbps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async installModelFromFile(path: string, modelId: string | null) : Promise<Result<ModelInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_model_from_file", { path, modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async benchmarkModels(clipsDir: string) : Promise<Result<ModelInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("benchmark_models", { clipsDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async modelsDiskReport() : Promise<Result<ModelsDiskReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("models_disk_report") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cleanupModelsDir(names: string[] | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cleanup_models_dir", { names }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setModelsDirectory(path: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_models_directory", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setActiveModel(modelId: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_active_model", { modelId }) };
@@ -567,6 +742,17 @@ async getMicrophoneMode() : Promise<Result<boolean, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async changeVadSettings(preset: VadPreset, customParams: VadParams | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_vad_settings", { preset, customParams }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setVadDebug(enabled: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_vad_debug", { enabled });
+},
 async getAvailableMicrophones() : Promise<Result<AudioDevice[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_available_microphones") };
@@ -591,6 +777,51 @@ async getSelectedMicrophone() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getMicrophoneChannelCount(deviceName: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_microphone_channel_count", { deviceName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Channels are numbered from zero; an empty list averages every channel.
+ */
+async setMicrophoneChannels(deviceName: string, channels: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_channels", { deviceName, channels }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Monitor sources that play back what the machine outputs. Only PulseAudio
+ * and PipeWire on Linux provide them; elsewhere the list is empty.
+ */
+async getAvailableLoopbackSources() : Promise<AudioDevice[]> {
+    return await TAURI_INVOKE("get_available_loopback_sources");
+},
+/**
+ * Capture system audio from `source_name`, mixed with the microphone or in
+ * its place. `null` goes back to the microphone alone.
+ */
+async setLoopbackSource(sourceName: string | null, mixWithMicrophone: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_loopback_source", { sourceName, mixWithMicrophone }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether other audio is currently lowered, and why the last attempt failed
+ * if it did.
+ */
+async getDuckingStatus() : Promise<DuckingStatus> {
+    return await TAURI_INVOKE("get_ducking_status");
+},
 async getAvailableOutputDevices() : Promise<Result<AudioDevice[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_available_output_devices") };
@@ -637,6 +868,17 @@ async getClamshellMicrophone() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async setMicrophonePriority(devices: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_priority", { devices }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getActiveMicrophone() : Promise<string | null> {
+    return await TAURI_INVOKE("get_active_microphone");
+},
 async isRecording() : Promise<boolean> {
     return await TAURI_INVOKE("is_recording");
 },
@@ -656,9 +898,72 @@ async stopRecording() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async setHandsFree(armed: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_hands_free", { armed });
+},
+async isHandsFreeArmed() : Promise<boolean> {
+    return await TAURI_INVOKE("is_hands_free_armed");
+},
+async changeHandsFreeSettings(silenceMs: number, maxUtteranceSecs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_hands_free_settings", { silenceMs, maxUtteranceSecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeWakeWordSettings(enabled: boolean, modelPath: string | null, threshold: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_wake_word_settings", { enabled, modelPath, threshold }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDspSettings(highPass: boolean, denoise: boolean, agc: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_dsp_settings", { highPass, denoise, agc }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDspStats() : Promise<Result<DspStageStats[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dsp_stats") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePreRoll(preRollMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_pre_roll", { preRollMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Zero disables a limit. Applies to recordings started afterwards.
+ */
+async changeRecordingLimits(maxSecs: number, maxMb: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_recording_limits", { maxSecs, maxMb }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setModelUnloadTimeout(timeout: ModelUnloadTimeout) : Promise<void> {
     await TAURI_INVOKE("set_model_unload_timeout", { timeout });
 },
+async setModelPoolBudget(budgetMb: number) : Promise<void> {
+    await TAURI_INVOKE("set_model_pool_budget", { budgetMb });
+},
+async getLoadedModels() : Promise<string[]> {
+    return await TAURI_INVOKE("get_loaded_models");
+},
 async getModelLoadStatus() : Promise<Result<ModelLoadStatus, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_model_load_status") };
@@ -723,6 +1028,54 @@ async updateRecordingRetentionPeriod(period: string) : Promise<Result<null, stri
     else return { status: "error", error: e  as any };
 }
 },
+async updateRecordingCodec(codec: RecordingCodec) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_recording_codec", { codec }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUsageStats(range: UsageRange) : Promise<Result<UsageStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_stats", { range }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryEncryptionStatus() : Promise<Result<HistoryEncryptionStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_encryption_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async enableHistoryEncryption(keySource: HistoryKeySource, passphrase: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_history_encryption", { keySource, passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async disableHistoryEncryption() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("disable_history_encryption") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unlockHistory(passphrase: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_history", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Checks if the Mac is a laptop by detecting battery presence
  * 
@@ -749,14 +1102,69 @@ async isLaptop() : Promise<Result<boolean, string>> {
 
 /** user-defined types **/
 
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; auto_submit?: boolean; auto_submit_key?: AutoSubmitKey; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; experimental_enabled?: boolean; keyboard_implementation?: KeyboardImplementation; show_tray_icon?: boolean; paste_delay_ms?: number; typing_tool?: TypingTool }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; vad_preset?: VadPreset; vad_custom_params?: VadParams; hands_free_silence_ms?: number; hands_free_max_utterance_secs?: number; wake_word_enabled?: boolean; wake_word_model?: string | null; wake_word_threshold?: number; dsp_high_pass?: boolean; dsp_denoise?: boolean; dsp_agc?: boolean; pre_roll_ms?: number; max_recording_secs?: number; max_recording_mb?: number; selected_microphone?: string | null; microphone_channels?: Partial<{ [key in string]: number[] }>; clamshell_microphone?: string | null; microphone_priority?: string[]; loopback_source?: string | null; loopback_mix_microphone?: boolean; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; model_pool_budget_mb?: number; model_manifest_source?: string | null; models_directory?: string | null; download_connections?: number; download_speed_limit_kbps?: number | null; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; recording_codec?: RecordingCodec; history_encryption_enabled?: boolean; history_encryption_key_source?: HistoryKeySource; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; auto_submit?: boolean; auto_submit_key?: AutoSubmitKey; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; duck_volume_percent?: number; append_trailing_space?: boolean; app_language?: string; experimental_enabled?: boolean; keyboard_implementation?: KeyboardImplementation; show_tray_icon?: boolean; paste_delay_ms?: number; typing_tool?: TypingTool }
 export type AudioDevice = { index: string; name: string; is_default: boolean }
 export type AutoSubmitKey = "enter" | "ctrl_enter" | "cmd_enter"
 export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
 export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
 export type CustomSounds = { start: boolean; stop: boolean }
+export type DailyUsage = { 
+/**
+ * Local date, `YYYY-MM-DD`
+ */
+day: string; transcriptions: number; words: number; audio_minutes: number; post_processed: number }
+export type DiskEntry = { name: string; size_bytes: number; is_directory: boolean; kind: DiskEntryKind; model_id: string | null; 
+/**
+ * Whether cleanup removes this entry
+ */
+reclaimable: boolean }
+export type DiskEntryKind = "model" | 
+/**
+ * Installed model that is neither in the catalogue nor imported
+ */
+"uncatalogued_model" | 
+/**
+ * Download that can still be resumed
+ */
+"partial" | "stale_partial" | 
+/**
+ * Extraction in progress
+ */
+"extracting" | 
+/**
+ * App bookkeeping such as the cached manifest
+ */
+"metadata" | 
+/**
+ * Leftover of an interrupted extraction or temporary file
+ */
+"orphan" | 
+/**
+ * Not created by the app; never removed
+ */
+"unknown"
+export type DspStageStats = { name: string; latency_ms: number; avg_micros: number; max_micros: number }
+/**
+ * Outcome of the last duck or restore, for the settings page and the
+ * `ducking-changed` event.
+ */
+export type DuckingStatus = { active: boolean; backend: string | null; streams: number; error: string | null }
 export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | "SenseVoice"
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
+export type HistoryEncryptionStatus = { enabled: boolean; key_source: HistoryKeySource; 
+/**
+ * False while a passphrase-protected history is waiting to be unlocked.
+ */
+unlocked: boolean }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null; 
+/**
+ * Where the transcript was sent, for bindings that do not paste
+ */
+output_target: string | null; 
+/**
+ * Why sending it there failed
+ */
+output_error: string | null }
+export type HistoryKeySource = "keyring" | "passphrase"
 /**
  * Result of changing keyboard implementation
  */
@@ -768,17 +1176,132 @@ reset_bindings: string[] }
 export type KeyboardImplementation = "tauri" | "handy_keys"
 export type LLMPrompt = { id: string; name: string; prompt: string }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
-export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean }
+export type ModelBenchmark = { word_error_rate: number; char_error_rate: number; 
+/**
+ * Processing time divided by audio duration; below 1.0 is faster than real time
+ */
+real_time_factor: number; clips: number; audio_seconds: number; 
+/**
+ * Unix timestamp (seconds) of the run
+ */
+measured_at: number }
+export type ModelImportPreview = { suggested_name: string; engine_type: EngineType; is_directory: boolean; size_mb: number; 
+/**
+ * Best guess from the engine and file name; empty when unknown
+ */
+supported_languages: string[] }
+export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; mirrors?: string[]; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean; sha256?: string | null; benchmark?: ModelBenchmark | null }
+export type ModelIntegrity = "ok" | "corrupted" | 
+/**
+ * No expected digest is known, so the files were not checked.
+ */
+"unverified" | 
+/**
+ * The files could not be read; `error` says why.
+ */
+"unreadable"
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+export type ModelUsage = { 
+/**
+ * `None` for entries recorded before model tracking existed
+ */
+model_id: string | null; transcriptions: number; audio_minutes: number; 
+/**
+ * Transcription time divided by audio duration; lower is faster
+ */
+average_rtf: number | null }
+export type ModelVerification = { model_id: string; status: ModelIntegrity; expected_sha256: string | null; actual_sha256: string | null; error?: string | null }
+export type ModelsDiskReport = { models_dir: string; total_bytes: number; reclaimable_bytes: number; 
+/**
+ * Largest first
+ */
+entries: DiskEntry[] }
+/**
+ * Where a binding delivers its transcript.
+ */
+export type OutputTarget = 
+/**
+ * Paste into the focused app with the paste method
+ */
+"paste" | 
+/**
+ * Only put the text on the clipboard
+ */
+"clipboard" | 
+/**
+ * Append to a file; `{date}` in the path becomes today's date
+ */
+{ file: { path: string } } | 
+/**
+ * POST `{ "text", "binding" }` as JSON to a URL
+ */
+{ http: { url: string } } | 
+/**
+ * Run a shell command with the text on its stdin
+ */
+{ command: { command: string } } | 
+/**
+ * Send to control socket subscribers as a `transcript` event
+ */
+"ipc"
 export type OverlayPosition = "none" | "top" | "bottom"
 export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v"
 export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null }
+export type RecordingCodec = "wav" | "flac" | "opus"
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
 export type RepoMainUpdateStatus = { is_repo: boolean; current_branch: string | null; target_ref: string | null; ahead: number; behind: number; update_available: boolean; error: string | null }
-export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
+export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string; model_id?: string | null; language?: string | null; post_process_prompt_id?: string | null; paste_method?: PasteMethod | null; output?: OutputTarget; trigger?: TriggerMode | null }
 export type SoundTheme = "marimba" | "pop" | "custom"
+/**
+ * How a binding's key presses and releases start and stop recording.
+ */
+export type TriggerMode = 
+/**
+ * Press to start, press again to stop
+ */
+"toggle" | 
+/**
+ * Record while the key is held
+ */
+"hold" | 
+/**
+ * Record while the key is held; a double tap keeps recording until the
+ * next press
+ */
+{ double_tap_lock: { window_ms: number } } | 
+/**
+ * Held longer than the threshold records while held, a shorter tap
+ * toggles
+ */
+{ hold_or_toggle: { threshold_ms: number } }
 export type TypingTool = "auto" | "wtype" | "kwtype" | "dotool" | "ydotool" | "xdotool"
+export type UsageRange = "week" | "month" | "year" | "all"
+export type UsageStats = { range: UsageRange; transcriptions: number; words: number; audio_minutes: number; post_processed: number; post_process_rate: number; estimated_minutes_saved: number; daily: DailyUsage[]; models: ModelUsage[] }
+/**
+ * Silero threshold and smoothing, counted in 30 ms frames.
+ */
+export type VadParams = { 
+/**
+ * Speech probability above which a frame counts as voice
+ */
+threshold: number; 
+/**
+ * Frames kept from before speech starts
+ */
+prefill_frames: number; 
+/**
+ * Frames kept after speech stops
+ */
+hangover_frames: number; 
+/**
+ * Consecutive voice frames needed to start speech
+ */
+onset_frames: number }
+/**
+ * Voice activity detection tuned for a recording environment.
+ */
+export type VadPreset = "quiet_room" | "office" | "outdoors" | "custom"
 
 /** tauri-specta globals **/
 
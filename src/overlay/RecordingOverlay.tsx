@@ -13,12 +13,22 @@ import { getLanguageDirection } from "@/lib/utils/rtl";
 
 type OverlayState = "recording" | "transcribing" | "processing";
 
+interface RecordingProgress {
+  elapsed_ms: number;
+  speech_ms: number;
+  max_ms: number | null;
+}
+
+// Start counting down this long before the recording limit stops recording
+const COUNTDOWN_MS = 30_000;
+
 const RecordingOverlay: React.FC = () => {
   const { t } = useTranslation();
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
   const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
   const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
+  const [secondsLeft, setSecondsLeft] = useState<number | null>(null);
   const direction = getLanguageDirection(i18n.language);
   const overlayFontFamily = i18n.language.toLowerCase().startsWith("dv")
     ? '"Faruma", "Noto Sans Thaana", -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif'
@@ -32,6 +42,7 @@ const RecordingOverlay: React.FC = () => {
         await syncLanguageFromSettings();
         const overlayState = event.payload as OverlayState;
         setState(overlayState);
+        setSecondsLeft(null);
         setIsVisible(true);
       });
 
@@ -54,11 +65,24 @@ const RecordingOverlay: React.FC = () => {
         setLevels(smoothed.slice(0, 9));
       });
 
+      // Warn before the recording limit ends the recording
+      const unlistenProgress = await listen<RecordingProgress>(
+        "recording-progress",
+        (event) => {
+          const { elapsed_ms, max_ms } = event.payload;
+          const left = max_ms === null ? Infinity : max_ms - elapsed_ms;
+          setSecondsLeft(
+            left <= COUNTDOWN_MS ? Math.max(0, Math.ceil(left / 1000)) : null,
+          );
+        },
+      );
+
       // Cleanup function
       return () => {
         unlistenShow();
         unlistenHide();
         unlistenLevel();
+        unlistenProgress();
       };
     };
 
@@ -78,7 +102,12 @@ const RecordingOverlay: React.FC = () => {
       <div className="overlay-left">{getIcon()}</div>
 
       <div className="overlay-middle">
-        {state === "recording" && (
+        {state === "recording" && secondsLeft !== null && (
+          <div className="transcribing-text">
+            {t("overlay.stopsIn", { seconds: secondsLeft })}
+          </div>
+        )}
+        {state === "recording" && secondsLeft === null && (
           <div className="bars-container">
             {levels.map((v, i) => (
               <div